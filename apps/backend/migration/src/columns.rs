@@ -33,14 +33,125 @@ pub enum User {
     Table,
     Id,
     Name,
+    MfaEnabled,
+    Role,
 }
 
 #[derive(DeriveIden)]
 pub enum IdentityLink {
     #[sea_orm(iden = "identity_links")]
     Table,
+    // 適用済みのマイグレーションの識別子は変更しない
+    #[allow(dead_code)]
     Id,
     Provider,
     Sub,
     UserId,
 }
+
+#[derive(DeriveIden)]
+pub enum LocalCredential {
+    #[sea_orm(iden = "local_credentials")]
+    Table,
+    Email,
+    PasswordHash,
+    TokenVersion,
+    PasswordResetCodeHash,
+    PasswordResetExpiresAt,
+    SignInCodeSession,
+    SignInCodeHash,
+    SignInCodeExpiresAt,
+}
+
+#[derive(DeriveIden)]
+pub enum SignUpCompensation {
+    #[sea_orm(iden = "sign_up_compensations")]
+    Table,
+    Provider,
+    Username,
+    Sub,
+    Attempts,
+    LastError,
+    ResolvedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum Role {
+    #[sea_orm(iden = "roles")]
+    Table,
+    Id,
+    Name,
+    Description,
+}
+
+#[derive(DeriveIden)]
+pub enum Permission {
+    #[sea_orm(iden = "permissions")]
+    Table,
+    Id,
+    Name,
+    Description,
+}
+
+#[derive(DeriveIden)]
+pub enum RolePermission {
+    #[sea_orm(iden = "role_permissions")]
+    Table,
+    RoleId,
+    PermissionId,
+}
+
+#[derive(DeriveIden)]
+pub enum UserRoleAssignment {
+    #[sea_orm(iden = "user_role_assignments")]
+    Table,
+    UserId,
+    RoleId,
+}
+
+#[derive(DeriveIden)]
+pub enum Session {
+    #[sea_orm(iden = "sessions")]
+    Table,
+    UserId,
+    OriginJti,
+    Device,
+    IpAddress,
+    UserAgent,
+    LastUsedAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum RevokedToken {
+    #[sea_orm(iden = "revoked_tokens")]
+    Table,
+    Jti,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ApiToken {
+    #[sea_orm(iden = "api_tokens")]
+    Table,
+    UserId,
+    Name,
+    TokenPrefix,
+    TokenHash,
+    Scopes,
+    ExpiresAt,
+    LastUsedAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum SignInCodeRequest {
+    #[sea_orm(iden = "sign_in_code_requests")]
+    Table,
+    Email,
+    ProviderSession,
+    Attempts,
+    ExpiresAt,
+    ConsumedAt,
+    CreatedAt,
+}
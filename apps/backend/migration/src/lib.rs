@@ -3,6 +3,20 @@ pub use sea_orm_migration::prelude::*;
 mod columns;
 mod m20250505_051849_create_users;
 mod m20250506_100520_create_identity_links;
+mod m20250601_090000_create_local_credentials;
+mod m20250602_090000_add_password_reset_to_local_credentials;
+mod m20250603_090000_add_mfa_enabled_to_users;
+mod m20250604_090000_create_sign_up_compensations;
+mod m20250605_090000_add_role_to_users;
+mod m20250606_090000_create_roles;
+mod m20250607_090000_create_permissions;
+mod m20250608_090000_create_role_permissions;
+mod m20250609_090000_create_user_role_assignments;
+mod m20250610_090000_create_sessions;
+mod m20250611_090000_create_revoked_tokens;
+mod m20250612_090000_create_api_tokens;
+mod m20250613_090000_create_sign_in_code_requests;
+mod m20250614_090000_add_sign_in_code_to_local_credentials;
 
 pub struct Migrator;
 
@@ -12,6 +26,20 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20250505_051849_create_users::Migration),
             Box::new(m20250506_100520_create_identity_links::Migration),
+            Box::new(m20250601_090000_create_local_credentials::Migration),
+            Box::new(m20250602_090000_add_password_reset_to_local_credentials::Migration),
+            Box::new(m20250603_090000_add_mfa_enabled_to_users::Migration),
+            Box::new(m20250604_090000_create_sign_up_compensations::Migration),
+            Box::new(m20250605_090000_add_role_to_users::Migration),
+            Box::new(m20250606_090000_create_roles::Migration),
+            Box::new(m20250607_090000_create_permissions::Migration),
+            Box::new(m20250608_090000_create_role_permissions::Migration),
+            Box::new(m20250609_090000_create_user_role_assignments::Migration),
+            Box::new(m20250610_090000_create_sessions::Migration),
+            Box::new(m20250611_090000_create_revoked_tokens::Migration),
+            Box::new(m20250612_090000_create_api_tokens::Migration),
+            Box::new(m20250613_090000_create_sign_in_code_requests::Migration),
+            Box::new(m20250614_090000_add_sign_in_code_to_local_credentials::Migration),
         ]
     }
 }
@@ -0,0 +1,35 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::LocalCredential;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LocalCredential::Table)
+                    .add_column(string_null(LocalCredential::PasswordResetCodeHash))
+                    .add_column(timestamp_with_time_zone_null(
+                        LocalCredential::PasswordResetExpiresAt,
+                    ))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LocalCredential::Table)
+                    .drop_column(LocalCredential::PasswordResetCodeHash)
+                    .drop_column(LocalCredential::PasswordResetExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
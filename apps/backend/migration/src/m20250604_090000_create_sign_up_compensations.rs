@@ -0,0 +1,38 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::SignUpCompensation;
+use crate::columns::{define_created_at, define_id, define_updated_at};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SignUpCompensation::Table)
+                    .if_not_exists()
+                    .col(define_id())
+                    .col(string(SignUpCompensation::Provider))
+                    .col(string(SignUpCompensation::Username))
+                    .col(string(SignUpCompensation::Sub))
+                    .col(integer(SignUpCompensation::Attempts).default(0))
+                    .col(text(SignUpCompensation::LastError))
+                    .col(timestamp_with_time_zone_null(
+                        SignUpCompensation::ResolvedAt,
+                    ))
+                    .col(define_created_at())
+                    .col(define_updated_at())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SignUpCompensation::Table).to_owned())
+            .await
+    }
+}
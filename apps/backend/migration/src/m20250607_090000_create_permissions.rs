@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::Permission;
+use crate::columns::{define_created_at, define_id, define_updated_at};
+
+// アプリケーションが参照する権限の一覧（backend::domain::value_objects::permissionと揃える）
+const PERMISSIONS: [(&str, &str); 5] = [
+    ("users:read", "Read any user"),
+    ("users:create", "Create users"),
+    ("users:update", "Update any user"),
+    ("users:delete", "Delete users"),
+    ("roles:manage", "Manage roles and role assignments"),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Permission::Table)
+                    .if_not_exists()
+                    .col(define_id())
+                    .col(string_uniq(Permission::Name))
+                    .col(text_null(Permission::Description))
+                    .col(define_created_at())
+                    .col(define_updated_at())
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert();
+        insert
+            .into_table(Permission::Table)
+            .columns([Permission::Name, Permission::Description]);
+        for (name, description) in PERMISSIONS {
+            insert.values_panic([name.into(), description.into()]);
+        }
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Permission::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::define_created_at;
+use crate::columns::{Role, User, UserRoleAssignment};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRoleAssignment::Table)
+                    .if_not_exists()
+                    .col(uuid(UserRoleAssignment::UserId))
+                    .col(uuid(UserRoleAssignment::RoleId))
+                    .col(define_created_at())
+                    .primary_key(
+                        Index::create()
+                            .col(UserRoleAssignment::UserId)
+                            .col(UserRoleAssignment::RoleId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_role_assignments-user_id")
+                            .from(UserRoleAssignment::Table, UserRoleAssignment::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-user_role_assignments-role_id")
+                            .from(UserRoleAssignment::Table, UserRoleAssignment::RoleId)
+                            .to(Role::Table, Role::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserRoleAssignment::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::RevokedToken;
+use crate::columns::{define_created_at, define_id, define_updated_at};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RevokedToken::Table)
+                    .if_not_exists()
+                    .col(define_id())
+                    // 失効させたアクセストークンのjti、またはセッション全体を表すorigin_jti
+                    .col(string_uniq(RevokedToken::Jti))
+                    // この時刻を過ぎると元のトークンも期限切れのため、記録を削除してよい
+                    .col(timestamp_with_time_zone(RevokedToken::ExpiresAt))
+                    .col(define_created_at())
+                    .col(define_updated_at())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-revoked_tokens-expires_at")
+                    .table(RevokedToken::Table)
+                    .col(RevokedToken::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RevokedToken::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::{define_created_at, define_id, define_updated_at};
+use crate::columns::{ApiToken, User};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiToken::Table)
+                    .if_not_exists()
+                    .col(define_id())
+                    .col(uuid(ApiToken::UserId))
+                    .col(string(ApiToken::Name))
+                    // 一覧でトークンを見分けるための先頭部分
+                    .col(string(ApiToken::TokenPrefix))
+                    // 平文は保存せず、SHA-256のハッシュで照合する
+                    .col(string_uniq(ApiToken::TokenHash))
+                    // 許可する権限名を空白区切りで保存する
+                    .col(text(ApiToken::Scopes))
+                    .col(timestamp_with_time_zone_null(ApiToken::ExpiresAt))
+                    .col(timestamp_with_time_zone_null(ApiToken::LastUsedAt))
+                    .col(timestamp_with_time_zone_null(ApiToken::RevokedAt))
+                    .col(define_created_at())
+                    .col(define_updated_at())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-api_tokens-user_id")
+                            .from(ApiToken::Table, ApiToken::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-api_tokens-user_id")
+                    .table(ApiToken::Table)
+                    .col(ApiToken::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiToken::Table).to_owned())
+            .await
+    }
+}
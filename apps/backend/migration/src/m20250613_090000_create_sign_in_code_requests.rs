@@ -0,0 +1,48 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::SignInCodeRequest;
+use crate::columns::{define_created_at, define_id, define_updated_at};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SignInCodeRequest::Table)
+                    .if_not_exists()
+                    .col(define_id())
+                    .col(string(SignInCodeRequest::Email))
+                    // 認証プロバイダーのチャレンジのセッション（クライアントには渡さない）
+                    .col(text_null(SignInCodeRequest::ProviderSession))
+                    .col(integer(SignInCodeRequest::Attempts).default(0))
+                    .col(timestamp_with_time_zone(SignInCodeRequest::ExpiresAt))
+                    .col(timestamp_with_time_zone_null(SignInCodeRequest::ConsumedAt))
+                    .col(define_created_at())
+                    .col(define_updated_at())
+                    .to_owned(),
+            )
+            .await?;
+
+        // メールアドレスごとのレート制限で、直近の要求を数える
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-sign_in_code_requests-email-created_at")
+                    .table(SignInCodeRequest::Table)
+                    .col(SignInCodeRequest::Email)
+                    .col(SignInCodeRequest::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SignInCodeRequest::Table).to_owned())
+            .await
+    }
+}
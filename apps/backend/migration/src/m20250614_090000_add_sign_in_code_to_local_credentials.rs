@@ -0,0 +1,38 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::columns::LocalCredential;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LocalCredential::Table)
+                    // クライアントに渡すセッションはランダムなIDだけにし、コードのハッシュはDBに残す
+                    .add_column(string_null(LocalCredential::SignInCodeSession))
+                    .add_column(string_null(LocalCredential::SignInCodeHash))
+                    .add_column(timestamp_with_time_zone_null(
+                        LocalCredential::SignInCodeExpiresAt,
+                    ))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LocalCredential::Table)
+                    .drop_column(LocalCredential::SignInCodeSession)
+                    .drop_column(LocalCredential::SignInCodeHash)
+                    .drop_column(LocalCredential::SignInCodeExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
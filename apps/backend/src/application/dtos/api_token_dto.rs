@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::api_token::ApiToken;
+use crate::presentation::graphql::types::api_token_type::CreateApiTokenInput;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ApiToken> for ApiTokenDTO {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            scopes: token.scopes,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiTokenDto {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<CreateApiTokenInput> for CreateApiTokenDto {
+    fn from(input: CreateApiTokenInput) -> Self {
+        Self {
+            name: input.name,
+            scopes: input.scopes,
+            expires_at: input.expires_at,
+        }
+    }
+}
+
+// 平文のトークンは作成時のレスポンスでだけ返す
+#[derive(Debug, Clone)]
+pub struct CreatedApiTokenDTO {
+    pub api_token: ApiTokenDTO,
+    pub token: String,
+}
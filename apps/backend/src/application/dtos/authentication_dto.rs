@@ -1,4 +1,17 @@
-use crate::presentation::graphql::types::authentication_type::{SignInInput, SignUpInput};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::application::dtos::identity_link_dto::IdentityLinkDto;
+use crate::application::dtos::user_dto::UserDTO;
+use crate::domain::value_objects::authentication::{
+    AuthChallenge, AuthenticationTokens, Claims, CodeDeliveryDetails, SoftwareTokenAssociation,
+};
+use crate::presentation::graphql::types::authentication_type::{
+    ConfirmForgotPasswordInput, ConfirmSignUpInput, ForgotPasswordInput, RequestSignInCodeInput,
+    ResendConfirmationCodeInput, RespondToAuthChallengeInput, SignInInput, SignUpInput,
+    VerifySignInCodeInput,
+};
 
 #[derive(Debug, Clone)]
 pub struct SignUpInputDTO {
@@ -17,6 +30,53 @@ impl From<SignUpInput> for SignUpInputDTO {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct SignUpOutputDTO {
+    pub identity_link: IdentityLinkDto,
+    pub user_confirmed: bool,
+    pub session: Option<String>,
+}
+
+pub struct ConfirmSignUpInputDTO {
+    pub email: String,
+    pub confirmation_code: String,
+}
+
+impl From<ConfirmSignUpInput> for ConfirmSignUpInputDTO {
+    fn from(input: ConfirmSignUpInput) -> Self {
+        Self {
+            email: input.email,
+            confirmation_code: input.confirmation_code,
+        }
+    }
+}
+
+pub struct ResendConfirmationCodeInputDTO {
+    pub email: String,
+}
+
+impl From<ResendConfirmationCodeInput> for ResendConfirmationCodeInputDTO {
+    fn from(input: ResendConfirmationCodeInput) -> Self {
+        Self { email: input.email }
+    }
+}
+
+pub struct CodeDeliveryDetailsDTO {
+    pub destination: Option<String>,
+    pub delivery_medium: Option<String>,
+    pub attribute_name: Option<String>,
+}
+
+impl From<CodeDeliveryDetails> for CodeDeliveryDetailsDTO {
+    fn from(details: CodeDeliveryDetails) -> Self {
+        Self {
+            destination: details.destination,
+            delivery_medium: details.delivery_medium,
+            attribute_name: details.attribute_name,
+        }
+    }
+}
+
 pub struct SignInInputDTO {
     pub email: String,
     pub password: String,
@@ -31,15 +91,211 @@ impl From<SignInInput> for SignInInputDTO {
     }
 }
 
-pub struct SignInOutputDTO {
+pub struct AuthenticationTokensDTO {
     pub id_token: String,
     pub access_token: String,
     pub refresh_token: String,
     pub expires_in: i32,
 }
 
+impl From<AuthenticationTokens> for AuthenticationTokensDTO {
+    fn from(tokens: AuthenticationTokens) -> Self {
+        Self {
+            id_token: tokens.id_token,
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            expires_in: tokens.expires_in,
+        }
+    }
+}
+
+pub struct AuthChallengeDTO {
+    pub name: String,
+    pub session: Option<String>,
+    pub parameters: HashMap<String, String>,
+}
+
+impl From<AuthChallenge> for AuthChallengeDTO {
+    fn from(challenge: AuthChallenge) -> Self {
+        Self {
+            name: challenge.name,
+            session: challenge.session,
+            parameters: challenge.parameters,
+        }
+    }
+}
+
+pub enum SignInOutputDTO {
+    Authenticated(AuthenticationTokensDTO),
+    Challenge(AuthChallengeDTO),
+}
+
+pub struct RespondToAuthChallengeInputDTO {
+    pub email: String,
+    pub challenge_name: String,
+    pub session: Option<String>,
+    pub responses: HashMap<String, String>,
+}
+
+impl From<RespondToAuthChallengeInput> for RespondToAuthChallengeInputDTO {
+    fn from(input: RespondToAuthChallengeInput) -> Self {
+        Self {
+            email: input.email,
+            challenge_name: input.challenge_name,
+            session: input.session,
+            responses: input
+                .responses
+                .into_iter()
+                .map(|response| (response.name, response.value))
+                .collect(),
+        }
+    }
+}
+
+pub struct RequestSignInCodeInputDTO {
+    pub email: String,
+}
+
+impl From<RequestSignInCodeInput> for RequestSignInCodeInputDTO {
+    fn from(input: RequestSignInCodeInput) -> Self {
+        Self { email: input.email }
+    }
+}
+
+// ワンタイムコードを送信した結果
+pub struct SignInCodeChallengeDTO {
+    // verifySignInCodeに渡すセッション
+    pub session: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub code_delivery_details: CodeDeliveryDetailsDTO,
+}
+
+pub struct VerifySignInCodeInputDTO {
+    pub session: Uuid,
+    pub code: String,
+}
+
+impl From<VerifySignInCodeInput> for VerifySignInCodeInputDTO {
+    fn from(input: VerifySignInCodeInput) -> Self {
+        Self {
+            session: input.session,
+            code: input.code,
+        }
+    }
+}
+
+pub struct RefreshTokensInputDTO {
+    pub refresh_token: String,
+}
+
+pub struct ForgotPasswordInputDTO {
+    pub email: String,
+}
+
+impl From<ForgotPasswordInput> for ForgotPasswordInputDTO {
+    fn from(input: ForgotPasswordInput) -> Self {
+        Self { email: input.email }
+    }
+}
+
+pub struct ConfirmForgotPasswordInputDTO {
+    pub email: String,
+    pub confirmation_code: String,
+    pub new_password: String,
+}
+
+impl From<ConfirmForgotPasswordInput> for ConfirmForgotPasswordInputDTO {
+    fn from(input: ConfirmForgotPasswordInput) -> Self {
+        Self {
+            email: input.email,
+            confirmation_code: input.confirmation_code,
+            new_password: input.new_password,
+        }
+    }
+}
+
+pub struct ChangePasswordInputDTO {
+    pub access_token: String,
+    pub old_password: String,
+    pub new_password: String,
+}
+
+pub struct AssociateSoftwareTokenInputDTO {
+    pub access_token: String,
+}
+
+pub struct AssociateSoftwareTokenOutputDTO {
+    pub secret_code: String,
+    pub session: Option<String>,
+}
+
+impl From<SoftwareTokenAssociation> for AssociateSoftwareTokenOutputDTO {
+    fn from(association: SoftwareTokenAssociation) -> Self {
+        Self {
+            secret_code: association.secret_code,
+            session: association.session,
+        }
+    }
+}
+
+pub struct VerifySoftwareTokenInputDTO {
+    pub access_token: String,
+    pub user_code: String,
+    pub friendly_device_name: Option<String>,
+}
+
+pub struct SetMfaPreferenceInputDTO {
+    pub access_token: String,
+    pub user_id: Uuid,
+    pub enabled: bool,
+}
+
+// サインインした端末の情報（セッション一覧に表示する）
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfoDTO {
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// 現在のセッションだけをサインアウトする
 pub struct SignOutInputDTO {
+    pub user_id: Uuid,
+    // アクセストークンを検証したプロバイダー
+    pub provider: String,
+    pub jti: Option<String>,
+    pub origin_jti: Option<String>,
+    // アクセストークンのexp（失効の記録をこの時刻まで残す）
+    pub expires_at: Option<DateTime<Utc>>,
+    pub refresh_token: Option<String>,
+}
+
+// すべての端末のセッションをサインアウトする
+pub struct GlobalSignOutInputDTO {
+    pub user_id: Uuid,
+    // アクセストークンを検証したプロバイダー
+    pub provider: String,
     pub username: String,
+    pub jti: Option<String>,
+    pub origin_jti: Option<String>,
+    // アクセストークンのexp（失効の記録をこの時刻まで残す）
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// Hosted UIからのコールバックで受け取った認可コードと、開始時に生成した値
+pub struct AuthorizationCodeInputDTO {
+    pub code: String,
+    pub code_verifier: String,
+    pub nonce: String,
+}
+
+// アクセストークンで認証されたユーザーと、リクエスト中に使う実効権限
+pub struct AuthenticatedUserDTO {
+    pub user: UserDTO,
+    pub permissions: HashSet<String>,
+    pub claims: Claims,
+    // パーソナルアクセストークンで認証された場合のトークンID
+    pub api_token_id: Option<Uuid>,
 }
 
 // impl From<SignOutInput> for SignOutInputDTO {
@@ -25,3 +25,11 @@ impl From<IdentityLink> for IdentityLinkDto {
         }
     }
 }
+
+// 別のプロバイダーのアカウントを、認証中のユーザーに紐付ける
+pub struct LinkIdentityInputDTO {
+    pub user_id: Uuid,
+    pub provider: String,
+    // 紐付けるプロバイダーが発行したアクセストークン（またはIDトークン）
+    pub token: String,
+}
@@ -2,6 +2,9 @@
 // DTOは、レイヤー間でデータを転送するために使用されます。
 
 // DTOモジュール
+pub mod api_token_dto;
 pub mod authentication_dto;
 pub mod identity_link_dto;
+pub mod role_dto;
+pub mod session_dto;
 pub mod user_dto;
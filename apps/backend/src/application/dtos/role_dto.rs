@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::{permission::Permission, role::Role};
+use crate::presentation::graphql::types::role_type::CreateRoleInput;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Role> for RoleDTO {
+    fn from(role: Role) -> Self {
+        Self {
+            id: role.id,
+            name: role.name,
+            description: role.description,
+            permissions: role.permissions,
+            created_at: role.created_at,
+            updated_at: role.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<Permission> for PermissionDTO {
+    fn from(permission: Permission) -> Self {
+        Self {
+            id: permission.id,
+            name: permission.name,
+            description: permission.description,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateRoleDto {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+impl From<CreateRoleInput> for CreateRoleDto {
+    fn from(input: CreateRoleInput) -> Self {
+        Self {
+            name: input.name,
+            description: input.description,
+            permissions: input.permissions,
+        }
+    }
+}
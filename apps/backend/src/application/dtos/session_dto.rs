@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::session::Session;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDTO {
+    pub id: Uuid,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    // リクエストに使われたアクセストークンのセッション
+    pub current: bool,
+    pub last_used_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionDTO {
+    pub fn from_session(session: Session, current_origin_jti: Option<&str>) -> Self {
+        Self {
+            id: session.id,
+            current: current_origin_jti == Some(session.origin_jti.as_str()),
+            device: session.device,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            last_used_at: session.last_used_at,
+            created_at: session.created_at,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    domain::entities::user::User,
+    domain::{entities::user::User, enums::user_role::UserRole},
     presentation::graphql::types::user_type::{CreateUserInput, UpdateUserInput},
 };
 use chrono::{DateTime, Utc};
@@ -12,6 +12,8 @@ use super::identity_link_dto::IdentityLinkDto;
 pub struct UserDTO {
     pub id: Uuid,
     pub name: String,
+    pub role: UserRole,
+    pub mfa_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub identity_links: Vec<IdentityLinkDto>,
@@ -22,9 +24,15 @@ impl From<User> for UserDTO {
         Self {
             id: user.id,
             name: user.name,
+            role: user.role,
+            mfa_enabled: user.mfa_enabled,
             created_at: user.created_at,
             updated_at: user.updated_at,
-            identity_links: user.identity_links.into_iter().map(IdentityLinkDto::from).collect(),
+            identity_links: user
+                .identity_links
+                .into_iter()
+                .map(IdentityLinkDto::from)
+                .collect(),
         }
     }
 }
@@ -1,23 +1,119 @@
 use thiserror::Error;
 
+use crate::domain::errors::{AuthenticationError, RepositoryError};
+
 // アプリケーションレベルのエラー定義
 #[derive(Debug, Error)]
 pub enum ApplicationError {
+    #[error("認証が必要です: {0}")]
+    Unauthenticated(String),
+
+    #[error("権限がありません: {0}")]
+    Forbidden(String),
+
     #[error("エンティティが見つかりません: {0}")]
     NotFound(String),
-    
-    #[error("権限がありません: {0}")]
-    Unauthorized(String),
-    
+
+    #[error("データが競合しています: {0}")]
+    Conflict(String),
+
     #[error("バリデーションエラー: {0}")]
     ValidationError(String),
-    
+
+    // クライアントが原因を区別できるよう、認証プロバイダーのエラーをそのまま保持する
+    #[error("認証エラー: {0}")]
+    Authentication(AuthenticationError),
+
     #[error("データベースエラー: {0}")]
     DatabaseError(String),
-    
+
     #[error("内部エラー: {0}")]
     InternalError(String),
 }
 
+impl From<RepositoryError> for ApplicationError {
+    fn from(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::NotFound(entity) => {
+                ApplicationError::NotFound(format!("{} not found", entity))
+            }
+            // 制約名などの詳細はクライアントに返さない
+            RepositoryError::Conflict(_) => {
+                ApplicationError::Conflict("Resource already exists".to_string())
+            }
+            RepositoryError::Database(message) => ApplicationError::DatabaseError(message),
+        }
+    }
+}
+
+impl From<AuthenticationError> for ApplicationError {
+    fn from(error: AuthenticationError) -> Self {
+        match error {
+            // トークンが不正な理由はクライアントに返さない
+            AuthenticationError::InvalidToken(_) | AuthenticationError::ExpiredToken(_) => {
+                ApplicationError::Unauthenticated("Invalid or expired token".to_string())
+            }
+            AuthenticationError::InvalidParameter(message)
+            | AuthenticationError::Unsupported(message) => {
+                ApplicationError::ValidationError(message)
+            }
+            AuthenticationError::Provider(message) => ApplicationError::InternalError(message),
+            error => ApplicationError::Authentication(error),
+        }
+    }
+}
+
 // Result型のエイリアス
 pub type ApplicationResult<T> = Result<T, ApplicationError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_conflict_hides_constraint_details() {
+        let error = ApplicationError::from(RepositoryError::Conflict(
+            "duplicate key value violates unique constraint \"idx_identity_links_sub\"".to_string(),
+        ));
+
+        match error {
+            ApplicationError::Conflict(message) => assert_eq!(message, "Resource already exists"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_token_hides_reason() {
+        let error = ApplicationError::from(AuthenticationError::InvalidToken(
+            "ExpiredSignature".to_string(),
+        ));
+
+        match error {
+            ApplicationError::Unauthenticated(message) => {
+                assert_eq!(message, "Invalid or expired token")
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_provider_error_is_internal() {
+        let error = ApplicationError::from(AuthenticationError::Provider(
+            "dispatch failure".to_string(),
+        ));
+
+        assert!(matches!(error, ApplicationError::InternalError(_)));
+    }
+
+    #[test]
+    fn test_classified_authentication_error_is_kept() {
+        let error = ApplicationError::from(AuthenticationError::UserNotConfirmed(
+            "User is not confirmed.".to_string(),
+        ));
+
+        assert!(matches!(
+            error,
+            ApplicationError::Authentication(AuthenticationError::UserNotConfirmed(_))
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod dtos;
 pub mod errors;
+pub mod policies;
 pub mod services;
 pub mod usecases;
 
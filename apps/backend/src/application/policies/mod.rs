@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::{dtos::user_dto::UserDTO, errors::ApplicationResult};
+
+pub mod user_policy;
+
+pub use user_policy::UserPolicy;
+
+// リソースに対する操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Read,
+    Update,
+    Delete,
+}
+
+// リソースの種類ごとに、ユーザーがそのリソースを操作できるかを判定する
+#[async_trait]
+pub trait Policy: Send + Sync + 'static {
+    type Resource: Send + Sync;
+
+    // 判定対象のリソースをリポジトリから読み込む
+    async fn find(&self, id: Uuid) -> ApplicationResult<Option<Self::Resource>>;
+
+    fn authorize(&self, actor: &UserDTO, action: Action, resource: &Self::Resource) -> bool;
+
+    // 一覧のうち、操作が許可されたリソースだけを残す
+    fn filter_authorized(
+        &self,
+        actor: &UserDTO,
+        action: Action,
+        resources: Vec<Self::Resource>,
+    ) -> Vec<Self::Resource> {
+        resources
+            .into_iter()
+            .filter(|resource| self.authorize(actor, action, resource))
+            .collect()
+    }
+}
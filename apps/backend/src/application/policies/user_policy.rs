@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::{dtos::user_dto::UserDTO, errors::ApplicationResult};
+use crate::domain::repositories::user_repository::UserRepository;
+
+use super::{Action, Policy};
+
+// ユーザーは自分自身の参照・更新のみ可能で、管理者はすべてのユーザーを管理できる
+// 削除は管理者のみ（利用者自身の退会は認証プロバイダー側の処理が必要なため）
+pub struct UserPolicy {
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl UserPolicy {
+    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
+        Self { user_repository }
+    }
+}
+
+#[async_trait]
+impl Policy for UserPolicy {
+    type Resource = UserDTO;
+
+    async fn find(&self, id: Uuid) -> ApplicationResult<Option<UserDTO>> {
+        let user = self.user_repository.find_by_id(id).await?;
+        Ok(user.map(UserDTO::from))
+    }
+
+    fn authorize(&self, actor: &UserDTO, action: Action, resource: &UserDTO) -> bool {
+        if actor.role.is_admin() {
+            return true;
+        }
+
+        match action {
+            Action::Read | Action::Update => actor.id == resource.id,
+            Action::Delete => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::{
+            identity_link::IdentityLink,
+            user::{NewUser, User, UserChanges},
+        },
+        enums::user_role::UserRole,
+        errors::RepositoryResult,
+    };
+    use mockall::mock;
+
+    mock! {
+        UserRepo {}
+
+        #[async_trait]
+        impl UserRepository for UserRepo {
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
+        }
+    }
+
+    fn user(role: UserRole) -> UserDTO {
+        UserDTO::from(User {
+            id: Uuid::new_v4(),
+            name: "Test User".to_string(),
+            role,
+            mfa_enabled: false,
+            identity_links: vec![],
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        })
+    }
+
+    fn policy() -> UserPolicy {
+        UserPolicy::new(Arc::new(MockUserRepo::new()))
+    }
+
+    #[test]
+    fn test_user_can_read_and_update_only_themselves() {
+        let policy = policy();
+        let actor = user(UserRole::User);
+        let other = user(UserRole::User);
+
+        assert!(policy.authorize(&actor, Action::Read, &actor));
+        assert!(policy.authorize(&actor, Action::Update, &actor));
+        assert!(!policy.authorize(&actor, Action::Read, &other));
+        assert!(!policy.authorize(&actor, Action::Update, &other));
+        assert!(!policy.authorize(&actor, Action::Delete, &actor));
+    }
+
+    #[test]
+    fn test_admin_can_manage_anyone() {
+        let policy = policy();
+        let admin = user(UserRole::Admin);
+        let other = user(UserRole::User);
+
+        assert!(policy.authorize(&admin, Action::Read, &other));
+        assert!(policy.authorize(&admin, Action::Update, &other));
+        assert!(policy.authorize(&admin, Action::Delete, &other));
+    }
+
+    #[test]
+    fn test_filter_authorized_drops_other_users() {
+        let policy = policy();
+        let actor = user(UserRole::User);
+        let users = vec![user(UserRole::User), actor.clone(), user(UserRole::Admin)];
+
+        let visible = policy.filter_authorized(&actor, Action::Read, users);
+
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].id, actor.id);
+    }
+
+    #[tokio::test]
+    async fn test_find_loads_through_repository() {
+        let id = Uuid::new_v4();
+        let mut repository = MockUserRepo::new();
+        repository
+            .expect_find_by_id()
+            .withf(move |requested| *requested == id)
+            .returning(|_| Ok(None));
+        let policy = UserPolicy::new(Arc::new(repository));
+
+        assert!(policy.find(id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::api_token_dto::{ApiTokenDTO, CreateApiTokenDto, CreatedApiTokenDTO};
+use crate::domain::entities::api_token::NewApiToken;
+use crate::domain::repositories::{
+    api_token_repository::ApiTokenRepository, permission_repository::PermissionRepository,
+};
+use crate::domain::value_objects::api_token_secret::ApiTokenSecret;
+
+// 機械的なクライアント向けのパーソナルアクセストークンを管理する
+pub struct ApiTokenService {
+    api_token_repository: Arc<dyn ApiTokenRepository>,
+    permission_repository: Arc<dyn PermissionRepository>,
+}
+
+impl ApiTokenService {
+    pub fn new(
+        api_token_repository: Arc<dyn ApiTokenRepository>,
+        permission_repository: Arc<dyn PermissionRepository>,
+    ) -> Self {
+        Self {
+            api_token_repository,
+            permission_repository,
+        }
+    }
+
+    // スコープには既存の権限名だけを指定できる（所有者が持つ権限かどうかは呼び出し側で確認する）
+    pub async fn create_token(
+        &self,
+        user_id: Uuid,
+        input: CreateApiTokenDto,
+    ) -> ApplicationResult<CreatedApiTokenDTO> {
+        if input
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= chrono::Utc::now())
+        {
+            return Err(ApplicationError::ValidationError(
+                "expiresAt must be in the future".to_string(),
+            ));
+        }
+        let mut scopes = Vec::new();
+        for scope in input.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(ApplicationError::ValidationError(
+                "At least one scope is required".to_string(),
+            ));
+        }
+        let known: HashSet<String> = self
+            .permission_repository
+            .find_all_permissions()
+            .await?
+            .into_iter()
+            .map(|permission| permission.name)
+            .collect();
+        if let Some(unknown) = scopes.iter().find(|scope| !known.contains(*scope)) {
+            return Err(ApplicationError::NotFound(format!(
+                "Permission '{}' not found",
+                unknown
+            )));
+        }
+
+        let secret = ApiTokenSecret::generate();
+        let token = self
+            .api_token_repository
+            .create(NewApiToken {
+                user_id,
+                name: input.name,
+                token_prefix: secret.display_prefix(),
+                token_hash: secret.hash(),
+                scopes,
+                expires_at: input.expires_at,
+            })
+            .await?;
+
+        Ok(CreatedApiTokenDTO {
+            api_token: ApiTokenDTO::from(token),
+            token: secret.into_string(),
+        })
+    }
+
+    pub async fn get_user_tokens(&self, user_id: Uuid) -> ApplicationResult<Vec<ApiTokenDTO>> {
+        let tokens = self
+            .api_token_repository
+            .find_active_by_user(user_id)
+            .await?;
+        Ok(tokens.into_iter().map(ApiTokenDTO::from).collect())
+    }
+
+    // 他のユーザーのトークンは存在しないものとして扱う
+    pub async fn revoke_token(&self, user_id: Uuid, token_id: Uuid) -> ApplicationResult<bool> {
+        match self.api_token_repository.find_by_id(token_id).await? {
+            Some(token) if token.user_id == user_id => {
+                if !token.is_revoked() {
+                    self.api_token_repository.revoke(token.id).await?;
+                }
+                Ok(true)
+            }
+            _ => Err(ApplicationError::NotFound(
+                "API token not found".to_string(),
+            )),
+        }
+    }
+}
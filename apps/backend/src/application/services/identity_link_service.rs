@@ -0,0 +1,50 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::identity_link_dto::IdentityLinkDto;
+use crate::domain::errors::RepositoryError;
+use crate::domain::repositories::identity_link_repository::IdentityLinkRepository;
+
+// ユーザーに紐付いたプロバイダーのアカウントの一覧と紐付けの解除
+pub struct IdentityLinkService {
+    identity_link_repository: Arc<dyn IdentityLinkRepository>,
+}
+
+impl IdentityLinkService {
+    pub fn new(identity_link_repository: Arc<dyn IdentityLinkRepository>) -> Self {
+        Self {
+            identity_link_repository,
+        }
+    }
+
+    pub async fn get_user_identities(
+        &self,
+        user_id: Uuid,
+    ) -> ApplicationResult<Vec<IdentityLinkDto>> {
+        let identity_links = self.identity_link_repository.find_by_user(user_id).await?;
+        Ok(identity_links
+            .into_iter()
+            .map(IdentityLinkDto::from)
+            .collect())
+    }
+
+    // 他のユーザーの紐付けは存在しないものとして扱う
+    // 最後の1つを解除するとサインインできなくなるため、解除できない
+    pub async fn unlink_identity(&self, user_id: Uuid, id: Uuid) -> ApplicationResult<bool> {
+        match self
+            .identity_link_repository
+            .delete_unless_last(user_id, id)
+            .await
+        {
+            Ok(()) => Ok(true),
+            Err(RepositoryError::NotFound(_)) => Err(ApplicationError::NotFound(
+                "Identity link not found".to_string(),
+            )),
+            Err(RepositoryError::Conflict(_)) => Err(ApplicationError::Conflict(
+                "Cannot unlink the last identity of a user".to_string(),
+            )),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
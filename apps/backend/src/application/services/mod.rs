@@ -2,12 +2,29 @@ use std::sync::Arc;
 
 // サービスモジュールのインポート
 // pub mod authentication_service;
+pub mod api_token_service;
+pub mod identity_link_service;
+pub mod role_service;
+pub mod session_service;
+pub mod token_denylist;
 pub mod user_service;
 
+use crate::application::policies::UserPolicy;
+use crate::domain::repositories::api_token_repository::ApiTokenRepository;
 use crate::domain::repositories::identity_link_repository::IdentityLinkRepository;
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::domain::repositories::revoked_token_repository::RevokedTokenRepository;
+use crate::domain::repositories::session_repository::SessionRepository;
+use crate::domain::repositories::sign_in_code_request_repository::SignInCodeRequestRepository;
+use crate::domain::repositories::sign_up_compensation_repository::SignUpCompensationRepository;
 use crate::domain::repositories::user_repository::UserRepository;
 
 // エクスポート
+pub use api_token_service::ApiTokenService;
+pub use identity_link_service::IdentityLinkService;
+pub use role_service::RoleService;
+pub use session_service::SessionService;
+pub use token_denylist::TokenDenylist;
 pub use user_service::UserService;
 
 // リポジトリを格納する構造体
@@ -15,17 +32,47 @@ pub use user_service::UserService;
 pub struct Repositories {
     pub user_repository: Arc<dyn UserRepository>,
     pub identity_link_repository: Arc<dyn IdentityLinkRepository>,
+    pub sign_up_compensation_repository: Arc<dyn SignUpCompensationRepository>,
+    pub permission_repository: Arc<dyn PermissionRepository>,
+    pub session_repository: Arc<dyn SessionRepository>,
+    pub revoked_token_repository: Arc<dyn RevokedTokenRepository>,
+    pub api_token_repository: Arc<dyn ApiTokenRepository>,
+    pub sign_in_code_request_repository: Arc<dyn SignInCodeRequestRepository>,
 }
 
 // サービスを格納する構造体
 #[derive(Clone)]
 pub struct Services {
     pub user_service: Arc<UserService>,
+    pub user_policy: Arc<UserPolicy>,
+    pub role_service: Arc<RoleService>,
+    pub session_service: Arc<SessionService>,
+    pub api_token_service: Arc<ApiTokenService>,
+    pub identity_link_service: Arc<IdentityLinkService>,
 }
 
 // リポジトリからサービスを初期化する関数
-pub async fn init_services(repositories: Arc<Repositories>) -> Services {
+pub async fn init_services(
+    repositories: Arc<Repositories>,
+    token_denylist: Arc<TokenDenylist>,
+) -> Services {
     Services {
         user_service: Arc::new(UserService::new(repositories.user_repository.clone())),
+        user_policy: Arc::new(UserPolicy::new(repositories.user_repository.clone())),
+        role_service: Arc::new(RoleService::new(
+            repositories.permission_repository.clone(),
+            repositories.user_repository.clone(),
+        )),
+        session_service: Arc::new(SessionService::new(
+            repositories.session_repository.clone(),
+            token_denylist,
+        )),
+        api_token_service: Arc::new(ApiTokenService::new(
+            repositories.api_token_repository.clone(),
+            repositories.permission_repository.clone(),
+        )),
+        identity_link_service: Arc::new(IdentityLinkService::new(
+            repositories.identity_link_repository.clone(),
+        )),
     }
 }
@@ -0,0 +1,91 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::role_dto::{CreateRoleDto, PermissionDTO, RoleDTO};
+use crate::domain::entities::role::NewRole;
+use crate::domain::repositories::{
+    permission_repository::PermissionRepository, user_repository::UserRepository,
+};
+
+// ロールと権限、ユーザーへのロール割り当てを管理する
+pub struct RoleService {
+    permission_repository: Arc<dyn PermissionRepository>,
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl RoleService {
+    pub fn new(
+        permission_repository: Arc<dyn PermissionRepository>,
+        user_repository: Arc<dyn UserRepository>,
+    ) -> Self {
+        Self {
+            permission_repository,
+            user_repository,
+        }
+    }
+
+    pub async fn get_all_roles(&self) -> ApplicationResult<Vec<RoleDTO>> {
+        let roles = self.permission_repository.find_all_roles().await?;
+        Ok(roles.into_iter().map(RoleDTO::from).collect())
+    }
+
+    pub async fn get_all_permissions(&self) -> ApplicationResult<Vec<PermissionDTO>> {
+        let permissions = self.permission_repository.find_all_permissions().await?;
+        Ok(permissions.into_iter().map(PermissionDTO::from).collect())
+    }
+
+    pub async fn get_user_roles(&self, user_id: Uuid) -> ApplicationResult<Vec<RoleDTO>> {
+        let roles = self
+            .permission_repository
+            .find_roles_by_user(user_id)
+            .await?;
+        Ok(roles.into_iter().map(RoleDTO::from).collect())
+    }
+
+    pub async fn create_role(&self, input: CreateRoleDto) -> ApplicationResult<RoleDTO> {
+        let role = self
+            .permission_repository
+            .create_role(NewRole {
+                name: input.name,
+                description: input.description,
+                permissions: input.permissions,
+            })
+            .await?;
+        Ok(RoleDTO::from(role))
+    }
+
+    pub async fn set_role_permissions(
+        &self,
+        role_id: Uuid,
+        permissions: Vec<String>,
+    ) -> ApplicationResult<RoleDTO> {
+        let role = self
+            .permission_repository
+            .set_role_permissions(role_id, permissions)
+            .await?;
+        Ok(RoleDTO::from(role))
+    }
+
+    pub async fn delete_role(&self, role_id: Uuid) -> ApplicationResult<bool> {
+        self.permission_repository.delete_role(role_id).await?;
+        Ok(true)
+    }
+
+    pub async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> ApplicationResult<bool> {
+        if self.user_repository.find_by_id(user_id).await?.is_none() {
+            return Err(ApplicationError::NotFound("User not found".to_string()));
+        }
+        self.permission_repository
+            .assign_role(user_id, role_id)
+            .await?;
+        Ok(true)
+    }
+
+    pub async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> ApplicationResult<bool> {
+        self.permission_repository
+            .unassign_role(user_id, role_id)
+            .await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::session_dto::SessionDTO;
+use crate::application::services::token_denylist::TokenDenylist;
+use crate::domain::repositories::session_repository::SessionRepository;
+
+// サインイン中の端末の一覧と、端末ごとのサインアウト
+pub struct SessionService {
+    session_repository: Arc<dyn SessionRepository>,
+    token_denylist: Arc<TokenDenylist>,
+}
+
+impl SessionService {
+    pub fn new(
+        session_repository: Arc<dyn SessionRepository>,
+        token_denylist: Arc<TokenDenylist>,
+    ) -> Self {
+        Self {
+            session_repository,
+            token_denylist,
+        }
+    }
+
+    pub async fn get_active_sessions(
+        &self,
+        user_id: Uuid,
+        current_origin_jti: Option<&str>,
+    ) -> ApplicationResult<Vec<SessionDTO>> {
+        let sessions = self.session_repository.find_active_by_user(user_id).await?;
+        Ok(sessions
+            .into_iter()
+            .map(|session| SessionDTO::from_session(session, current_origin_jti))
+            .collect())
+    }
+
+    // 他のユーザーのセッションは存在しないものとして扱う
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> ApplicationResult<bool> {
+        match self.session_repository.find_by_id(session_id).await? {
+            Some(session) if session.user_id == user_id => {
+                if !session.is_revoked() {
+                    self.session_repository.revoke(session.id).await?;
+                    // その端末が持っているアクセストークンも即座に使えなくする
+                    self.token_denylist.revoke(&session.origin_jti).await?;
+                }
+                Ok(true)
+            }
+            _ => Err(ApplicationError::NotFound("Session not found".to_string())),
+        }
+    }
+}
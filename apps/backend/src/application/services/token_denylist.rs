@@ -0,0 +1,129 @@
+use crate::application::errors::ApplicationResult;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+use crate::domain::entities::revoked_token::RevokedToken;
+use crate::domain::repositories::revoked_token_repository::RevokedTokenRepository;
+use crate::domain::value_objects::authentication::Claims;
+
+// 署名の検証だけでは期限まで使えてしまうアクセストークンを、サインアウト後は即座に拒否する
+pub struct TokenDenylist {
+    revoked_token_repository: Arc<dyn RevokedTokenRepository>,
+    // 発行済みのアクセストークンが期限切れになるまでの最長の時間
+    access_token_lifetime: Duration,
+}
+
+impl TokenDenylist {
+    pub fn new(
+        revoked_token_repository: Arc<dyn RevokedTokenRepository>,
+        access_token_lifetime: Duration,
+    ) -> Self {
+        Self {
+            revoked_token_repository,
+            access_token_lifetime,
+        }
+    }
+
+    // origin_jtiを渡すと、同じサインインから発行されたすべてのアクセストークンを拒否する
+    // 同じサインインからのリフレッシュは、RefreshTokensがセッションの失効を確認して拒否する
+    pub async fn revoke(&self, jti: &str) -> ApplicationResult<()> {
+        self.revoke_until(jti, None).await
+    }
+
+    // サインアウトしたトークンのセッション全体を拒否する（origin_jtiが無ければトークン単体）
+    // expires_atにはサインアウトしたアクセストークンのexpを渡す
+    pub async fn revoke_access_token(
+        &self,
+        jti: Option<&str>,
+        origin_jti: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> ApplicationResult<()> {
+        match origin_jti.or(jti) {
+            Some(jti) => self.revoke_until(jti, expires_at).await,
+            None => Ok(()),
+        }
+    }
+
+    // 設定された有効期間より長いトークンでも、期限まで拒否し続ける
+    async fn revoke_until(
+        &self,
+        jti: &str,
+        token_expires_at: Option<DateTime<Utc>>,
+    ) -> ApplicationResult<()> {
+        let lifetime_end = Utc::now() + self.access_token_lifetime;
+        self.revoked_token_repository
+            .add(RevokedToken {
+                jti: jti.to_string(),
+                expires_at: token_expires_at
+                    .map_or(lifetime_end, |expires_at| expires_at.max(lifetime_end)),
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_revoked(&self, claims: &Claims) -> ApplicationResult<bool> {
+        let jtis: Vec<String> = [&claims.jti, &claims.origin_jti]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        Ok(self.revoked_token_repository.contains_any(&jtis).await?)
+    }
+
+    // 元のトークンが期限切れになった記録を削除する
+    pub async fn purge_expired(&self) -> ApplicationResult<u64> {
+        Ok(self.revoked_token_repository.purge_expired().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::errors::RepositoryResult;
+    use async_trait::async_trait;
+    use mockall::mock;
+
+    mock! {
+        RevokedTokenRepo {}
+
+        #[async_trait]
+        impl RevokedTokenRepository for RevokedTokenRepo {
+            async fn add(&self, token: RevokedToken) -> RepositoryResult<()>;
+            async fn contains_any(&self, jtis: &[String]) -> RepositoryResult<bool>;
+            async fn purge_expired(&self) -> RepositoryResult<u64>;
+        }
+    }
+
+    // 記録された失効の期限を返す
+    async fn revoked_until(token_expires_at: Option<DateTime<Utc>>) -> DateTime<Utc> {
+        let recorded = Arc::new(std::sync::Mutex::new(None));
+        let mut repository = MockRevokedTokenRepo::new();
+        let recorded_token = recorded.clone();
+        repository.expect_add().times(1).returning(move |token| {
+            assert_eq!(token.jti, "jti");
+            *recorded_token.lock().unwrap() = Some(token.expires_at);
+            Ok(())
+        });
+        let denylist = TokenDenylist::new(Arc::new(repository), Duration::hours(1));
+
+        denylist
+            .revoke_access_token(Some("jti"), None, token_expires_at)
+            .await
+            .unwrap();
+        recorded.lock().unwrap().take().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_revoke_access_token_keeps_entry_until_token_expires() {
+        // 設定された有効期間より長いトークンは、そのexpまで拒否する
+        let expires_at = Utc::now() + Duration::hours(3);
+        assert_eq!(revoked_until(Some(expires_at)).await, expires_at);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_access_token_keeps_entry_for_at_least_the_lifetime() {
+        let minimum = Utc::now() + Duration::hours(1);
+        assert!(revoked_until(Some(Utc::now() + Duration::minutes(5))).await >= minimum);
+        assert!(revoked_until(None).await >= minimum);
+    }
+}
@@ -1,8 +1,12 @@
-use anyhow::Result;
+use crate::application::errors::{ApplicationError, ApplicationResult};
 use std::sync::Arc;
 
 use crate::application::dtos::user_dto::{CreateUserDto, UpdateUserDto, UserDTO};
-use crate::domain::{repositories::user_repository::UserRepository, entities::user::NewUser};
+use crate::domain::{
+    entities::user::{NewUser, UserChanges},
+    enums::user_role::UserRole,
+    repositories::user_repository::UserRepository,
+};
 use uuid::Uuid;
 
 pub struct UserService {
@@ -11,43 +15,104 @@ pub struct UserService {
 
 impl UserService {
     pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        Self {
-            user_repository: user_repository,
-        }
+        Self { user_repository }
     }
 
-    pub async fn create_user(&self, input: CreateUserDto) -> Result<UserDTO> {
+    pub async fn create_user(&self, input: CreateUserDto) -> ApplicationResult<UserDTO> {
         let user = NewUser::from(input);
         let created_user = self.user_repository.create(user).await?;
         Ok(UserDTO::from(created_user))
     }
 
-    pub async fn get_user(&self, id: Uuid) -> Result<Option<UserDTO>> {
-        let user = self.user_repository.find_by_id(id).await?; // id is Uuid, find_by_id now expects Uuid
+    pub async fn get_user(&self, id: Uuid) -> ApplicationResult<Option<UserDTO>> {
+        let user = self.user_repository.find_by_id(id).await?;
         Ok(user.map(UserDTO::from))
     }
 
-    pub async fn get_all_users(&self) -> Result<Vec<UserDTO>> {
+    pub async fn get_all_users(&self) -> ApplicationResult<Vec<UserDTO>> {
         let users = self.user_repository.find_all().await?;
         Ok(users.into_iter().map(UserDTO::from).collect())
     }
 
-    pub async fn update_user(&self, id: Uuid, input: UpdateUserDto) -> Result<Option<UserDTO>> {
-        if let Some(mut user) = self.user_repository.find_by_id(id).await? { // id is Uuid, find_by_id now expects Uuid
-            user.name = input.name;
-            let updated_user = self.user_repository.update(user).await?;
+    pub async fn update_user(
+        &self,
+        id: Uuid,
+        input: UpdateUserDto,
+    ) -> ApplicationResult<Option<UserDTO>> {
+        if self.user_repository.find_by_id(id).await?.is_some() {
+            let changes = UserChanges {
+                name: Some(input.name),
+                ..Default::default()
+            };
+            let updated_user = self.user_repository.update(id, changes).await?;
             Ok(Some(UserDTO::from(updated_user)))
         } else {
             Ok(None)
         }
     }
 
-    pub async fn delete_user(&self, id: Uuid) -> Result<bool> { // id is Uuid
-        if let Some(_) = self.user_repository.find_by_id(id).await? { // id is Uuid, find_by_id now expects Uuid
-            self.user_repository.delete(id).await?; // id is Uuid, delete now expects Uuid
+    pub async fn delete_user(&self, id: Uuid) -> ApplicationResult<bool> {
+        if self.user_repository.find_by_id(id).await?.is_some() {
+            self.user_repository.delete(id).await?;
             Ok(true)
         } else {
             Ok(false)
         }
     }
+
+    // 管理者が他のユーザーのロールを変更する
+    // 管理者が自分自身を降格して管理者不在になるのを防ぐため、自分のロールは変更できない
+    pub async fn set_user_role(
+        &self,
+        actor_id: Uuid,
+        id: Uuid,
+        role: UserRole,
+    ) -> ApplicationResult<UserDTO> {
+        if actor_id == id {
+            return Err(ApplicationError::Forbidden(
+                "Cannot change your own role".to_string(),
+            ));
+        }
+
+        if self.user_repository.find_by_id(id).await?.is_none() {
+            return Err(ApplicationError::NotFound("User not found".to_string()));
+        }
+        let changes = UserChanges {
+            role: Some(role),
+            ..Default::default()
+        };
+        let updated_user = self.user_repository.update(id, changes).await?;
+
+        Ok(UserDTO::from(updated_user))
+    }
+
+    // 同じ人物が別々に作ったアカウントを1つにまとめる
+    // sourceのIdentityLinkや所有データはtargetに移り、sourceは削除される
+    pub async fn merge_users(
+        &self,
+        actor_id: Uuid,
+        source_id: Uuid,
+        target_id: Uuid,
+    ) -> ApplicationResult<UserDTO> {
+        if source_id == target_id {
+            return Err(ApplicationError::ValidationError(
+                "Cannot merge a user into itself".to_string(),
+            ));
+        }
+        // 操作中の管理者自身のアカウントが削除されないよう、自分をsourceにはできない
+        if actor_id == source_id {
+            return Err(ApplicationError::Forbidden(
+                "Cannot merge your own account into another user".to_string(),
+            ));
+        }
+
+        for id in [source_id, target_id] {
+            if self.user_repository.find_by_id(id).await?.is_none() {
+                return Err(ApplicationError::NotFound("User not found".to_string()));
+            }
+        }
+        let merged_user = self.user_repository.merge(source_id, target_id).await?;
+
+        Ok(UserDTO::from(merged_user))
+    }
 }
@@ -0,0 +1,33 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::{
+        AssociateSoftwareTokenInputDTO, AssociateSoftwareTokenOutputDTO,
+    },
+    domain::services::authentication_service::AuthenticationService,
+};
+
+pub struct AssociateSoftwareToken {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl AssociateSoftwareToken {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: AssociateSoftwareTokenInputDTO,
+    ) -> ApplicationResult<AssociateSoftwareTokenOutputDTO> {
+        let association = self
+            .authentication_service
+            .associate_software_token(&input.access_token)
+            .await?;
+
+        Ok(AssociateSoftwareTokenOutputDTO::from(association))
+    }
+}
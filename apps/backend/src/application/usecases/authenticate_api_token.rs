@@ -0,0 +1,296 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use chrono::{Duration, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::{authentication_dto::AuthenticatedUserDTO, user_dto::UserDTO},
+    domain::{
+        enums::user_role::UserRole,
+        errors::AuthenticationError,
+        repositories::{
+            api_token_repository::ApiTokenRepository, permission_repository::PermissionRepository,
+            user_repository::UserRepository,
+        },
+        value_objects::{api_token_secret::ApiTokenSecret, authentication::Claims},
+    },
+};
+
+// 最終使用日時の書き込みは、この間隔に1回までに抑える
+const LAST_USED_UPDATE_INTERVAL: Duration = Duration::minutes(1);
+
+// パーソナルアクセストークンを、所有者のUserContextとして認証する
+pub struct AuthenticateApiToken {
+    api_token_repository: Arc<dyn ApiTokenRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    permission_repository: Arc<dyn PermissionRepository>,
+}
+
+impl AuthenticateApiToken {
+    pub fn new(
+        api_token_repository: Arc<dyn ApiTokenRepository>,
+        user_repository: Arc<dyn UserRepository>,
+        permission_repository: Arc<dyn PermissionRepository>,
+    ) -> Self {
+        Self {
+            api_token_repository,
+            user_repository,
+            permission_repository,
+        }
+    }
+
+    pub async fn execute(&self, token: &str) -> ApplicationResult<AuthenticatedUserDTO> {
+        let secret = ApiTokenSecret::parse(token)
+            .ok_or_else(|| invalid_token("Not a personal access token"))?;
+        let api_token = self
+            .api_token_repository
+            .find_by_hash(&secret.hash())
+            .await?
+            .filter(|api_token| !api_token.is_revoked())
+            .ok_or_else(|| invalid_token("Unknown or revoked personal access token"))?;
+        let now = Utc::now();
+        if api_token.is_expired(now) {
+            return Err(ApplicationError::Authentication(
+                AuthenticationError::ExpiredToken("Personal access token has expired".to_string()),
+            ));
+        }
+        let user = self
+            .user_repository
+            .find_by_id(api_token.user_id)
+            .await?
+            .ok_or_else(|| invalid_token("Owner of the personal access token not found"))?;
+
+        // スコープのうち、所有者が現在も持っている権限だけを使える
+        let owner_permissions: HashSet<String> = self
+            .permission_repository
+            .find_permissions_by_user(user.id)
+            .await?
+            .into_iter()
+            .collect();
+        let permissions = api_token
+            .scopes
+            .iter()
+            .filter(|scope| user.role.is_admin() || owner_permissions.contains(*scope))
+            .cloned()
+            .collect();
+
+        if api_token
+            .last_used_at
+            .is_none_or(|last_used_at| now - last_used_at >= LAST_USED_UPDATE_INTERVAL)
+        {
+            // 記録に失敗しても認証は続ける
+            if let Err(e) = self.api_token_repository.touch(api_token.id).await {
+                tracing::warn!("Failed to record personal access token usage: {}", e);
+            }
+        }
+
+        let mut user_dto = UserDTO::from(user);
+        // トークンは管理者としては振る舞わず、スコープで許可された権限だけを持つ
+        user_dto.role = UserRole::User;
+        // プロバイダーのsubは無いため、所有者のユーザーIDを使う
+        let claims = Claims {
+            sub: user_dto.id.to_string(),
+            scopes: api_token.scopes,
+            expires_at: api_token.expires_at,
+            jti: Some(api_token.id.to_string()),
+            ..Default::default()
+        };
+        Ok(AuthenticatedUserDTO {
+            user: user_dto,
+            permissions,
+            claims,
+            api_token_id: Some(api_token.id),
+        })
+    }
+}
+
+fn invalid_token(message: &str) -> ApplicationError {
+    ApplicationError::Authentication(AuthenticationError::InvalidToken(message.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::{
+            api_token::{ApiToken, NewApiToken},
+            identity_link::IdentityLink,
+            permission::Permission,
+            role::{NewRole, Role},
+            user::{NewUser, User, UserChanges},
+        },
+        errors::RepositoryResult,
+    };
+    use async_trait::async_trait;
+    use mockall::mock;
+    use uuid::Uuid;
+
+    mock! {
+        ApiTokenRepo {}
+
+        #[async_trait]
+        impl ApiTokenRepository for ApiTokenRepo {
+            async fn create(&self, token: NewApiToken) -> RepositoryResult<ApiToken>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<ApiToken>>;
+            async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<ApiToken>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ApiToken>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        UserRepo {}
+
+        #[async_trait]
+        impl UserRepository for UserRepo {
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
+        }
+    }
+
+    mock! {
+        PermissionRepo {}
+
+        #[async_trait]
+        impl PermissionRepository for PermissionRepo {
+            async fn find_permissions_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<String>>;
+            async fn find_all_permissions(&self) -> RepositoryResult<Vec<Permission>>;
+            async fn find_all_roles(&self) -> RepositoryResult<Vec<Role>>;
+            async fn find_roles_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Role>>;
+            async fn create_role(&self, role: NewRole) -> RepositoryResult<Role>;
+            async fn set_role_permissions(&self, role_id: Uuid, permissions: Vec<String>) -> RepositoryResult<Role>;
+            async fn delete_role(&self, role_id: Uuid) -> RepositoryResult<()>;
+            async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+            async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn test_api_token(secret: &ApiTokenSecret, user_id: Uuid, scopes: &[&str]) -> ApiToken {
+        ApiToken {
+            id: Uuid::new_v4(),
+            user_id,
+            name: "nightly batch".to_string(),
+            token_prefix: secret.display_prefix(),
+            token_hash: secret.hash(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_at: None,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    // 所有者のロールと権限を指定して、保存済みのトークンを認証するユースケースを組み立てる
+    fn create_use_case(
+        api_token: ApiToken,
+        role: UserRole,
+        owner_permissions: Vec<String>,
+    ) -> AuthenticateApiToken {
+        let mut mock_api_token_repo = MockApiTokenRepo::new();
+        let token_hash = api_token.token_hash.clone();
+        mock_api_token_repo
+            .expect_find_by_hash()
+            .returning(move |hash| Ok((hash == token_hash).then(|| api_token.clone())));
+        mock_api_token_repo.expect_touch().returning(|_| Ok(()));
+
+        let mut mock_user_repo = MockUserRepo::new();
+        mock_user_repo.expect_find_by_id().returning(move |id| {
+            Ok(Some(User {
+                id,
+                name: "Batch Owner".to_string(),
+                role,
+                mfa_enabled: false,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                identity_links: vec![],
+            }))
+        });
+
+        let mut mock_permission_repo = MockPermissionRepo::new();
+        mock_permission_repo
+            .expect_find_permissions_by_user()
+            .returning(move |_| Ok(owner_permissions.clone()));
+
+        AuthenticateApiToken::new(
+            Arc::new(mock_api_token_repo),
+            Arc::new(mock_user_repo),
+            Arc::new(mock_permission_repo),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_api_token_limits_permissions_to_scopes() {
+        let secret = ApiTokenSecret::generate();
+        let user_id = Uuid::new_v4();
+        let api_token = test_api_token(&secret, user_id, &["users:read", "users:delete"]);
+        let token_id = api_token.id;
+        let use_case = create_use_case(
+            api_token,
+            UserRole::User,
+            vec!["users:read".to_string(), "users:update".to_string()],
+        );
+
+        let authenticated = use_case
+            .execute(&secret.clone().into_string())
+            .await
+            .unwrap();
+
+        assert_eq!(authenticated.user.id, user_id);
+        assert_eq!(authenticated.api_token_id, Some(token_id));
+        // 所有者が持たないusers:deleteと、スコープに無いusers:updateは使えない
+        assert_eq!(
+            authenticated.permissions,
+            HashSet::from(["users:read".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_api_token_never_acts_as_admin() {
+        let secret = ApiTokenSecret::generate();
+        let api_token = test_api_token(&secret, Uuid::new_v4(), &["users:delete"]);
+        let use_case = create_use_case(api_token, UserRole::Admin, vec![]);
+
+        let authenticated = use_case
+            .execute(&secret.clone().into_string())
+            .await
+            .unwrap();
+
+        assert_eq!(authenticated.user.role, UserRole::User);
+        assert!(authenticated.permissions.contains("users:delete"));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_api_token_rejects_revoked_and_expired_tokens() {
+        let secret = ApiTokenSecret::generate();
+        let mut revoked = test_api_token(&secret, Uuid::new_v4(), &["users:read"]);
+        revoked.revoked_at = Some(Utc::now());
+        let result = create_use_case(revoked, UserRole::User, vec![])
+            .execute(&secret.clone().into_string())
+            .await;
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::InvalidToken(_)
+            ))
+        ));
+
+        let mut expired = test_api_token(&secret, Uuid::new_v4(), &["users:read"]);
+        expired.expires_at = Some(Utc::now() - Duration::days(1));
+        let result = create_use_case(expired, UserRole::User, vec![])
+            .execute(&secret.clone().into_string())
+            .await;
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::ExpiredToken(_)
+            ))
+        ));
+    }
+}
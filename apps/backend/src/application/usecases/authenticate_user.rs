@@ -1,57 +1,594 @@
-use anyhow::Result;
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::{
-    application::dtos::{identity_link_dto::IdentityLinkDto, user_dto::UserDTO},
+    application::{
+        dtos::{
+            authentication_dto::AuthenticatedUserDTO, identity_link_dto::IdentityLinkDto,
+            user_dto::UserDTO,
+        },
+        services::token_denylist::TokenDenylist,
+    },
     domain::{
+        enums::user_role::UserRole,
+        errors::{AuthenticationError, RepositoryError},
         repositories::{
-            identity_link_repository::IdentityLinkRepository, user_repository::UserRepository,
+            identity_link_repository::IdentityLinkRepository,
+            permission_repository::PermissionRepository, user_repository::UserRepository,
         },
-        services::authentication_service::AuthenticationService,
+        services::authentication_service_registry::AuthenticationServiceRegistry,
+        value_objects::group_mapping::GroupMapping,
     },
 };
 
 pub struct AuthenticateUser {
-    authentication_service: Arc<dyn AuthenticationService>,
+    authentication_services: Arc<AuthenticationServiceRegistry>,
     identity_link_repository: Arc<dyn IdentityLinkRepository>,
     user_repository: Arc<dyn UserRepository>,
+    permission_repository: Arc<dyn PermissionRepository>,
+    token_denylist: Arc<TokenDenylist>,
+    group_mapping: GroupMapping,
 }
 
 impl AuthenticateUser {
     pub fn new(
-        authentication_service: Arc<dyn AuthenticationService>,
+        authentication_services: Arc<AuthenticationServiceRegistry>,
         identity_link_repository: Arc<dyn IdentityLinkRepository>,
         user_repository: Arc<dyn UserRepository>,
+        permission_repository: Arc<dyn PermissionRepository>,
+        token_denylist: Arc<TokenDenylist>,
+        group_mapping: GroupMapping,
     ) -> Self {
         Self {
-            authentication_service,
+            authentication_services,
             identity_link_repository,
             user_repository,
+            permission_repository,
+            token_denylist,
+            group_mapping,
         }
     }
 
-    pub async fn execute(&self, access_token: &str) -> Result<UserDTO> {
-        let claims = self
-            .authentication_service
+    pub async fn execute(&self, access_token: &str) -> ApplicationResult<AuthenticatedUserDTO> {
+        // トークンの発行者に対応するプロバイダーで検証する。未登録の発行者のトークンは受け付けない
+        let authentication_service = self
+            .authentication_services
+            .verifier_for(access_token)
+            .ok_or_else(|| {
+                ApplicationError::Authentication(AuthenticationError::InvalidToken(
+                    "Unknown token issuer".to_string(),
+                ))
+            })?;
+        let provider = authentication_service.provider_name();
+        // 認証ミドルウェアが拒否の理由を区別できるよう、トークンの検証エラーは分類したまま返す
+        let mut claims = authentication_service
             .verify_token(access_token)
-            .await?;
-        let identity_link = self
+            .await
+            .map_err(|error| match error {
+                AuthenticationError::InvalidToken(_)
+                | AuthenticationError::ExpiredToken(_)
+                | AuthenticationError::UserNotFound(_) => ApplicationError::Authentication(error),
+                error => ApplicationError::from(error),
+            })?;
+        claims.provider = provider;
+        // 署名が有効でも、サインアウト済みのトークンは不正なトークンとして拒否する
+        if self.token_denylist.is_revoked(&claims).await? {
+            return Err(ApplicationError::Authentication(
+                AuthenticationError::InvalidToken("Access token has been revoked".to_string()),
+            ));
+        }
+        // トークンは有効だが、対応するユーザーがこのアプリケーションに存在しない
+        let identity_link = match self
             .identity_link_repository
-            .find_by_sub(&claims.sub)
-            .await?;
+            .find_by_sub(&claims.provider, &claims.sub)
+            .await
+        {
+            Ok(identity_link) => identity_link,
+            Err(RepositoryError::NotFound(_)) => return Err(unknown_user()),
+            Err(error) => return Err(error.into()),
+        };
         let user = self
             .user_repository
             .find_by_id(identity_link.user_id)
-            .await?;
-        match user {
-            Some(user) => {
-                let mut user_dto = UserDTO::from(user.clone());
-                user_dto.identity_links = vec![IdentityLinkDto::from(identity_link)];
-                Ok(user_dto)
-            }
-            None => {
-                return Err(anyhow::anyhow!("User not found"));
-            }
+            .await?
+            .ok_or_else(unknown_user)?;
+
+        // 実効権限はリクエストごとに一度だけ解決し、以降のガードはこの結果を参照する
+        let mut permissions: HashSet<String> = self
+            .permission_repository
+            .find_permissions_by_user(user.id)
+            .await?
+            .into_iter()
+            .collect();
+        // 外部のIdPのグループは、そのプロバイダーで対応付けを有効にした場合だけ使う
+        let groups: &[String] = if self.authentication_services.maps_groups(&claims.provider) {
+            &claims.groups
+        } else {
+            &[]
+        };
+        permissions.extend(self.group_mapping.permissions_for(groups));
+
+        let mut user_dto = UserDTO::from(user);
+        user_dto.identity_links = vec![IdentityLinkDto::from(identity_link)];
+        // グループで管理者になれるが、保存済みの管理者ロールをグループで下げることはしない
+        if self
+            .group_mapping
+            .role_for(groups)
+            .is_some_and(|role| role.is_admin())
+        {
+            user_dto.role = UserRole::Admin;
+        }
+        Ok(AuthenticatedUserDTO {
+            user: user_dto,
+            permissions,
+            claims,
+            api_token_id: None,
+        })
+    }
+}
+
+fn unknown_user() -> ApplicationError {
+    ApplicationError::Authentication(AuthenticationError::UserNotFound(
+        "No user is linked to this token".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::{
+        entities::{
+            identity_link::{IdentityLink, NewIdentityLink},
+            permission::Permission,
+            role::{NewRole, Role},
+            user::{NewUser, User, UserChanges},
+        },
+        enums::user_role::UserRole,
+        errors::RepositoryResult,
+        value_objects::authentication::Claims,
+    };
+    use crate::infrastructure::authentication::unverified_issuer;
+    use crate::infrastructure::memory::InMemoryRevokedTokenRepository;
+    use async_trait::async_trait;
+    use mockall::mock;
+
+    use uuid::Uuid;
+
+    mock! {
+        UserRepo {}
+
+        #[async_trait]
+        impl UserRepository for UserRepo {
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
+        }
+    }
+
+    mock! {
+        IdentityLinkRepo {}
+
+        #[async_trait]
+        impl IdentityLinkRepository for IdentityLinkRepo {
+            async fn create(&self, identity_link: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+            async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+            async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+            async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        PermissionRepo {}
+
+        #[async_trait]
+        impl PermissionRepository for PermissionRepo {
+            async fn find_permissions_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<String>>;
+            async fn find_all_permissions(&self) -> RepositoryResult<Vec<Permission>>;
+            async fn find_all_roles(&self) -> RepositoryResult<Vec<Role>>;
+            async fn find_roles_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Role>>;
+            async fn create_role(&self, role: NewRole) -> RepositoryResult<Role>;
+            async fn set_role_permissions(&self, role_id: Uuid, permissions: Vec<String>) -> RepositoryResult<Role>;
+            async fn delete_role(&self, role_id: Uuid) -> RepositoryResult<()>;
+            async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+            async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
         }
     }
+
+    const COGNITO_ISSUER: &str = "https://cognito.example.com";
+
+    // COGNITO_ISSUERが発行したトークンを、既定のプロバイダーで検証する
+    fn registry(mut auth_service: MockAuthenticationService) -> Arc<AuthenticationServiceRegistry> {
+        auth_service
+            .expect_provider_name()
+            .returning(|| "cognito".to_string());
+        Arc::new(AuthenticationServiceRegistry::new(
+            COGNITO_ISSUER,
+            Arc::new(auth_service),
+            unverified_issuer,
+        ))
+    }
+
+    fn token_denylist() -> Arc<TokenDenylist> {
+        Arc::new(TokenDenylist::new(
+            Arc::new(InMemoryRevokedTokenRepository::new()),
+            chrono::Duration::hours(1),
+        ))
+    }
+
+    // グループとロール割り当てを持つユーザーで認証するユースケースを組み立てる
+    fn create_use_case(
+        user_id: Uuid,
+        groups: Vec<String>,
+        permissions: Vec<String>,
+        group_mapping: GroupMapping,
+        token_denylist: Arc<TokenDenylist>,
+    ) -> AuthenticateUser {
+        let mut mock_auth_service = MockAuthenticationService::new();
+        mock_auth_service.expect_verify_token().returning(move |_| {
+            Ok(Claims {
+                sub: "test-sub-123".to_string(),
+                groups: groups.clone(),
+                scopes: vec!["aws.cognito.signin.user.admin".to_string()],
+                jti: Some("test-jti".to_string()),
+                origin_jti: Some("test-origin-jti".to_string()),
+                ..Default::default()
+            })
+        });
+
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo
+            .expect_find_by_sub()
+            .returning(move |provider, sub| {
+                Ok(IdentityLink {
+                    id: Uuid::new_v4(),
+                    provider: provider.to_string(),
+                    sub: sub.to_string(),
+                    user_id,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+            });
+
+        let mut mock_user_repo = MockUserRepo::new();
+        mock_user_repo.expect_find_by_id().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "Support Staff".to_string(),
+                role: UserRole::User,
+                mfa_enabled: false,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                identity_links: vec![],
+            }))
+        });
+
+        let mut mock_permission_repo = MockPermissionRepo::new();
+        mock_permission_repo
+            .expect_find_permissions_by_user()
+            .withf(move |requested| *requested == user_id)
+            .times(1)
+            .returning(move |_| Ok(permissions.clone()));
+
+        AuthenticateUser::new(
+            registry(mock_auth_service),
+            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_user_repo),
+            Arc::new(mock_permission_repo),
+            token_denylist,
+            group_mapping,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_resolves_permissions_once() {
+        let user_id = Uuid::new_v4();
+        let use_case = create_use_case(
+            user_id,
+            vec![],
+            vec!["users:read".to_string(), "users:update".to_string()],
+            GroupMapping::default(),
+            token_denylist(),
+        );
+
+        let authenticated = use_case
+            .execute(&token_issued_by(COGNITO_ISSUER))
+            .await
+            .unwrap();
+
+        assert_eq!(authenticated.user.id, user_id);
+        assert_eq!(authenticated.user.identity_links.len(), 1);
+        assert!(authenticated.permissions.contains("users:read"));
+        assert!(authenticated.permissions.contains("users:update"));
+        assert!(!authenticated.permissions.contains("users:delete"));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_applies_group_mapping() {
+        let use_case = create_use_case(
+            Uuid::new_v4(),
+            vec!["admins".to_string(), "support".to_string()],
+            vec![],
+            GroupMapping::parse("admins=admin", "support=users:read"),
+            token_denylist(),
+        );
+
+        let authenticated = use_case
+            .execute(&token_issued_by(COGNITO_ISSUER))
+            .await
+            .unwrap();
+
+        assert_eq!(authenticated.user.role, UserRole::Admin);
+        assert!(authenticated.permissions.contains("users:read"));
+        assert_eq!(authenticated.claims.groups, vec!["admins", "support"]);
+        assert_eq!(
+            authenticated.claims.scopes,
+            vec!["aws.cognito.signin.user.admin"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_ignores_unmapped_groups() {
+        let use_case = create_use_case(
+            Uuid::new_v4(),
+            vec!["guests".to_string()],
+            vec![],
+            GroupMapping::parse("admins=admin", "support=users:read"),
+            token_denylist(),
+        );
+
+        let authenticated = use_case
+            .execute(&token_issued_by(COGNITO_ISSUER))
+            .await
+            .unwrap();
+
+        assert_eq!(authenticated.user.role, UserRole::User);
+        assert!(authenticated.permissions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_rejects_revoked_session() {
+        let token_denylist = token_denylist();
+        token_denylist.revoke("test-origin-jti").await.unwrap();
+        let mut mock_auth_service = MockAuthenticationService::new();
+        mock_auth_service.expect_verify_token().returning(|_| {
+            Ok(Claims {
+                sub: "test-sub-123".to_string(),
+                jti: Some("refreshed-jti".to_string()),
+                origin_jti: Some("test-origin-jti".to_string()),
+                ..Default::default()
+            })
+        });
+        // 失効済みのトークンではユーザーを引かない
+        let use_case = AuthenticateUser::new(
+            registry(mock_auth_service),
+            Arc::new(MockIdentityLinkRepo::new()),
+            Arc::new(MockUserRepo::new()),
+            Arc::new(MockPermissionRepo::new()),
+            token_denylist,
+            GroupMapping::default(),
+        );
+
+        let result = use_case.execute(&token_issued_by(COGNITO_ISSUER)).await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::InvalidToken(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_keeps_expired_token_reason() {
+        let mut mock_auth_service = MockAuthenticationService::new();
+        mock_auth_service.expect_verify_token().returning(|_| {
+            Err(AuthenticationError::ExpiredToken(
+                "ExpiredSignature".to_string(),
+            ))
+        });
+        let use_case = AuthenticateUser::new(
+            registry(mock_auth_service),
+            Arc::new(MockIdentityLinkRepo::new()),
+            Arc::new(MockUserRepo::new()),
+            Arc::new(MockPermissionRepo::new()),
+            token_denylist(),
+            GroupMapping::default(),
+        );
+
+        let result = use_case.execute(&token_issued_by(COGNITO_ISSUER)).await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::ExpiredToken(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_reports_unknown_user() {
+        let mut mock_auth_service = MockAuthenticationService::new();
+        mock_auth_service.expect_verify_token().returning(|_| {
+            Ok(Claims {
+                sub: "unlinked-sub".to_string(),
+                ..Default::default()
+            })
+        });
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo
+            .expect_find_by_sub()
+            .returning(|_, _| Err(RepositoryError::NotFound("identity link".to_string())));
+        let use_case = AuthenticateUser::new(
+            registry(mock_auth_service),
+            Arc::new(mock_identity_link_repo),
+            Arc::new(MockUserRepo::new()),
+            Arc::new(MockPermissionRepo::new()),
+            token_denylist(),
+            GroupMapping::default(),
+        );
+
+        let result = use_case.execute(&token_issued_by(COGNITO_ISSUER)).await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::UserNotFound(_)
+            ))
+        ));
+    }
+
+    fn token_issued_by(issuer: &str) -> String {
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &serde_json::json!({ "iss": issuer, "sub": "google-sub-123" }),
+            &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap()
+    }
+
+    // Cognitoを既定とし、https://accounts.google.comが発行したトークンをgoogleで検証するユースケース
+    fn google_use_case(
+        user_id: Uuid,
+        groups: Vec<String>,
+        map_groups: bool,
+        group_mapping: GroupMapping,
+    ) -> AuthenticateUser {
+        let mut registry = AuthenticationServiceRegistry::new(
+            "https://cognito.example.com",
+            Arc::new({
+                let mut cognito = MockAuthenticationService::new();
+                cognito
+                    .expect_provider_name()
+                    .returning(|| "cognito".to_string());
+                cognito.expect_verify_token().never();
+                cognito
+            }),
+            unverified_issuer,
+        );
+        let mut google = MockAuthenticationService::new();
+        google
+            .expect_provider_name()
+            .returning(|| "google".to_string());
+        google.expect_verify_token().returning(move |_| {
+            Ok(Claims {
+                sub: "google-sub-123".to_string(),
+                groups: groups.clone(),
+                ..Default::default()
+            })
+        });
+        registry
+            .register("https://accounts.google.com", Arc::new(google), map_groups)
+            .unwrap();
+        // 同じsubでも、別のプロバイダーのアカウントとは紐付けない
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo
+            .expect_find_by_sub()
+            .withf(|provider, sub| provider == "google" && sub == "google-sub-123")
+            .returning(move |provider, sub| {
+                Ok(IdentityLink {
+                    id: Uuid::new_v4(),
+                    provider: provider.to_string(),
+                    sub: sub.to_string(),
+                    user_id,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+            });
+        let mut mock_user_repo = MockUserRepo::new();
+        mock_user_repo.expect_find_by_id().returning(|id| {
+            Ok(Some(User {
+                id,
+                name: "Workspace User".to_string(),
+                role: UserRole::User,
+                mfa_enabled: false,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                identity_links: vec![],
+            }))
+        });
+        let mut mock_permission_repo = MockPermissionRepo::new();
+        mock_permission_repo
+            .expect_find_permissions_by_user()
+            .returning(|_| Ok(vec![]));
+        AuthenticateUser::new(
+            Arc::new(registry),
+            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_user_repo),
+            Arc::new(mock_permission_repo),
+            token_denylist(),
+            group_mapping,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_user_verifies_with_provider_of_issuer() {
+        let user_id = Uuid::new_v4();
+        let use_case = google_use_case(user_id, vec![], false, GroupMapping::default());
+
+        let authenticated = use_case
+            .execute(&token_issued_by("https://accounts.google.com"))
+            .await
+            .unwrap();
+        assert_eq!(authenticated.user.id, user_id);
+        assert_eq!(authenticated.claims.provider, "google");
+
+        let result = use_case
+            .execute(&token_issued_by("https://attacker.example.com"))
+            .await;
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::InvalidToken(_)
+            ))
+        ));
+
+        // issを読めないトークンも、既定のプロバイダーに渡さずに拒否する
+        let result = use_case.execute("not-a-jwt").await;
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::InvalidToken(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_group_mapping_is_not_applied_to_external_provider() {
+        // 外部のIdPが同じ名前のグループを発行しても、管理者にはしない
+        let group_mapping = GroupMapping::parse("admins=admin", "admins=users:delete");
+        let use_case = google_use_case(
+            Uuid::new_v4(),
+            vec!["admins".to_string()],
+            false,
+            group_mapping.clone(),
+        );
+
+        let authenticated = use_case
+            .execute(&token_issued_by("https://accounts.google.com"))
+            .await
+            .unwrap();
+        assert_eq!(authenticated.user.role, UserRole::User);
+        assert!(!authenticated.permissions.contains("users:delete"));
+
+        // プロバイダーごとに明示した場合は対応付ける
+        let use_case = google_use_case(
+            Uuid::new_v4(),
+            vec!["admins".to_string()],
+            true,
+            group_mapping,
+        );
+
+        let authenticated = use_case
+            .execute(&token_issued_by("https://accounts.google.com"))
+            .await
+            .unwrap();
+        assert_eq!(authenticated.user.role, UserRole::Admin);
+        assert!(authenticated.permissions.contains("users:delete"));
+    }
 }
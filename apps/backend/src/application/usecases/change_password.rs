@@ -0,0 +1,30 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::ChangePasswordInputDTO,
+    domain::services::authentication_service::AuthenticationService,
+};
+
+pub struct ChangePassword {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl ChangePassword {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(&self, input: ChangePasswordInputDTO) -> ApplicationResult<()> {
+        self.authentication_service
+            .change_password(
+                &input.access_token,
+                &input.old_password,
+                &input.new_password,
+            )
+            .await?;
+        Ok(())
+    }
+}
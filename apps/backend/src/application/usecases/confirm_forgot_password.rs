@@ -0,0 +1,45 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::ConfirmForgotPasswordInputDTO,
+    domain::{
+        errors::AuthenticationError, services::authentication_service::AuthenticationService,
+    },
+};
+
+pub struct ConfirmForgotPassword {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl ConfirmForgotPassword {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(&self, input: ConfirmForgotPasswordInputDTO) -> ApplicationResult<()> {
+        // 未登録のメールアドレスと区別できないよう、コードやパスワード、ユーザーの状態に関するエラーは
+        // すべて同じエラーにする（パスワードポリシーはプロバイダーがユーザーの確認後に検証するため、これも含める）
+        self.authentication_service
+            .confirm_forgot_password(&input.email, &input.confirmation_code, &input.new_password)
+            .await
+            .map_err(|e| match e {
+                AuthenticationError::UserNotFound(_)
+                | AuthenticationError::UserNotConfirmed(_)
+                | AuthenticationError::NotAuthorized(_)
+                | AuthenticationError::CodeMismatch { .. }
+                | AuthenticationError::ExpiredCode { .. }
+                | AuthenticationError::InvalidPassword { .. } => {
+                    tracing::warn!("Confirm forgot password failed: {}", e);
+                    ApplicationError::Authentication(AuthenticationError::CodeMismatch {
+                        parameter: "confirmation_code",
+                        message: "Password reset failed. Check the code and new password."
+                            .to_string(),
+                    })
+                }
+                e => ApplicationError::from(e),
+            })
+    }
+}
@@ -0,0 +1,26 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::ConfirmSignUpInputDTO,
+    domain::services::authentication_service::AuthenticationService,
+};
+
+pub struct ConfirmSignUp {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl ConfirmSignUp {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(&self, input: ConfirmSignUpInputDTO) -> ApplicationResult<()> {
+        self.authentication_service
+            .confirm_sign_up(&input.email, &input.confirmation_code)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::ForgotPasswordInputDTO,
+    domain::{
+        errors::AuthenticationError, services::authentication_service::AuthenticationService,
+    },
+};
+
+pub struct ForgotPassword {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl ForgotPassword {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(&self, input: ForgotPasswordInputDTO) -> ApplicationResult<()> {
+        // メールアドレスの登録有無を推測されないよう、失敗はログに残すだけで成功として扱う
+        // レート制限は登録の有無に関係なく発生するため、そのまま返す
+        match self
+            .authentication_service
+            .forgot_password(&input.email)
+            .await
+        {
+            Err(e @ AuthenticationError::TooManyRequests(_)) => Err(e.into()),
+            Err(e) => {
+                tracing::warn!("Forgot password request failed: {}", e);
+                Ok(())
+            }
+            Ok(_) => Ok(()),
+        }
+    }
+}
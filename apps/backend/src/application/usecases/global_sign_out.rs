@@ -0,0 +1,236 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+
+use crate::{
+    application::{
+        dtos::authentication_dto::GlobalSignOutInputDTO, services::token_denylist::TokenDenylist,
+    },
+    domain::{
+        errors::AuthenticationError, repositories::session_repository::SessionRepository,
+        services::authentication_service_registry::AuthenticationServiceRegistry,
+    },
+};
+
+// ユーザーのすべての端末のセッションをサインアウトする
+pub struct GlobalSignOut {
+    authentication_services: Arc<AuthenticationServiceRegistry>,
+    session_repository: Arc<dyn SessionRepository>,
+    token_denylist: Arc<TokenDenylist>,
+}
+
+impl GlobalSignOut {
+    pub fn new(
+        authentication_services: Arc<AuthenticationServiceRegistry>,
+        session_repository: Arc<dyn SessionRepository>,
+        token_denylist: Arc<TokenDenylist>,
+    ) -> Self {
+        Self {
+            authentication_services,
+            session_repository,
+            token_denylist,
+        }
+    }
+
+    pub async fn execute(&self, input: GlobalSignOutInputDTO) -> ApplicationResult<()> {
+        // トークンを発行したプロバイダーのセッションを終了する
+        let authentication_service = self
+            .authentication_services
+            .get(&input.provider)
+            .ok_or_else(|| {
+                ApplicationError::InternalError(format!(
+                    "Unknown authentication provider: {}",
+                    input.provider
+                ))
+            })?;
+        // プロバイダー側のセッションを終了できないプロバイダーでは、このアプリケーションのトークンの拒否だけを行う
+        match authentication_service.sign_out(&input.username).await {
+            Ok(()) | Err(AuthenticationError::Unsupported(_)) => {}
+            Err(error) => return Err(error.into()),
+        }
+        // 各端末が持っている期限内のアクセストークンも受け付けない
+        for session in self
+            .session_repository
+            .find_active_by_user(input.user_id)
+            .await?
+        {
+            self.token_denylist.revoke(&session.origin_jti).await?;
+        }
+        // 記録されていないセッションに備えて、現在のトークンも拒否する
+        self.token_denylist
+            .revoke_access_token(
+                input.jti.as_deref(),
+                input.origin_jti.as_deref(),
+                input.expires_at,
+            )
+            .await?;
+        self.session_repository
+            .revoke_all_by_user(input.user_id)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::{
+        entities::session::{NewSession, Session},
+        errors::{AuthenticationResult, RepositoryResult},
+        value_objects::authentication::Claims,
+    };
+    use crate::infrastructure::authentication::unverified_issuer;
+    use crate::infrastructure::memory::InMemoryRevokedTokenRepository;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use mockall::mock;
+    use uuid::Uuid;
+
+    mock! {
+        SessionRepo {}
+
+        #[async_trait]
+        impl SessionRepository for SessionRepo {
+            async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+            async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn service(
+        name: &'static str,
+        sign_out: fn() -> AuthenticationResult<()>,
+    ) -> MockAuthenticationService {
+        let mut service = MockAuthenticationService::new();
+        service
+            .expect_provider_name()
+            .returning(move || name.to_string());
+        service.expect_sign_out().returning(move |_| sign_out());
+        service
+    }
+
+    fn session_repository(user_id: Uuid, revoked_all: usize) -> MockSessionRepo {
+        let mut session_repository = MockSessionRepo::new();
+        session_repository
+            .expect_find_active_by_user()
+            .returning(move |_| {
+                Ok(vec![Session {
+                    id: Uuid::new_v4(),
+                    user_id,
+                    origin_jti: "session-origin-jti".to_string(),
+                    device: None,
+                    ip_address: None,
+                    user_agent: None,
+                    last_used_at: Utc::now(),
+                    revoked_at: None,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                }])
+            });
+        session_repository
+            .expect_revoke_all_by_user()
+            .withf(move |id| *id == user_id)
+            .times(revoked_all)
+            .returning(|_| Ok(()));
+        session_repository
+    }
+
+    fn global_sign_out(
+        google: MockAuthenticationService,
+        session_repository: MockSessionRepo,
+        token_denylist: Arc<TokenDenylist>,
+    ) -> GlobalSignOut {
+        let mut registry = AuthenticationServiceRegistry::new(
+            "https://cognito.example.com",
+            Arc::new(service("cognito", || Ok(()))),
+            unverified_issuer,
+        );
+        registry
+            .register("https://accounts.google.com", Arc::new(google), false)
+            .unwrap();
+        GlobalSignOut::new(
+            Arc::new(registry),
+            Arc::new(session_repository),
+            token_denylist,
+        )
+    }
+
+    fn token_denylist() -> Arc<TokenDenylist> {
+        Arc::new(TokenDenylist::new(
+            Arc::new(InMemoryRevokedTokenRepository::new()),
+            chrono::Duration::hours(1),
+        ))
+    }
+
+    fn input(user_id: Uuid) -> GlobalSignOutInputDTO {
+        GlobalSignOutInputDTO {
+            user_id,
+            provider: "google".to_string(),
+            username: "google-sub-123".to_string(),
+            jti: Some("current-jti".to_string()),
+            origin_jti: Some("current-origin-jti".to_string()),
+            expires_at: None,
+        }
+    }
+
+    fn claims(origin_jti: &str) -> Claims {
+        Claims {
+            origin_jti: Some(origin_jti.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_global_sign_out_revokes_only_own_tokens_when_provider_cannot_sign_out() {
+        // OIDCプロバイダーのセッションは残るが、このアプリケーションのトークンはすべて拒否する
+        let user_id = Uuid::new_v4();
+        let token_denylist = token_denylist();
+        let global_sign_out = global_sign_out(
+            service("google", || {
+                Err(AuthenticationError::Unsupported(
+                    "Global sign out is not supported by the google provider".to_string(),
+                ))
+            }),
+            session_repository(user_id, 1),
+            token_denylist.clone(),
+        );
+
+        let result = global_sign_out.execute(input(user_id)).await;
+
+        assert!(result.is_ok());
+        assert!(
+            token_denylist
+                .is_revoked(&claims("session-origin-jti"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            token_denylist
+                .is_revoked(&claims("current-origin-jti"))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_sign_out_stops_on_provider_error() {
+        let user_id = Uuid::new_v4();
+        let global_sign_out = global_sign_out(
+            service("google", || {
+                Err(AuthenticationError::UserNotFound(
+                    "User does not exist.".to_string(),
+                ))
+            }),
+            session_repository(user_id, 0),
+            token_denylist(),
+        );
+
+        let result = global_sign_out.execute(input(user_id)).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::identity_link_dto::{IdentityLinkDto, LinkIdentityInputDTO},
+    domain::{
+        entities::identity_link::NewIdentityLink, errors::RepositoryError,
+        repositories::identity_link_repository::IdentityLinkRepository,
+        services::authentication_service_registry::AuthenticationServiceRegistry,
+    },
+};
+
+// 別のプロバイダーで発行されたトークンを検証し、そのアカウントを認証中のユーザーに紐付ける
+// 紐付けた後は、どちらのプロバイダーでサインインしても同じユーザーとして扱われる
+pub struct LinkIdentity {
+    authentication_services: Arc<AuthenticationServiceRegistry>,
+    identity_link_repository: Arc<dyn IdentityLinkRepository>,
+}
+
+impl LinkIdentity {
+    pub fn new(
+        authentication_services: Arc<AuthenticationServiceRegistry>,
+        identity_link_repository: Arc<dyn IdentityLinkRepository>,
+    ) -> Self {
+        Self {
+            authentication_services,
+            identity_link_repository,
+        }
+    }
+
+    pub async fn execute(&self, input: LinkIdentityInputDTO) -> ApplicationResult<IdentityLinkDto> {
+        let authentication_service = self
+            .authentication_services
+            .get(&input.provider)
+            .ok_or_else(|| {
+                ApplicationError::NotFound(format!(
+                    "Authentication provider '{}' not found",
+                    input.provider
+                ))
+            })?;
+        // 発行者や署名が一致しないトークンはここで拒否される
+        let claims = authentication_service.verify_token(&input.token).await?;
+
+        // 1つのアカウントを複数のユーザーに紐付けることはできない
+        match self
+            .identity_link_repository
+            .find_by_sub(&input.provider, &claims.sub)
+            .await
+        {
+            Ok(identity_link) if identity_link.user_id == input.user_id => {
+                return Err(ApplicationError::Conflict(
+                    "Identity is already linked to this user".to_string(),
+                ));
+            }
+            Ok(_) => {
+                return Err(ApplicationError::Conflict(
+                    "Identity is linked to another user".to_string(),
+                ));
+            }
+            Err(RepositoryError::NotFound(_)) => {}
+            Err(error) => return Err(error.into()),
+        }
+
+        let identity_link = self
+            .identity_link_repository
+            .create(NewIdentityLink {
+                provider: input.provider,
+                sub: claims.sub,
+                user_id: input.user_id,
+            })
+            .await?;
+
+        Ok(IdentityLinkDto::from(identity_link))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::{
+        entities::identity_link::IdentityLink,
+        errors::{AuthenticationError, RepositoryResult},
+        services::authentication_service::AuthenticationService,
+        value_objects::authentication::Claims,
+    };
+    use crate::infrastructure::authentication::unverified_issuer;
+    use async_trait::async_trait;
+    use mockall::mock;
+
+    use uuid::Uuid;
+
+    mock! {
+        IdentityLinkRepo {}
+
+        #[async_trait]
+        impl IdentityLinkRepository for IdentityLinkRepo {
+            async fn create(&self, identity_link: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+            async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+            async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+            async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn service(name: &'static str, verify: bool) -> Arc<dyn AuthenticationService> {
+        let mut service = MockAuthenticationService::new();
+        service
+            .expect_provider_name()
+            .returning(move || name.to_string());
+        service.expect_verify_token().returning(move |_| {
+            if verify {
+                Ok(Claims {
+                    sub: "google-sub-123".to_string(),
+                    ..Default::default()
+                })
+            } else {
+                Err(AuthenticationError::InvalidToken(
+                    "bad signature".to_string(),
+                ))
+            }
+        });
+        Arc::new(service)
+    }
+
+    fn registry(google_verifies: bool) -> Arc<AuthenticationServiceRegistry> {
+        let mut registry = AuthenticationServiceRegistry::new(
+            "https://cognito.example.com",
+            service("cognito", true),
+            unverified_issuer,
+        );
+        registry
+            .register(
+                "https://accounts.google.com",
+                service("google", google_verifies),
+                false,
+            )
+            .unwrap();
+        Arc::new(registry)
+    }
+
+    fn identity_link(provider: &str, sub: &str, user_id: Uuid) -> IdentityLink {
+        IdentityLink {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            sub: sub.to_string(),
+            user_id,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn input(user_id: Uuid, provider: &str) -> LinkIdentityInputDTO {
+        LinkIdentityInputDTO {
+            user_id,
+            provider: provider.to_string(),
+            token: "google-token".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_identity_creates_link_for_verified_subject() {
+        let user_id = Uuid::new_v4();
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo
+            .expect_find_by_sub()
+            .withf(|provider, sub| provider == "google" && sub == "google-sub-123")
+            .returning(|_, _| Err(RepositoryError::NotFound("identity link".to_string())));
+        mock_identity_link_repo
+            .expect_create()
+            .withf(move |link| link.provider == "google" && link.user_id == user_id)
+            .times(1)
+            .returning(|link| Ok(identity_link(&link.provider, &link.sub, link.user_id)));
+        let use_case = LinkIdentity::new(registry(true), Arc::new(mock_identity_link_repo));
+
+        let linked = use_case.execute(input(user_id, "google")).await.unwrap();
+
+        assert_eq!(linked.provider, "google");
+        assert_eq!(linked.sub, "google-sub-123");
+        assert_eq!(linked.user_id, user_id);
+    }
+
+    #[tokio::test]
+    async fn test_link_identity_rejects_identity_of_another_user() {
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo
+            .expect_find_by_sub()
+            .returning(|provider, sub| Ok(identity_link(provider, sub, Uuid::new_v4())));
+        mock_identity_link_repo.expect_create().never();
+        let use_case = LinkIdentity::new(registry(true), Arc::new(mock_identity_link_repo));
+
+        let result = use_case.execute(input(Uuid::new_v4(), "google")).await;
+
+        assert!(matches!(result, Err(ApplicationError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_link_identity_rejects_unverified_token_and_unknown_provider() {
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo.expect_find_by_sub().never();
+        mock_identity_link_repo.expect_create().never();
+        let use_case = LinkIdentity::new(registry(false), Arc::new(mock_identity_link_repo));
+
+        let result = use_case.execute(input(Uuid::new_v4(), "google")).await;
+        assert!(matches!(result, Err(ApplicationError::Unauthenticated(_))));
+
+        let result = use_case.execute(input(Uuid::new_v4(), "github")).await;
+        assert!(matches!(result, Err(ApplicationError::NotFound(_))));
+    }
+}
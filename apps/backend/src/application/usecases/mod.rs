@@ -1,52 +1,190 @@
 use std::sync::Arc;
 
+use associate_software_token::AssociateSoftwareToken;
+use authenticate_api_token::AuthenticateApiToken;
 use authenticate_user::AuthenticateUser;
+use change_password::ChangePassword;
+use confirm_forgot_password::ConfirmForgotPassword;
+use confirm_sign_up::ConfirmSignUp;
+use forgot_password::ForgotPassword;
+use global_sign_out::GlobalSignOut;
+use link_identity::LinkIdentity;
+use refresh_tokens::RefreshTokens;
+use request_sign_in_code::RequestSignInCode;
+use resend_confirmation_code::ResendConfirmationCode;
+use respond_to_auth_challenge::RespondToAuthChallenge;
+use retry_sign_up_compensations::RetrySignUpCompensations;
+use set_mfa_preference::SetMfaPreference;
 use sign_in::SignIn;
+use sign_in_with_authorization_code::SignInWithAuthorizationCode;
 use sign_up::SignUp;
+use verify_sign_in_code::VerifySignInCode;
+use verify_software_token::VerifySoftwareToken;
 
-use crate::domain::services::authentication_service::AuthenticationService;
+use crate::domain::services::authentication_service_registry::AuthenticationServiceRegistry;
+use crate::domain::services::authorization_code_service::AuthorizationCodeService;
+use crate::domain::value_objects::group_mapping::GroupMapping;
 
-use super::services::Repositories;
+use super::services::{Repositories, TokenDenylist};
 
+pub mod associate_software_token;
+pub mod authenticate_api_token;
 pub mod authenticate_user;
+pub mod change_password;
+pub mod confirm_forgot_password;
+pub mod confirm_sign_up;
+pub mod forgot_password;
+pub mod global_sign_out;
+pub mod link_identity;
+pub mod refresh_tokens;
+pub mod request_sign_in_code;
+pub mod resend_confirmation_code;
+pub mod respond_to_auth_challenge;
+pub mod retry_sign_up_compensations;
+pub mod set_mfa_preference;
 pub mod sign_in;
+pub mod sign_in_with_authorization_code;
 pub mod sign_out;
 pub mod sign_up;
+pub mod verify_sign_in_code;
+pub mod verify_software_token;
 
 pub struct UseCases {
     pub sign_up: Arc<SignUp>,
+    pub confirm_sign_up: Arc<ConfirmSignUp>,
+    pub resend_confirmation_code: Arc<ResendConfirmationCode>,
     pub sign_in: Arc<SignIn>,
+    // Hosted UIが設定されている場合だけ使える
+    pub sign_in_with_authorization_code: Option<Arc<SignInWithAuthorizationCode>>,
+    pub respond_to_auth_challenge: Arc<RespondToAuthChallenge>,
+    pub request_sign_in_code: Arc<RequestSignInCode>,
+    pub verify_sign_in_code: Arc<VerifySignInCode>,
+    pub refresh_tokens: Arc<RefreshTokens>,
+    pub forgot_password: Arc<ForgotPassword>,
+    pub confirm_forgot_password: Arc<ConfirmForgotPassword>,
+    pub change_password: Arc<ChangePassword>,
+    pub associate_software_token: Arc<AssociateSoftwareToken>,
+    pub verify_software_token: Arc<VerifySoftwareToken>,
+    pub set_mfa_preference: Arc<SetMfaPreference>,
     pub sign_out: Arc<sign_out::SignOut>,
+    pub global_sign_out: Arc<GlobalSignOut>,
     pub authenticate_user: Arc<AuthenticateUser>,
+    pub authenticate_api_token: Arc<AuthenticateApiToken>,
+    pub link_identity: Arc<LinkIdentity>,
+    pub retry_sign_up_compensations: Arc<RetrySignUpCompensations>,
 }
 
 pub fn init_use_cases(
     repositories: Arc<Repositories>,
-    authentication_service: Arc<dyn AuthenticationService>,
+    authentication_services: Arc<AuthenticationServiceRegistry>,
+    authorization_code_service: Option<Arc<dyn AuthorizationCodeService>>,
+    group_mapping: GroupMapping,
+    token_denylist: Arc<TokenDenylist>,
 ) -> UseCases {
+    // サインアップやパスワードでのサインインなど、アカウントの操作は既定のプロバイダーで行う
+    let authentication_service = authentication_services.default_service();
     let sign_up = SignUp::new(
         authentication_service.clone(),
         repositories.user_repository.clone(),
-        repositories.identity_link_repository.clone(),
+        repositories.sign_up_compensation_repository.clone(),
     );
 
-    let sign_in = SignIn::new(
+    let confirm_sign_up = ConfirmSignUp::new(authentication_service.clone());
+    let resend_confirmation_code = ResendConfirmationCode::new(authentication_service.clone());
+
+    let sign_in = Arc::new(SignIn::new(
         authentication_service.clone(),
         repositories.identity_link_repository.clone(),
         repositories.user_repository.clone(),
+        repositories.session_repository.clone(),
+    ));
+    let respond_to_auth_challenge =
+        RespondToAuthChallenge::new(authentication_service.clone(), sign_in.clone());
+    let request_sign_in_code = RequestSignInCode::new(
+        authentication_service.clone(),
+        repositories.sign_in_code_request_repository.clone(),
+    );
+    let verify_sign_in_code = VerifySignInCode::new(
+        authentication_service.clone(),
+        repositories.sign_in_code_request_repository.clone(),
+        sign_in.clone(),
     );
+    let sign_in_with_authorization_code = authorization_code_service.map(|service| {
+        Arc::new(SignInWithAuthorizationCode::new(
+            service,
+            authentication_service.clone(),
+            repositories.identity_link_repository.clone(),
+            repositories.user_repository.clone(),
+            sign_in.clone(),
+        ))
+    });
 
-    let sign_out = sign_out::SignOut::new(authentication_service.clone());
-    let authenticate_user = AuthenticateUser::new(
+    let refresh_tokens = RefreshTokens::new(
+        authentication_service.clone(),
+        repositories.session_repository.clone(),
+    );
+    let forgot_password = ForgotPassword::new(authentication_service.clone());
+    let confirm_forgot_password = ConfirmForgotPassword::new(authentication_service.clone());
+    let change_password = ChangePassword::new(authentication_service.clone());
+    let associate_software_token = AssociateSoftwareToken::new(authentication_service.clone());
+    let verify_software_token = VerifySoftwareToken::new(authentication_service.clone());
+    let set_mfa_preference = SetMfaPreference::new(
         authentication_service.clone(),
+        repositories.user_repository.clone(),
+    );
+    let sign_out = sign_out::SignOut::new(
+        authentication_services.clone(),
+        repositories.session_repository.clone(),
+        token_denylist.clone(),
+    );
+    let global_sign_out = GlobalSignOut::new(
+        authentication_services.clone(),
+        repositories.session_repository.clone(),
+        token_denylist.clone(),
+    );
+    let link_identity = LinkIdentity::new(
+        authentication_services.clone(),
+        repositories.identity_link_repository.clone(),
+    );
+    let authenticate_user = AuthenticateUser::new(
+        authentication_services,
         repositories.identity_link_repository.clone(),
         repositories.user_repository.clone(),
+        repositories.permission_repository.clone(),
+        token_denylist,
+        group_mapping,
+    );
+    let authenticate_api_token = AuthenticateApiToken::new(
+        repositories.api_token_repository.clone(),
+        repositories.user_repository.clone(),
+        repositories.permission_repository.clone(),
+    );
+    let retry_sign_up_compensations = RetrySignUpCompensations::new(
+        authentication_service.clone(),
+        repositories.sign_up_compensation_repository.clone(),
     );
 
     UseCases {
         sign_up: Arc::new(sign_up),
-        sign_in: Arc::new(sign_in),
+        confirm_sign_up: Arc::new(confirm_sign_up),
+        resend_confirmation_code: Arc::new(resend_confirmation_code),
+        sign_in,
+        sign_in_with_authorization_code,
+        respond_to_auth_challenge: Arc::new(respond_to_auth_challenge),
+        request_sign_in_code: Arc::new(request_sign_in_code),
+        verify_sign_in_code: Arc::new(verify_sign_in_code),
+        refresh_tokens: Arc::new(refresh_tokens),
+        forgot_password: Arc::new(forgot_password),
+        confirm_forgot_password: Arc::new(confirm_forgot_password),
+        change_password: Arc::new(change_password),
+        associate_software_token: Arc::new(associate_software_token),
+        verify_software_token: Arc::new(verify_software_token),
+        set_mfa_preference: Arc::new(set_mfa_preference),
         sign_out: Arc::new(sign_out),
+        global_sign_out: Arc::new(global_sign_out),
         authenticate_user: Arc::new(authenticate_user),
+        authenticate_api_token: Arc::new(authenticate_api_token),
+        link_identity: Arc::new(link_identity),
+        retry_sign_up_compensations: Arc::new(retry_sign_up_compensations),
     }
 }
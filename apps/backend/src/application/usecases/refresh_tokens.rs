@@ -0,0 +1,73 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::{AuthenticationTokensDTO, RefreshTokensInputDTO},
+    domain::{
+        errors::AuthenticationError, repositories::session_repository::SessionRepository,
+        services::authentication_service::AuthenticationService,
+    },
+};
+
+pub struct RefreshTokens {
+    authentication_service: Arc<dyn AuthenticationService>,
+    session_repository: Arc<dyn SessionRepository>,
+}
+
+impl RefreshTokens {
+    pub fn new(
+        authentication_service: Arc<dyn AuthenticationService>,
+        session_repository: Arc<dyn SessionRepository>,
+    ) -> Self {
+        Self {
+            authentication_service,
+            session_repository,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: RefreshTokensInputDTO,
+    ) -> ApplicationResult<AuthenticationTokensDTO> {
+        let output = self
+            .authentication_service
+            .refresh_tokens(&input.refresh_token)
+            .await?;
+
+        // 他の端末から失効させたセッションは、プロバイダー側のトークンが有効でも更新させない
+        let claims = self
+            .authentication_service
+            .verify_token(&output.access_token)
+            .await?;
+        // origin_jtiの無いプロバイダーはセッションを記録できないため、プロバイダー側の失効に任せる
+        let Some(origin_jti) = claims.origin_jti else {
+            return Ok(AuthenticationTokensDTO::from(output));
+        };
+        match self
+            .session_repository
+            .find_by_origin_jti(&origin_jti)
+            .await?
+        {
+            Some(session) if !session.is_revoked() => {
+                self.session_repository.touch(session.id).await?
+            }
+            // 記録の無いセッション（この機能より前のサインイン）も、失効させたかどうか確認できないため拒否する
+            // 拒否しないと、拒否リストの記録が消えた後に失効させたセッションのトークンを再び発行できてしまう
+            _ => {
+                if let Err(e) = self
+                    .authentication_service
+                    .revoke_token(&input.refresh_token)
+                    .await
+                {
+                    tracing::warn!("Failed to revoke refresh token of revoked session: {}", e);
+                }
+                return Err(AuthenticationError::NotAuthorized(
+                    "Refresh Token has been revoked".to_string(),
+                )
+                .into());
+            }
+        }
+
+        Ok(AuthenticationTokensDTO::from(output))
+    }
+}
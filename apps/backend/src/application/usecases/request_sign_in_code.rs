@@ -0,0 +1,204 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::{
+        CodeDeliveryDetailsDTO, RequestSignInCodeInputDTO, SignInCodeChallengeDTO,
+    },
+    domain::{
+        entities::sign_in_code_request::NewSignInCodeRequest, errors::AuthenticationError,
+        repositories::sign_in_code_request_repository::SignInCodeRequestRepository,
+        services::authentication_service::AuthenticationService,
+    },
+};
+
+// Cognitoのチャレンジのセッションと同じ有効期間
+const SIGN_IN_CODE_TTL: i64 = 3 * 60;
+// メールアドレスごとに、この期間内に送れるコードの数を制限する
+const SIGN_IN_CODE_RATE_LIMIT_WINDOW: i64 = 15 * 60;
+const MAX_SIGN_IN_CODE_REQUESTS: u64 = 5;
+
+// パスワードの代わりに、メールで送るワンタイムコードでのサインインを開始する
+pub struct RequestSignInCode {
+    authentication_service: Arc<dyn AuthenticationService>,
+    sign_in_code_request_repository: Arc<dyn SignInCodeRequestRepository>,
+}
+
+impl RequestSignInCode {
+    pub fn new(
+        authentication_service: Arc<dyn AuthenticationService>,
+        sign_in_code_request_repository: Arc<dyn SignInCodeRequestRepository>,
+    ) -> Self {
+        Self {
+            authentication_service,
+            sign_in_code_request_repository,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: RequestSignInCodeInputDTO,
+    ) -> ApplicationResult<SignInCodeChallengeDTO> {
+        let now = Utc::now();
+        let recent_requests = self
+            .sign_in_code_request_repository
+            .count_by_email_since(
+                &input.email,
+                now - Duration::seconds(SIGN_IN_CODE_RATE_LIMIT_WINDOW),
+            )
+            .await?;
+        if recent_requests >= MAX_SIGN_IN_CODE_REQUESTS {
+            return Err(ApplicationError::Authentication(
+                AuthenticationError::TooManyRequests(
+                    "Too many sign in codes requested, please try again later".to_string(),
+                ),
+            ));
+        }
+
+        // メールアドレスの登録有無を推測されないよう、存在しないユーザーにも同じ形で応答する
+        // プロバイダーのセッションが無い要求は、どのコードでも検証に失敗する
+        let (provider_session, code_delivery_details) = match self
+            .authentication_service
+            .request_sign_in_code(&input.email)
+            .await
+        {
+            Ok(challenge) => (
+                challenge.session,
+                CodeDeliveryDetailsDTO::from(challenge.code_delivery_details),
+            ),
+            Err(AuthenticationError::UserNotFound(_)) => (
+                None,
+                CodeDeliveryDetailsDTO {
+                    destination: None,
+                    delivery_medium: Some("EMAIL".to_string()),
+                    attribute_name: Some("email".to_string()),
+                },
+            ),
+            Err(error) => return Err(error.into()),
+        };
+        let request = self
+            .sign_in_code_request_repository
+            .create(NewSignInCodeRequest {
+                email: input.email,
+                provider_session,
+                expires_at: now + Duration::seconds(SIGN_IN_CODE_TTL),
+            })
+            .await?;
+
+        Ok(SignInCodeChallengeDTO {
+            session: request.id,
+            expires_at: request.expires_at,
+            code_delivery_details,
+        })
+    }
+
+    // レート制限の期間を過ぎた要求を削除する（有効期間はレート制限の期間より短い）
+    pub async fn purge_expired(&self) -> ApplicationResult<u64> {
+        let purged = self
+            .sign_in_code_request_repository
+            .delete_created_before(Utc::now() - Duration::seconds(SIGN_IN_CODE_RATE_LIMIT_WINDOW))
+            .await?;
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::{
+        entities::sign_in_code_request::SignInCodeRequest, errors::RepositoryResult,
+    };
+    use async_trait::async_trait;
+    use chrono::DateTime;
+    use mockall::mock;
+
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    mock! {
+        SignInCodeRequestRepo {}
+
+        #[async_trait]
+        impl SignInCodeRequestRepository for SignInCodeRequestRepo {
+            async fn create(&self, request: NewSignInCodeRequest) -> RepositoryResult<SignInCodeRequest>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn count_by_email_since(&self, email: &str, since: DateTime<Utc>) -> RepositoryResult<u64>;
+            async fn start_attempt(&self, id: Uuid, max_attempts: i32, now: DateTime<Utc>) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn update_provider_session(&self, id: Uuid, provider_session: Option<String>) -> RepositoryResult<()>;
+            async fn consume(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn delete_created_before(&self, before: DateTime<Utc>) -> RepositoryResult<u64>;
+        }
+    }
+
+    fn input() -> RequestSignInCodeInputDTO {
+        RequestSignInCodeInputDTO {
+            email: "user@example.com".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_requests_over_the_rate_limit() {
+        // 上限に達している場合は、コードを送信しない
+        let mut mock_repo = MockSignInCodeRequestRepo::new();
+        mock_repo
+            .expect_count_by_email_since()
+            .withf(|email, _| email == "user@example.com")
+            .returning(|_, _| Ok(MAX_SIGN_IN_CODE_REQUESTS));
+        let use_case = RequestSignInCode::new(
+            Arc::new(MockAuthenticationService::new()),
+            Arc::new(mock_repo),
+        );
+
+        let result = use_case.execute(input()).await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::TooManyRequests(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_gets_the_same_response_without_provider_session() {
+        let mut auth_service = MockAuthenticationService::new();
+        auth_service.expect_request_sign_in_code().returning(|_| {
+            Err(AuthenticationError::UserNotFound(
+                "User does not exist.".to_string(),
+            ))
+        });
+        let created: Arc<Mutex<Vec<NewSignInCodeRequest>>> = Arc::default();
+        let mut mock_repo = MockSignInCodeRequestRepo::new();
+        mock_repo
+            .expect_count_by_email_since()
+            .returning(|_, _| Ok(0));
+        let recorded = created.clone();
+        mock_repo.expect_create().returning(move |request| {
+            recorded.lock().unwrap().push(request.clone());
+            Ok(SignInCodeRequest {
+                id: Uuid::new_v4(),
+                email: request.email,
+                provider_session: request.provider_session,
+                attempts: 0,
+                expires_at: request.expires_at,
+                consumed_at: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+        });
+        let use_case = RequestSignInCode::new(Arc::new(auth_service), Arc::new(mock_repo));
+
+        let output = use_case.execute(input()).await.unwrap();
+
+        assert!(output.expires_at > Utc::now());
+        assert_eq!(
+            output.code_delivery_details.delivery_medium.as_deref(),
+            Some("EMAIL")
+        );
+        let created = created.lock().unwrap();
+        assert_eq!(created.len(), 1);
+        assert!(created[0].provider_session.is_none());
+    }
+}
@@ -0,0 +1,32 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::{
+        CodeDeliveryDetailsDTO, ResendConfirmationCodeInputDTO,
+    },
+    domain::services::authentication_service::AuthenticationService,
+};
+
+pub struct ResendConfirmationCode {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl ResendConfirmationCode {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: ResendConfirmationCodeInputDTO,
+    ) -> ApplicationResult<CodeDeliveryDetailsDTO> {
+        let details = self
+            .authentication_service
+            .resend_confirmation_code(&input.email)
+            .await?;
+        Ok(CodeDeliveryDetailsDTO::from(details))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::{
+        dtos::authentication_dto::{
+            ClientInfoDTO, RespondToAuthChallengeInputDTO, SignInOutputDTO,
+        },
+        usecases::sign_in::SignIn,
+    },
+    domain::services::authentication_service::AuthenticationService,
+};
+
+pub struct RespondToAuthChallenge {
+    authentication_service: Arc<dyn AuthenticationService>,
+    sign_in: Arc<SignIn>,
+}
+
+impl RespondToAuthChallenge {
+    pub fn new(
+        authentication_service: Arc<dyn AuthenticationService>,
+        sign_in: Arc<SignIn>,
+    ) -> Self {
+        Self {
+            authentication_service,
+            sign_in,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: RespondToAuthChallengeInputDTO,
+        client: ClientInfoDTO,
+    ) -> ApplicationResult<SignInOutputDTO> {
+        let auth_output = self
+            .authentication_service
+            .respond_to_auth_challenge(
+                &input.email,
+                &input.challenge_name,
+                input.session,
+                &input.responses,
+            )
+            .await?;
+
+        // 応答後にさらに別のチャレンジが返ることもあるため、サインインと同じ後処理を通す
+        self.sign_in.complete(auth_output, client).await
+    }
+}
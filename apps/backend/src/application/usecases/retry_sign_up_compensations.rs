@@ -0,0 +1,59 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::domain::{
+    repositories::sign_up_compensation_repository::SignUpCompensationRepository,
+    services::authentication_service::AuthenticationService,
+};
+
+// 未解決のサインアップ補償（認証プロバイダー側のユーザー削除）を再試行する
+pub struct RetrySignUpCompensations {
+    authentication_service: Arc<dyn AuthenticationService>,
+    sign_up_compensation_repository: Arc<dyn SignUpCompensationRepository>,
+}
+
+impl RetrySignUpCompensations {
+    pub fn new(
+        authentication_service: Arc<dyn AuthenticationService>,
+        sign_up_compensation_repository: Arc<dyn SignUpCompensationRepository>,
+    ) -> Self {
+        Self {
+            authentication_service,
+            sign_up_compensation_repository,
+        }
+    }
+
+    // 解決できた件数を返す
+    pub async fn execute(&self) -> ApplicationResult<usize> {
+        let provider = self.authentication_service.provider_name();
+        let compensations = self
+            .sign_up_compensation_repository
+            .find_unresolved()
+            .await?;
+
+        let mut resolved = 0;
+        for compensation in compensations
+            .into_iter()
+            .filter(|compensation| compensation.provider == provider)
+        {
+            match self
+                .authentication_service
+                .delete_user(&compensation.username)
+                .await
+            {
+                Ok(_) => {
+                    self.sign_up_compensation_repository
+                        .mark_resolved(compensation.id)
+                        .await?;
+                    resolved += 1;
+                }
+                Err(error) => {
+                    self.sign_up_compensation_repository
+                        .record_failure(compensation.id, &error.to_string())
+                        .await?;
+                }
+            }
+        }
+        Ok(resolved)
+    }
+}
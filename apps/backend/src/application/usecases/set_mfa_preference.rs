@@ -0,0 +1,48 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::{authentication_dto::SetMfaPreferenceInputDTO, user_dto::UserDTO},
+    domain::{
+        entities::user::UserChanges, repositories::user_repository::UserRepository,
+        services::authentication_service::AuthenticationService,
+    },
+};
+
+pub struct SetMfaPreference {
+    authentication_service: Arc<dyn AuthenticationService>,
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl SetMfaPreference {
+    pub fn new(
+        authentication_service: Arc<dyn AuthenticationService>,
+        user_repository: Arc<dyn UserRepository>,
+    ) -> Self {
+        Self {
+            authentication_service,
+            user_repository,
+        }
+    }
+
+    pub async fn execute(&self, input: SetMfaPreferenceInputDTO) -> ApplicationResult<UserDTO> {
+        let user = self
+            .user_repository
+            .find_by_id(input.user_id)
+            .await?
+            .ok_or_else(|| ApplicationError::NotFound("User not found".to_string()))?;
+
+        self.authentication_service
+            .set_mfa_preference(&input.access_token, input.enabled)
+            .await?;
+
+        // 認証プロバイダー側の設定が成功した後に、表示用の状態を保存する
+        let changes = UserChanges {
+            mfa_enabled: Some(input.enabled),
+            ..Default::default()
+        };
+        let user = self.user_repository.update(user.id, changes).await?;
+
+        Ok(UserDTO::from(user))
+    }
+}
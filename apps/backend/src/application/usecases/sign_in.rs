@@ -1,11 +1,18 @@
-use anyhow::Result;
+use crate::application::errors::{ApplicationError, ApplicationResult};
 use std::sync::Arc;
 
 use crate::{
-    application::dtos::authentication_dto::{SignInInputDTO, SignInOutputDTO},
+    application::dtos::authentication_dto::{
+        AuthChallengeDTO, AuthenticationTokensDTO, ClientInfoDTO, SignInInputDTO, SignInOutputDTO,
+    },
     domain::{
-        repositories::{identity_link_repository::IdentityLinkRepository, user_repository::UserRepository},
+        entities::session::NewSession,
+        repositories::{
+            identity_link_repository::IdentityLinkRepository,
+            session_repository::SessionRepository, user_repository::UserRepository,
+        },
         services::authentication_service::AuthenticationService,
+        value_objects::authentication::SignInOutput,
     },
 };
 
@@ -13,6 +20,7 @@ pub struct SignIn {
     authentication_service: Arc<dyn AuthenticationService>,
     identity_link_repository: Arc<dyn IdentityLinkRepository>,
     user_repository: Arc<dyn UserRepository>,
+    session_repository: Arc<dyn SessionRepository>,
 }
 
 impl SignIn {
@@ -20,45 +28,83 @@ impl SignIn {
         authentication_service: Arc<dyn AuthenticationService>,
         identity_link_repository: Arc<dyn IdentityLinkRepository>,
         user_repository: Arc<dyn UserRepository>,
+        session_repository: Arc<dyn SessionRepository>,
     ) -> Self {
         Self {
             authentication_service,
             identity_link_repository,
             user_repository,
+            session_repository,
         }
     }
 
-    pub async fn execute(&self, input: SignInInputDTO) -> Result<SignInOutputDTO> {
+    pub async fn execute(
+        &self,
+        input: SignInInputDTO,
+        client: ClientInfoDTO,
+    ) -> ApplicationResult<SignInOutputDTO> {
         // First, authenticate with the authentication service
         let auth_output = self
             .authentication_service
             .sign_in(&input.email, &input.password)
             .await?;
 
+        self.complete(auth_output, client).await
+    }
+
+    // Tokens are only handed out once the user is confirmed to exist in our database.
+    // Challenges are returned as-is so the client can continue the flow.
+    pub(crate) async fn complete(
+        &self,
+        auth_output: SignInOutput,
+        client: ClientInfoDTO,
+    ) -> ApplicationResult<SignInOutputDTO> {
+        let tokens = match auth_output {
+            SignInOutput::Authenticated(tokens) => tokens,
+            SignInOutput::Challenge(challenge) => {
+                return Ok(SignInOutputDTO::Challenge(AuthChallengeDTO::from(
+                    challenge,
+                )));
+            }
+        };
+
         // Verify the token to get user claims
         let claims = self
             .authentication_service
-            .verify_token(&auth_output.access_token)
+            .verify_token(&tokens.access_token)
             .await?;
 
         // Look up the identity link to ensure the user exists in our database
         let identity_link = self
             .identity_link_repository
-            .find_by_sub(&claims.sub)
+            .find_by_sub(&self.authentication_service.provider_name(), &claims.sub)
             .await?;
 
         // Verify the user exists in our database
-        let _user = self
+        let user = self
             .user_repository
             .find_by_id(identity_link.user_id)
             .await?
-            .ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            .ok_or_else(|| ApplicationError::NotFound("User not found".to_string()))?;
+
+        // 端末ごとにサインアウトできるよう、サインインをセッションとして記録する
+        match claims.origin_jti {
+            Some(origin_jti) => {
+                self.session_repository
+                    .create(NewSession {
+                        user_id: user.id,
+                        origin_jti,
+                        device: client.device,
+                        ip_address: client.ip_address,
+                        user_agent: client.user_agent,
+                    })
+                    .await?;
+            }
+            None => tracing::warn!("Access token has no origin_jti; session is not recorded"),
+        }
 
-        Ok(SignInOutputDTO {
-            id_token: auth_output.id_token,
-            access_token: auth_output.access_token,
-            refresh_token: auth_output.refresh_token,
-            expires_in: auth_output.expires_in,
-        })
+        Ok(SignInOutputDTO::Authenticated(
+            AuthenticationTokensDTO::from(tokens),
+        ))
     }
 }
@@ -0,0 +1,386 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use std::sync::Arc;
+
+use crate::{
+    application::{
+        dtos::authentication_dto::{
+            AuthenticationTokensDTO, AuthorizationCodeInputDTO, ClientInfoDTO, SignInOutputDTO,
+        },
+        usecases::sign_in::SignIn,
+    },
+    domain::{
+        entities::user::NewUser,
+        enums::user_role::UserRole,
+        errors::{AuthenticationError, RepositoryError},
+        repositories::{
+            identity_link_repository::IdentityLinkRepository, user_repository::UserRepository,
+        },
+        services::{
+            authentication_service::AuthenticationService,
+            authorization_code_service::AuthorizationCodeService,
+        },
+        value_objects::authentication::SignInOutput,
+    },
+};
+
+// Hosted UIでサインインしたユーザーの認可コードをトークンと交換し、サインインを完了する
+// Googleなどの外部プロバイダーで初めてサインインしたユーザーは、SignUpと同じようにここで作成する
+pub struct SignInWithAuthorizationCode {
+    authorization_code_service: Arc<dyn AuthorizationCodeService>,
+    authentication_service: Arc<dyn AuthenticationService>,
+    identity_link_repository: Arc<dyn IdentityLinkRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    sign_in: Arc<SignIn>,
+}
+
+impl SignInWithAuthorizationCode {
+    pub fn new(
+        authorization_code_service: Arc<dyn AuthorizationCodeService>,
+        authentication_service: Arc<dyn AuthenticationService>,
+        identity_link_repository: Arc<dyn IdentityLinkRepository>,
+        user_repository: Arc<dyn UserRepository>,
+        sign_in: Arc<SignIn>,
+    ) -> Self {
+        Self {
+            authorization_code_service,
+            authentication_service,
+            identity_link_repository,
+            user_repository,
+            sign_in,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: AuthorizationCodeInputDTO,
+        client: ClientInfoDTO,
+    ) -> ApplicationResult<AuthenticationTokensDTO> {
+        let output = self
+            .authorization_code_service
+            .exchange_code(&input.code, &input.code_verifier)
+            .await?;
+        // 別のリクエストで発行されたIDトークンを受け付けない
+        if output.id_token.nonce.as_deref() != Some(input.nonce.as_str()) {
+            return Err(not_authorized("ID token nonce does not match"));
+        }
+        let claims = self
+            .authentication_service
+            .verify_token(&output.tokens.access_token)
+            .await?;
+        if claims.sub != output.id_token.sub {
+            return Err(not_authorized(
+                "ID token does not belong to the access token",
+            ));
+        }
+
+        let provider = self.authentication_service.provider_name();
+        match self
+            .identity_link_repository
+            .find_by_sub(&provider, &claims.sub)
+            .await
+        {
+            Ok(_) => {}
+            Err(RepositoryError::NotFound(_)) => {
+                let id_token = output.id_token;
+                let name = id_token
+                    .name
+                    .or(id_token.email)
+                    .or(claims.username)
+                    .unwrap_or_else(|| claims.sub.clone());
+                let result = self
+                    .user_repository
+                    .create_with_identity_link(
+                        NewUser {
+                            name,
+                            role: UserRole::default(),
+                        },
+                        provider,
+                        claims.sub,
+                    )
+                    .await;
+                match result {
+                    // 同じユーザーの初回サインインが同時に行われた場合は、先に作成されたユーザーを使う
+                    Ok(_) | Err(RepositoryError::Conflict(_)) => {}
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        // セッションの記録はパスワードでのサインインと共通
+        match self
+            .sign_in
+            .complete(SignInOutput::Authenticated(output.tokens), client)
+            .await?
+        {
+            SignInOutputDTO::Authenticated(tokens) => Ok(tokens),
+            SignInOutputDTO::Challenge(challenge) => Err(ApplicationError::InternalError(format!(
+                "Unexpected challenge after authorization code exchange: {}",
+                challenge.name
+            ))),
+        }
+    }
+}
+
+fn not_authorized(message: &str) -> ApplicationError {
+    ApplicationError::Authentication(AuthenticationError::NotAuthorized(message.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::{
+        entities::{
+            identity_link::{IdentityLink, NewIdentityLink},
+            session::{NewSession, Session},
+            user::{User, UserChanges},
+        },
+        errors::{AuthenticationResult, RepositoryResult},
+        repositories::session_repository::SessionRepository,
+        value_objects::{
+            authentication::{
+                AuthenticationTokens, AuthorizationCodeTokens, Claims, IdTokenClaims,
+            },
+            authorization_request::AuthorizationRequest,
+        },
+    };
+    use async_trait::async_trait;
+    use mockall::mock;
+
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    mock! {
+        AuthorizationCodeClient {}
+
+        #[async_trait]
+        impl AuthorizationCodeService for AuthorizationCodeClient {
+            fn authorization_url<'a>(&self, request: &AuthorizationRequest, identity_provider: Option<&'a str>) -> String;
+            async fn exchange_code(&self, code: &str, code_verifier: &str) -> AuthenticationResult<AuthorizationCodeTokens>;
+        }
+    }
+
+    mock! {
+        UserRepo {}
+
+        #[async_trait]
+        impl UserRepository for UserRepo {
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
+        }
+    }
+
+    mock! {
+        IdentityLinkRepo {}
+
+        #[async_trait]
+        impl IdentityLinkRepository for IdentityLinkRepo {
+            async fn create(&self, identity_link: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+            async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+            async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+            async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        SessionRepo {}
+
+        #[async_trait]
+        impl SessionRepository for SessionRepo {
+            async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+            async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn test_user(id: Uuid, name: &str) -> User {
+        User {
+            id,
+            name: name.to_string(),
+            role: UserRole::User,
+            mfa_enabled: false,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            identity_links: vec![],
+        }
+    }
+
+    fn authorization_code_client(nonce: &'static str) -> MockAuthorizationCodeClient {
+        let mut client = MockAuthorizationCodeClient::new();
+        client
+            .expect_exchange_code()
+            .withf(|code, code_verifier| code == "auth-code" && code_verifier == "verifier")
+            .returning(move |_, _| {
+                Ok(AuthorizationCodeTokens {
+                    tokens: AuthenticationTokens {
+                        id_token: "id-token".to_string(),
+                        access_token: "access-token".to_string(),
+                        refresh_token: "refresh-token".to_string(),
+                        expires_in: 3600,
+                    },
+                    id_token: IdTokenClaims {
+                        sub: "google-sub-123".to_string(),
+                        nonce: Some(nonce.to_string()),
+                        email: Some("user@example.com".to_string()),
+                        name: Some("Social User".to_string()),
+                    },
+                })
+            });
+        client
+    }
+
+    fn auth_service() -> Arc<dyn AuthenticationService> {
+        let mut auth_service = MockAuthenticationService::new();
+        auth_service
+            .expect_provider_name()
+            .returning(|| "cognito".to_string());
+        auth_service.expect_verify_token().returning(|_| {
+            Ok(Claims {
+                sub: "google-sub-123".to_string(),
+                origin_jti: Some("origin-jti".to_string()),
+                ..Default::default()
+            })
+        });
+        Arc::new(auth_service)
+    }
+
+    // create_with_identity_linkで作成した紐付けを、以降のfind_by_subで返す
+    fn use_case(
+        authorization_code_client: MockAuthorizationCodeClient,
+        created_users: Arc<Mutex<Vec<String>>>,
+    ) -> SignInWithAuthorizationCode {
+        let identity_links: Arc<Mutex<Vec<IdentityLink>>> = Arc::default();
+
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        let found = identity_links.clone();
+        mock_identity_link_repo
+            .expect_find_by_sub()
+            .returning(move |provider, sub| {
+                found
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|link| link.provider == provider && link.sub == sub)
+                    .cloned()
+                    .ok_or_else(|| RepositoryError::NotFound("identity link".to_string()))
+            });
+
+        let mut mock_user_repo = MockUserRepo::new();
+        mock_user_repo
+            .expect_create_with_identity_link()
+            .returning(move |user, provider, sub| {
+                created_users.lock().unwrap().push(user.name.clone());
+                let user = test_user(Uuid::new_v4(), &user.name);
+                let identity_link = IdentityLink {
+                    id: Uuid::new_v4(),
+                    provider,
+                    sub,
+                    user_id: user.id,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                };
+                identity_links.lock().unwrap().push(identity_link.clone());
+                Ok((user, identity_link))
+            });
+        mock_user_repo
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(test_user(id, "Social User"))));
+
+        let mut mock_session_repo = MockSessionRepo::new();
+        mock_session_repo
+            .expect_create()
+            .withf(|session| session.origin_jti == "origin-jti")
+            .returning(|session| {
+                Ok(Session {
+                    id: Uuid::new_v4(),
+                    user_id: session.user_id,
+                    origin_jti: session.origin_jti,
+                    device: session.device,
+                    ip_address: session.ip_address,
+                    user_agent: session.user_agent,
+                    last_used_at: chrono::Utc::now(),
+                    revoked_at: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+            });
+
+        let auth_service = auth_service();
+        let identity_link_repo: Arc<dyn IdentityLinkRepository> = Arc::new(mock_identity_link_repo);
+        let user_repo: Arc<dyn UserRepository> = Arc::new(mock_user_repo);
+        let sign_in = Arc::new(SignIn::new(
+            auth_service.clone(),
+            identity_link_repo.clone(),
+            user_repo.clone(),
+            Arc::new(mock_session_repo),
+        ));
+        SignInWithAuthorizationCode::new(
+            Arc::new(authorization_code_client),
+            auth_service,
+            identity_link_repo,
+            user_repo,
+            sign_in,
+        )
+    }
+
+    fn input(nonce: &str) -> AuthorizationCodeInputDTO {
+        AuthorizationCodeInputDTO {
+            code: "auth-code".to_string(),
+            code_verifier: "verifier".to_string(),
+            nonce: nonce.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_sign_in_creates_user_and_identity_link() {
+        let created_users: Arc<Mutex<Vec<String>>> = Arc::default();
+        let use_case = use_case(
+            authorization_code_client("test-nonce"),
+            created_users.clone(),
+        );
+
+        let tokens = use_case
+            .execute(input("test-nonce"), ClientInfoDTO::default())
+            .await
+            .unwrap();
+        assert_eq!(tokens.access_token, "access-token");
+        // 2回目のサインインでは、作成済みのユーザーを使う
+        use_case
+            .execute(input("test-nonce"), ClientInfoDTO::default())
+            .await
+            .unwrap();
+
+        assert_eq!(*created_users.lock().unwrap(), vec!["Social User"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_id_token_with_other_nonce() {
+        let created_users: Arc<Mutex<Vec<String>>> = Arc::default();
+        let use_case = use_case(
+            authorization_code_client("other-nonce"),
+            created_users.clone(),
+        );
+
+        let result = use_case
+            .execute(input("test-nonce"), ClientInfoDTO::default())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::NotAuthorized(_)
+            ))
+        ));
+        assert!(created_users.lock().unwrap().is_empty());
+    }
+}
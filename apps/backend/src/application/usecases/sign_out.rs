@@ -1,26 +1,160 @@
-use anyhow::Result;
+use crate::application::errors::{ApplicationError, ApplicationResult};
 use std::sync::Arc;
 
 use crate::{
-    application::dtos::authentication_dto::SignOutInputDTO,
-    domain::services::authentication_service::AuthenticationService,
+    application::{
+        dtos::authentication_dto::SignOutInputDTO, services::token_denylist::TokenDenylist,
+    },
+    domain::{
+        repositories::session_repository::SessionRepository,
+        services::authentication_service_registry::AuthenticationServiceRegistry,
+    },
 };
 
+// 現在の端末のセッションだけをサインアウトする
 pub struct SignOut {
-    authentication_service: Arc<dyn AuthenticationService>,
+    authentication_services: Arc<AuthenticationServiceRegistry>,
+    session_repository: Arc<dyn SessionRepository>,
+    token_denylist: Arc<TokenDenylist>,
 }
 
 impl SignOut {
-    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+    pub fn new(
+        authentication_services: Arc<AuthenticationServiceRegistry>,
+        session_repository: Arc<dyn SessionRepository>,
+        token_denylist: Arc<TokenDenylist>,
+    ) -> Self {
         Self {
-            authentication_service,
+            authentication_services,
+            session_repository,
+            token_denylist,
         }
     }
 
-    pub async fn execute(&self, input: SignOutInputDTO) -> Result<()> {
-        self.authentication_service
-            .sign_out(&input.username)
+    pub async fn execute(&self, input: SignOutInputDTO) -> ApplicationResult<()> {
+        if let Some(origin_jti) = &input.origin_jti {
+            let session = self
+                .session_repository
+                .find_by_origin_jti(origin_jti)
+                .await?;
+            if let Some(session) =
+                session.filter(|session| session.user_id == input.user_id && !session.is_revoked())
+            {
+                self.session_repository.revoke(session.id).await?;
+            }
+        }
+        // 期限内のアクセストークンも、これ以降は受け付けない
+        self.token_denylist
+            .revoke_access_token(
+                input.jti.as_deref(),
+                input.origin_jti.as_deref(),
+                input.expires_at,
+            )
             .await?;
+        // リフレッシュトークンが無い場合も、セッションの失効によって以降の更新は拒否される
+        // リフレッシュトークンは、アクセストークンを発行したプロバイダーで失効させる
+        if let Some(refresh_token) = &input.refresh_token {
+            let authentication_service = self
+                .authentication_services
+                .get(&input.provider)
+                .ok_or_else(|| {
+                    ApplicationError::InternalError(format!(
+                        "Unknown authentication provider: {}",
+                        input.provider
+                    ))
+                })?;
+            authentication_service.revoke_token(refresh_token).await?;
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::{
+        entities::session::{NewSession, Session},
+        errors::RepositoryResult,
+    };
+    use crate::infrastructure::authentication::unverified_issuer;
+    use crate::infrastructure::memory::InMemoryRevokedTokenRepository;
+    use async_trait::async_trait;
+    use mockall::mock;
+    use uuid::Uuid;
+
+    mock! {
+        SessionRepo {}
+
+        #[async_trait]
+        impl SessionRepository for SessionRepo {
+            async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+            async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn service(name: &'static str, revoked_tokens: usize) -> MockAuthenticationService {
+        let mut service = MockAuthenticationService::new();
+        service
+            .expect_provider_name()
+            .returning(move || name.to_string());
+        service
+            .expect_revoke_token()
+            .times(revoked_tokens)
+            .returning(|_| Ok(()));
+        service
+    }
+
+    fn sign_out(cognito: MockAuthenticationService, google: MockAuthenticationService) -> SignOut {
+        let mut registry = AuthenticationServiceRegistry::new(
+            "https://cognito.example.com",
+            Arc::new(cognito),
+            unverified_issuer,
+        );
+        registry
+            .register("https://accounts.google.com", Arc::new(google), false)
+            .unwrap();
+        SignOut::new(
+            Arc::new(registry),
+            Arc::new(MockSessionRepo::new()),
+            Arc::new(TokenDenylist::new(
+                Arc::new(InMemoryRevokedTokenRepository::new()),
+                chrono::Duration::hours(1),
+            )),
+        )
+    }
+
+    fn input(provider: &str) -> SignOutInputDTO {
+        SignOutInputDTO {
+            user_id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            jti: None,
+            origin_jti: None,
+            expires_at: None,
+            refresh_token: Some("refresh-token".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_revokes_refresh_token_at_caller_provider() {
+        let sign_out = sign_out(service("cognito", 0), service("google", 1));
+
+        let result = sign_out.execute(input("google")).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_with_unknown_provider() {
+        let sign_out = sign_out(service("cognito", 0), service("google", 0));
+
+        let result = sign_out.execute(input("")).await;
+
+        assert!(matches!(result, Err(ApplicationError::InternalError(_))));
+    }
+}
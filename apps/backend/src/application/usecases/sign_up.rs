@@ -1,12 +1,16 @@
-use anyhow::Result;
+use crate::application::errors::ApplicationResult;
 use std::sync::Arc;
 
 use crate::{
-    application::dtos::{authentication_dto::SignUpInputDTO, identity_link_dto::IdentityLinkDto},
+    application::dtos::{
+        authentication_dto::{SignUpInputDTO, SignUpOutputDTO},
+        identity_link_dto::IdentityLinkDto,
+    },
     domain::{
-        entities::{identity_link::NewIdentityLink, user::NewUser},
+        entities::{sign_up_compensation::NewSignUpCompensation, user::NewUser},
         repositories::{
-            identity_link_repository::IdentityLinkRepository, user_repository::UserRepository,
+            sign_up_compensation_repository::SignUpCompensationRepository,
+            user_repository::UserRepository,
         },
         services::authentication_service::AuthenticationService,
     },
@@ -15,94 +19,127 @@ use crate::{
 pub struct SignUp {
     authentication_service: Arc<dyn AuthenticationService>,
     user_repository: Arc<dyn UserRepository>,
-    identity_link_repository: Arc<dyn IdentityLinkRepository>,
+    sign_up_compensation_repository: Arc<dyn SignUpCompensationRepository>,
 }
 
 impl SignUp {
     pub fn new(
         authentication_service: Arc<dyn AuthenticationService>,
         user_repository: Arc<dyn UserRepository>,
-        identity_link_repository: Arc<dyn IdentityLinkRepository>,
+        sign_up_compensation_repository: Arc<dyn SignUpCompensationRepository>,
     ) -> Self {
         Self {
             authentication_service,
             user_repository,
-            identity_link_repository,
+            sign_up_compensation_repository,
         }
     }
 
-    pub async fn execute(&self, input: SignUpInputDTO) -> Result<IdentityLinkDto> {
+    pub async fn execute(&self, input: SignUpInputDTO) -> ApplicationResult<SignUpOutputDTO> {
         let output = self
             .authentication_service
             .sign_up(&input.email, &input.password)
             .await?;
-        let user = self.user_repository.create(NewUser::from(input)).await?;
-        let identity_link = self
-            .identity_link_repository
-            .create(NewIdentityLink {
-                provider: self.authentication_service.provider_name(),
-                sub: output.user_sub,
-                user_id: user.id,
-            })
-            .await?;
-        Ok(IdentityLinkDto::from(identity_link))
+        let email = input.email.clone();
+        let provider = self.authentication_service.provider_name();
+        let result = self
+            .user_repository
+            .create_with_identity_link(
+                NewUser::from(input),
+                provider.clone(),
+                output.user_sub.clone(),
+            )
+            .await;
+        match result {
+            Ok((_, identity_link)) => Ok(SignUpOutputDTO {
+                identity_link: IdentityLinkDto::from(identity_link),
+                user_confirmed: output.user_confirmed,
+                session: output.session,
+            }),
+            Err(error) => {
+                self.compensate(provider, email, output.user_sub).await;
+                Err(error.into())
+            }
+        }
+    }
+
+    // DBへの保存に失敗した場合、認証プロバイダー側のユーザーを削除して同じメールで再登録できるようにする
+    // 削除にも失敗した場合は、後で再試行できるように記録しておく
+    async fn compensate(&self, provider: String, username: String, sub: String) {
+        let Err(error) = self.authentication_service.delete_user(&username).await else {
+            return;
+        };
+        tracing::error!(
+            "Failed to delete {} user {} after sign up failure: {}",
+            provider,
+            sub,
+            error
+        );
+        let compensation = NewSignUpCompensation {
+            provider,
+            username,
+            sub,
+            last_error: error.to_string(),
+        };
+        if let Err(error) = self
+            .sign_up_compensation_repository
+            .create(compensation)
+            .await
+        {
+            tracing::error!("Failed to record sign up compensation: {}", error);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::application::dtos::{
-        authentication_dto::SignUpInputDTO,
-    };
+    use crate::application::dtos::authentication_dto::SignUpInputDTO;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
     use crate::domain::{
-        entities::{identity_link::IdentityLink, user::User},
+        entities::{
+            identity_link::IdentityLink,
+            sign_up_compensation::{NewSignUpCompensation, SignUpCompensation},
+            user::User,
+        },
+        enums::user_role::UserRole,
+        errors::{AuthenticationError, RepositoryError, RepositoryResult},
         repositories::{
-            identity_link_repository::IdentityLinkRepository, user_repository::UserRepository,
+            sign_up_compensation_repository::SignUpCompensationRepository,
+            user_repository::UserRepository,
         },
-        services::authentication_service::AuthenticationService,
         value_objects::authentication::SignUpOutput,
     };
-    use anyhow::Result;
     use async_trait::async_trait;
     use mockall::mock;
     use std::sync::Arc;
     use uuid::Uuid;
 
     // Mock implementations
-    mock! {
-        AuthService {}
-
-        #[async_trait]
-        impl AuthenticationService for AuthService {
-            fn provider_name(&self) -> String;
-            async fn sign_up(&self, email: &str, password: &str) -> Result<SignUpOutput>;
-            async fn sign_in(&self, email: &str, password: &str) -> Result<crate::domain::value_objects::authentication::SignInOutput>;
-            async fn sign_out(&self, access_token: &str) -> Result<()>;
-            async fn verify_token(&self, access_token: &str) -> Result<crate::domain::value_objects::authentication::Claims>;
-        }
-    }
-
     mock! {
         UserRepo {}
 
         #[async_trait]
         impl UserRepository for UserRepo {
-            async fn create(&self, user: crate::domain::entities::user::NewUser) -> Result<User>;
-            async fn find_by_id(&self, id: Uuid) -> Result<Option<User>>;
-            async fn find_all(&self) -> Result<Vec<User>>;
-            async fn update(&self, user: User) -> Result<User>;
-            async fn delete(&self, id: Uuid) -> Result<()>;
+            async fn create(&self, user: crate::domain::entities::user::NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: crate::domain::entities::user::NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: crate::domain::entities::user::UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
         }
     }
 
     mock! {
-        IdentityLinkRepo {}
+        CompensationRepo {}
 
         #[async_trait]
-        impl IdentityLinkRepository for IdentityLinkRepo {
-            async fn create(&self, new_identity_link: crate::domain::entities::identity_link::NewIdentityLink) -> Result<IdentityLink>;
-            async fn find_by_sub(&self, sub: &str) -> Result<IdentityLink>;
+        impl SignUpCompensationRepository for CompensationRepo {
+            async fn create(&self, compensation: NewSignUpCompensation) -> RepositoryResult<SignUpCompensation>;
+            async fn find_unresolved(&self) -> RepositoryResult<Vec<SignUpCompensation>>;
+            async fn mark_resolved(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn record_failure(&self, id: Uuid, error: &str) -> RepositoryResult<()>;
         }
     }
 
@@ -110,6 +147,8 @@ mod tests {
         User {
             id: Uuid::new_v4(),
             name: "Test User".to_string(),
+            role: UserRole::User,
+            mfa_enabled: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             identity_links: Vec::new(),
@@ -130,9 +169,9 @@ mod tests {
     #[tokio::test]
     async fn test_sign_up_success() {
         // Arrange
-        let mut mock_auth_service = MockAuthService::new();
+        let mut mock_auth_service = MockAuthenticationService::new();
         let mut mock_user_repo = MockUserRepo::new();
-        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        let mock_compensation_repo = MockCompensationRepo::new();
 
         let test_user = create_test_user();
         let test_identity_link = create_test_identity_link(test_user.id);
@@ -159,22 +198,22 @@ mod tests {
             .returning(|| "cognito".to_string());
 
         let user_clone = test_user.clone();
-        mock_user_repo
-            .expect_create()
-            .times(1)
-            .returning(move |_| Ok(user_clone.clone()));
-
         let identity_link_clone = test_identity_link.clone();
-        mock_identity_link_repo
-            .expect_create()
+        mock_user_repo
+            .expect_create_with_identity_link()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq("cognito".to_string()),
+                mockall::predicate::eq("test-sub-123".to_string()),
+            )
             .times(1)
-            .returning(move |_| Ok(identity_link_clone.clone()));
+            .returning(move |_, _, _| Ok((user_clone.clone(), identity_link_clone.clone())));
 
         // Create sign_up use case
         let sign_up = SignUp::new(
             Arc::new(mock_auth_service),
             Arc::new(mock_user_repo),
-            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_compensation_repo),
         );
 
         let input = SignUpInputDTO {
@@ -188,18 +227,19 @@ mod tests {
 
         // Assert
         assert!(result.is_ok());
-        let identity_link_dto = result.unwrap();
-        assert_eq!(identity_link_dto.provider, "cognito");
-        assert_eq!(identity_link_dto.sub, "test-sub-123");
-        assert_eq!(identity_link_dto.user_id, test_user.id);
+        let output_dto = result.unwrap();
+        assert_eq!(output_dto.identity_link.provider, "cognito");
+        assert_eq!(output_dto.identity_link.sub, "test-sub-123");
+        assert_eq!(output_dto.identity_link.user_id, test_user.id);
+        assert!(!output_dto.user_confirmed);
     }
 
     #[tokio::test]
     async fn test_sign_up_authentication_service_failure() {
         // Arrange
-        let mut mock_auth_service = MockAuthService::new();
+        let mut mock_auth_service = MockAuthenticationService::new();
         let mock_user_repo = MockUserRepo::new();
-        let mock_identity_link_repo = MockIdentityLinkRepo::new();
+        let mock_compensation_repo = MockCompensationRepo::new();
 
         // Setup mock expectations for authentication failure
         mock_auth_service
@@ -209,13 +249,17 @@ mod tests {
                 mockall::predicate::eq("password123"),
             )
             .times(1)
-            .returning(|_, _| Err(anyhow::anyhow!("Authentication service error")));
+            .returning(|_, _| {
+                Err(AuthenticationError::Provider(
+                    "Authentication service error".to_string(),
+                ))
+            });
 
         // Create sign_up use case
         let sign_up = SignUp::new(
             Arc::new(mock_auth_service),
             Arc::new(mock_user_repo),
-            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_compensation_repo),
         );
 
         let input = SignUpInputDTO {
@@ -229,18 +273,20 @@ mod tests {
 
         // Assert
         assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Authentication service error"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Authentication service error")
+        );
     }
 
     #[tokio::test]
-    async fn test_sign_up_user_repository_failure() {
+    async fn test_sign_up_deletes_provider_user_when_persistence_fails() {
         // Arrange
-        let mut mock_auth_service = MockAuthService::new();
+        let mut mock_auth_service = MockAuthenticationService::new();
         let mut mock_user_repo = MockUserRepo::new();
-        let mock_identity_link_repo = MockIdentityLinkRepo::new();
+        let mut mock_compensation_repo = MockCompensationRepo::new();
 
         // Setup mock expectations
         mock_auth_service
@@ -258,17 +304,30 @@ mod tests {
                 })
             });
 
+        mock_auth_service
+            .expect_provider_name()
+            .times(1)
+            .returning(|| "cognito".to_string());
+
         // Setup user repository failure
         mock_user_repo
-            .expect_create()
+            .expect_create_with_identity_link()
+            .times(1)
+            .returning(|_, _, _| Err(RepositoryError::Database("Database error".to_string())));
+
+        // The provider user is deleted, so nothing is left to record
+        mock_auth_service
+            .expect_delete_user()
+            .with(mockall::predicate::eq("test@example.com"))
             .times(1)
-            .returning(|_| Err(anyhow::anyhow!("Database error")));
+            .returning(|_| Ok(()));
+        mock_compensation_repo.expect_create().times(0);
 
         // Create sign_up use case
         let sign_up = SignUp::new(
             Arc::new(mock_auth_service),
             Arc::new(mock_user_repo),
-            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_compensation_repo),
         );
 
         let input = SignUpInputDTO {
@@ -286,21 +345,15 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_sign_up_identity_link_repository_failure() {
+    async fn test_sign_up_records_compensation_when_delete_fails() {
         // Arrange
-        let mut mock_auth_service = MockAuthService::new();
+        let mut mock_auth_service = MockAuthenticationService::new();
         let mut mock_user_repo = MockUserRepo::new();
-        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
-
-        let test_user = create_test_user();
+        let mut mock_compensation_repo = MockCompensationRepo::new();
 
         // Setup mock expectations
         mock_auth_service
             .expect_sign_up()
-            .with(
-                mockall::predicate::eq("test@example.com"),
-                mockall::predicate::eq("password123"),
-            )
             .times(1)
             .returning(|_, _| {
                 Ok(SignUpOutput {
@@ -315,23 +368,52 @@ mod tests {
             .times(1)
             .returning(|| "cognito".to_string());
 
-        let user_clone = test_user.clone();
         mock_user_repo
-            .expect_create()
+            .expect_create_with_identity_link()
+            .times(1)
+            .returning(|_, _, _| {
+                Err(RepositoryError::Database(
+                    "Identity link creation failed".to_string(),
+                ))
+            });
+
+        mock_auth_service
+            .expect_delete_user()
             .times(1)
-            .returning(move |_| Ok(user_clone.clone()));
+            .returning(|_| {
+                Err(AuthenticationError::Provider(
+                    "Cognito unavailable".to_string(),
+                ))
+            });
 
-        // Setup identity link repository failure
-        mock_identity_link_repo
+        mock_compensation_repo
             .expect_create()
+            .withf(|compensation| {
+                compensation.provider == "cognito"
+                    && compensation.username == "test@example.com"
+                    && compensation.sub == "test-sub-123"
+                    && compensation.last_error.contains("Cognito unavailable")
+            })
             .times(1)
-            .returning(|_| Err(anyhow::anyhow!("Identity link creation failed")));
+            .returning(|compensation| {
+                Ok(SignUpCompensation {
+                    id: Uuid::new_v4(),
+                    provider: compensation.provider,
+                    username: compensation.username,
+                    sub: compensation.sub,
+                    attempts: 1,
+                    last_error: compensation.last_error,
+                    resolved_at: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+            });
 
         // Create sign_up use case
         let sign_up = SignUp::new(
             Arc::new(mock_auth_service),
             Arc::new(mock_user_repo),
-            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_compensation_repo),
         );
 
         let input = SignUpInputDTO {
@@ -345,9 +427,11 @@ mod tests {
 
         // Assert
         assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Identity link creation failed"));
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Identity link creation failed")
+        );
     }
 }
@@ -0,0 +1,381 @@
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        dtos::authentication_dto::{
+            AuthenticationTokensDTO, ClientInfoDTO, SignInOutputDTO, VerifySignInCodeInputDTO,
+        },
+        usecases::sign_in::SignIn,
+    },
+    domain::{
+        errors::{AuthenticationError, RepositoryError},
+        repositories::sign_in_code_request_repository::SignInCodeRequestRepository,
+        services::authentication_service::AuthenticationService,
+        value_objects::authentication::{SignInCodeVerification, SignInOutput},
+    },
+};
+
+// 1つのコードに対して受け付ける誤ったコードの入力回数
+const MAX_SIGN_IN_CODE_ATTEMPTS: i32 = 3;
+
+// メールで受け取ったワンタイムコードを検証し、サインインを完了する
+pub struct VerifySignInCode {
+    authentication_service: Arc<dyn AuthenticationService>,
+    sign_in_code_request_repository: Arc<dyn SignInCodeRequestRepository>,
+    sign_in: Arc<SignIn>,
+}
+
+impl VerifySignInCode {
+    pub fn new(
+        authentication_service: Arc<dyn AuthenticationService>,
+        sign_in_code_request_repository: Arc<dyn SignInCodeRequestRepository>,
+        sign_in: Arc<SignIn>,
+    ) -> Self {
+        Self {
+            authentication_service,
+            sign_in_code_request_repository,
+            sign_in,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: VerifySignInCodeInputDTO,
+        client: ClientInfoDTO,
+    ) -> ApplicationResult<AuthenticationTokensDTO> {
+        // 期限切れで削除された要求や使用済みの要求は、期限切れと同じように扱う
+        // プロバイダーに問い合わせる前に試行回数を確保し、同時に送られたコードも上限までしか検証しない
+        let request = self
+            .sign_in_code_request_repository
+            .start_attempt(input.session, MAX_SIGN_IN_CODE_ATTEMPTS, Utc::now())
+            .await?
+            .ok_or_else(expired_code)?;
+
+        let verification = match request.provider_session.clone() {
+            Some(provider_session) => {
+                self.authentication_service
+                    .verify_sign_in_code(&request.email, Some(provider_session), &input.code)
+                    .await?
+            }
+            // 存在しないユーザーへの要求
+            None => SignInCodeVerification::Retry { session: None },
+        };
+
+        let tokens = match verification {
+            SignInCodeVerification::Authenticated(tokens) => tokens,
+            SignInCodeVerification::Retry { session } => {
+                self.sign_in_code_request_repository
+                    .update_provider_session(request.id, session)
+                    .await?;
+                if request.attempts >= MAX_SIGN_IN_CODE_ATTEMPTS {
+                    self.consume(request.id).await?;
+                    return Err(ApplicationError::Authentication(
+                        AuthenticationError::TooManyRequests(
+                            "Too many incorrect sign in codes, please request a new code"
+                                .to_string(),
+                        ),
+                    ));
+                }
+                return Err(ApplicationError::Authentication(
+                    AuthenticationError::CodeMismatch {
+                        parameter: "code",
+                        message: "Invalid sign in code provided, please try again.".to_string(),
+                    },
+                ));
+            }
+        };
+        // 同じセッションでのサインインを一度だけにする
+        self.consume(request.id).await?;
+
+        // セッションの記録はパスワードでのサインインと共通
+        match self
+            .sign_in
+            .complete(SignInOutput::Authenticated(tokens), client)
+            .await?
+        {
+            SignInOutputDTO::Authenticated(tokens) => Ok(tokens),
+            SignInOutputDTO::Challenge(challenge) => Err(ApplicationError::InternalError(format!(
+                "Unexpected challenge after sign in code verification: {}",
+                challenge.name
+            ))),
+        }
+    }
+
+    async fn consume(&self, id: Uuid) -> ApplicationResult<()> {
+        match self.sign_in_code_request_repository.consume(id).await {
+            Ok(()) => Ok(()),
+            // 同時に送られた別のリクエストで使用済みになった
+            Err(RepositoryError::NotFound(_)) => Err(expired_code()),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+fn expired_code() -> ApplicationError {
+    ApplicationError::Authentication(AuthenticationError::ExpiredCode {
+        parameter: "session",
+        message: "Sign in code has expired, please request a new code.".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+    use crate::domain::value_objects::authentication::AuthenticationTokens;
+    use crate::domain::{
+        entities::{
+            identity_link::{IdentityLink, NewIdentityLink},
+            session::{NewSession, Session},
+            sign_in_code_request::{NewSignInCodeRequest, SignInCodeRequest},
+            user::{NewUser, User, UserChanges},
+        },
+        errors::RepositoryResult,
+        repositories::{
+            identity_link_repository::IdentityLinkRepository,
+            session_repository::SessionRepository, user_repository::UserRepository,
+        },
+    };
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration};
+    use mockall::mock;
+
+    use std::sync::Mutex;
+
+    mock! {
+        SignInCodeRequestRepo {}
+
+        #[async_trait]
+        impl SignInCodeRequestRepository for SignInCodeRequestRepo {
+            async fn create(&self, request: NewSignInCodeRequest) -> RepositoryResult<SignInCodeRequest>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn count_by_email_since(&self, email: &str, since: DateTime<Utc>) -> RepositoryResult<u64>;
+            async fn start_attempt(&self, id: Uuid, max_attempts: i32, now: DateTime<Utc>) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn update_provider_session(&self, id: Uuid, provider_session: Option<String>) -> RepositoryResult<()>;
+            async fn consume(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn delete_created_before(&self, before: DateTime<Utc>) -> RepositoryResult<u64>;
+        }
+    }
+
+    mock! {
+        UserRepo {}
+
+        #[async_trait]
+        impl UserRepository for UserRepo {
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
+        }
+    }
+
+    mock! {
+        IdentityLinkRepo {}
+
+        #[async_trait]
+        impl IdentityLinkRepository for IdentityLinkRepo {
+            async fn create(&self, identity_link: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+            async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+            async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+            async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        SessionRepo {}
+
+        #[async_trait]
+        impl SessionRepository for SessionRepo {
+            async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+            async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn test_request(id: Uuid, attempts: i32, expires_at: DateTime<Utc>) -> SignInCodeRequest {
+        SignInCodeRequest {
+            id,
+            email: "user@example.com".to_string(),
+            provider_session: Some(format!("provider-session-{}", attempts)),
+            attempts,
+            expires_at,
+            consumed_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    // サインインの完了まで進まないテストでは、SignInのリポジトリは呼ばれない
+    fn use_case(
+        auth_service: MockAuthenticationService,
+        sign_in_code_request_repo: MockSignInCodeRequestRepo,
+    ) -> VerifySignInCode {
+        let auth_service: Arc<dyn AuthenticationService> = Arc::new(auth_service);
+        let sign_in = Arc::new(SignIn::new(
+            auth_service.clone(),
+            Arc::new(MockIdentityLinkRepo::new()),
+            Arc::new(MockUserRepo::new()),
+            Arc::new(MockSessionRepo::new()),
+        ));
+        VerifySignInCode::new(auth_service, Arc::new(sign_in_code_request_repo), sign_in)
+    }
+
+    fn input(session: Uuid) -> VerifySignInCodeInputDTO {
+        VerifySignInCodeInputDTO {
+            session,
+            code: "000000".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wrong_codes_are_limited_per_request() {
+        let id = Uuid::new_v4();
+        let stored = Arc::new(Mutex::new(test_request(
+            id,
+            0,
+            Utc::now() + Duration::minutes(3),
+        )));
+
+        let mut auth_service = MockAuthenticationService::new();
+        let issued: Arc<Mutex<i32>> = Arc::default();
+        auth_service
+            .expect_verify_sign_in_code()
+            .withf(|email, _, code| email == "user@example.com" && code == "000000")
+            .times(MAX_SIGN_IN_CODE_ATTEMPTS as usize)
+            .returning(move |_, session, _| {
+                // 前回の誤りで発行し直されたセッションで検証する
+                let mut issued = issued.lock().unwrap();
+                assert_eq!(session, Some(format!("provider-session-{}", *issued)));
+                *issued += 1;
+                Ok(SignInCodeVerification::Retry {
+                    session: Some(format!("provider-session-{}", *issued)),
+                })
+            });
+
+        // 条件の確認と加算を1つのUPDATEで行うリポジトリと同じように振る舞う
+        let mut mock_repo = MockSignInCodeRequestRepo::new();
+        let started = stored.clone();
+        mock_repo
+            .expect_start_attempt()
+            .withf(move |session, max_attempts, _| {
+                *session == id && *max_attempts == MAX_SIGN_IN_CODE_ATTEMPTS
+            })
+            .returning(move |_, max_attempts, _| {
+                let mut request = started.lock().unwrap();
+                if request.is_consumed() || request.attempts >= max_attempts {
+                    return Ok(None);
+                }
+                request.attempts += 1;
+                Ok(Some(request.clone()))
+            });
+        let updated = stored.clone();
+        mock_repo
+            .expect_update_provider_session()
+            .returning(move |_, session| {
+                updated.lock().unwrap().provider_session = session;
+                Ok(())
+            });
+        let consumed = stored.clone();
+        mock_repo.expect_consume().times(1).returning(move |_| {
+            consumed.lock().unwrap().consumed_at = Some(Utc::now());
+            Ok(())
+        });
+        let use_case = use_case(auth_service, mock_repo);
+
+        for _ in 1..MAX_SIGN_IN_CODE_ATTEMPTS {
+            let result = use_case.execute(input(id), ClientInfoDTO::default()).await;
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authentication(
+                    AuthenticationError::CodeMismatch { .. }
+                ))
+            ));
+        }
+        // 上限に達したコードは使用済みにして、新しいコードを要求させる
+        let result = use_case.execute(input(id), ClientInfoDTO::default()).await;
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::TooManyRequests(_)
+            ))
+        ));
+        // それ以降のコードはプロバイダーに問い合わせない
+        let result = use_case.execute(input(id), ClientInfoDTO::default()).await;
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::ExpiredCode { .. }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_request_is_not_verified() {
+        // 期限切れや使用済みの要求ではプロバイダーに問い合わせない
+        let mut mock_repo = MockSignInCodeRequestRepo::new();
+        mock_repo
+            .expect_start_attempt()
+            .returning(|_, _, _| Ok(None));
+        let use_case = use_case(MockAuthenticationService::new(), mock_repo);
+
+        let result = use_case
+            .execute(input(Uuid::new_v4()), ClientInfoDTO::default())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::ExpiredCode { .. }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_code_verified_concurrently_signs_in_once() {
+        // 同時に送られた正しいコードのうち、使用済みにできなかった方はサインインさせない
+        let mut auth_service = MockAuthenticationService::new();
+        auth_service
+            .expect_verify_sign_in_code()
+            .returning(|_, _, _| {
+                Ok(SignInCodeVerification::Authenticated(
+                    AuthenticationTokens {
+                        id_token: "id-token".to_string(),
+                        access_token: "access-token".to_string(),
+                        refresh_token: "refresh-token".to_string(),
+                        expires_in: 3600,
+                    },
+                ))
+            });
+        let mut mock_repo = MockSignInCodeRequestRepo::new();
+        mock_repo
+            .expect_start_attempt()
+            .returning(|id, _, _| Ok(Some(test_request(id, 1, Utc::now() + Duration::minutes(3)))));
+        mock_repo.expect_consume().returning(|_| {
+            Err(RepositoryError::NotFound(
+                "sign in code request".to_string(),
+            ))
+        });
+        let use_case = use_case(auth_service, mock_repo);
+
+        let result = use_case
+            .execute(input(Uuid::new_v4()), ClientInfoDTO::default())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authentication(
+                AuthenticationError::ExpiredCode { .. }
+            ))
+        ));
+    }
+}
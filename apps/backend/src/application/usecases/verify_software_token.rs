@@ -0,0 +1,30 @@
+use crate::application::errors::ApplicationResult;
+use std::sync::Arc;
+
+use crate::{
+    application::dtos::authentication_dto::VerifySoftwareTokenInputDTO,
+    domain::services::authentication_service::AuthenticationService,
+};
+
+pub struct VerifySoftwareToken {
+    authentication_service: Arc<dyn AuthenticationService>,
+}
+
+impl VerifySoftwareToken {
+    pub fn new(authentication_service: Arc<dyn AuthenticationService>) -> Self {
+        Self {
+            authentication_service,
+        }
+    }
+
+    pub async fn execute(&self, input: VerifySoftwareTokenInputDTO) -> ApplicationResult<()> {
+        self.authentication_service
+            .verify_software_token(
+                &input.access_token,
+                &input.user_code,
+                input.friendly_device_name,
+            )
+            .await?;
+        Ok(())
+    }
+}
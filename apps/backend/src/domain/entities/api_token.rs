@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue;
+use uuid::Uuid;
+
+use crate::infrastructure::database::models::api_token;
+
+// 作成するパーソナルアクセストークン（平文は含めない）
+#[derive(Debug, Clone)]
+pub struct NewApiToken {
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<NewApiToken> for api_token::ActiveModel {
+    fn from(token: NewApiToken) -> Self {
+        api_token::ActiveModel {
+            id: ActiveValue::NotSet,
+            user_id: ActiveValue::Set(token.user_id),
+            name: ActiveValue::Set(token.name),
+            token_prefix: ActiveValue::Set(token.token_prefix),
+            token_hash: ActiveValue::Set(token.token_hash),
+            scopes: ActiveValue::Set(token.scopes.join(" ")),
+            expires_at: ActiveValue::Set(token.expires_at),
+            last_used_at: ActiveValue::Set(None),
+            revoked_at: ActiveValue::Set(None),
+            ..Default::default()
+        }
+    }
+}
+
+// バッチ処理などの機械的なクライアントが、パスワードの代わりに使う資格情報
+#[derive(Debug, Clone)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    // 許可する権限名
+    pub scopes: Vec<String>,
+    // Noneの場合は無期限
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ApiToken {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
@@ -2,6 +2,13 @@
 // 例: ユーザー、製品、注文など
 
 // エンティティのモジュール
+pub mod api_token;
 pub mod identity_link;
+pub mod permission;
+pub mod revoked_token;
+pub mod role;
+pub mod session;
+pub mod sign_in_code_request;
+pub mod sign_up_compensation;
 pub mod token_set;
 pub mod user;
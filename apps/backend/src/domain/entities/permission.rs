@@ -0,0 +1,9 @@
+use uuid::Uuid;
+
+// "users:delete"のように「リソース:操作」の形式で名前を付ける
+#[derive(Debug, Clone)]
+pub struct Permission {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue;
+
+use crate::infrastructure::database::models::revoked_token;
+
+// サインアウトなどで失効させたアクセストークンのID（jtiまたはorigin_jti）
+#[derive(Debug, Clone)]
+pub struct RevokedToken {
+    pub jti: String,
+    // 元のトークンが期限切れになる時刻。これ以降は記録を削除してよい
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<RevokedToken> for revoked_token::ActiveModel {
+    fn from(token: RevokedToken) -> Self {
+        revoked_token::ActiveModel {
+            id: ActiveValue::NotSet,
+            jti: ActiveValue::Set(token.jti),
+            expires_at: ActiveValue::Set(token.expires_at),
+            ..Default::default()
+        }
+    }
+}
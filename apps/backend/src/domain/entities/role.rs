@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue;
+use uuid::Uuid;
+
+use crate::infrastructure::database::models::role;
+
+#[derive(Debug, Clone)]
+pub struct NewRole {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+impl From<NewRole> for role::ActiveModel {
+    fn from(role: NewRole) -> Self {
+        role::ActiveModel {
+            id: ActiveValue::NotSet,
+            name: ActiveValue::Set(role.name),
+            description: ActiveValue::Set(role.description),
+            ..Default::default()
+        }
+    }
+}
+
+// ユーザーに割り当てる権限の集合
+#[derive(Debug, Clone)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue;
+use uuid::Uuid;
+
+use crate::infrastructure::database::models::session;
+
+// サインイン時に記録する端末の情報
+#[derive(Debug, Clone)]
+pub struct NewSession {
+    pub user_id: Uuid,
+    pub origin_jti: String,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl From<NewSession> for session::ActiveModel {
+    fn from(session: NewSession) -> Self {
+        session::ActiveModel {
+            id: ActiveValue::NotSet,
+            user_id: ActiveValue::Set(session.user_id),
+            origin_jti: ActiveValue::Set(session.origin_jti),
+            device: ActiveValue::Set(session.device),
+            ip_address: ActiveValue::Set(session.ip_address),
+            user_agent: ActiveValue::Set(session.user_agent),
+            revoked_at: ActiveValue::Set(None),
+            ..Default::default()
+        }
+    }
+}
+
+// サインイン1回分のセッション。リフレッシュで発行されたトークンもorigin_jtiで紐づく
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub origin_jti: String,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub last_used_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
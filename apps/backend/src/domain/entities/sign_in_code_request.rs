@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue;
+use uuid::Uuid;
+
+use crate::infrastructure::database::models::sign_in_code_request;
+
+// 開始したワンタイムコードでのサインイン
+#[derive(Debug, Clone)]
+pub struct NewSignInCodeRequest {
+    pub email: String,
+    pub provider_session: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<NewSignInCodeRequest> for sign_in_code_request::ActiveModel {
+    fn from(request: NewSignInCodeRequest) -> Self {
+        sign_in_code_request::ActiveModel {
+            id: ActiveValue::NotSet,
+            email: ActiveValue::Set(request.email),
+            provider_session: ActiveValue::Set(request.provider_session),
+            attempts: ActiveValue::Set(0),
+            expires_at: ActiveValue::Set(request.expires_at),
+            consumed_at: ActiveValue::Set(None),
+            ..Default::default()
+        }
+    }
+}
+
+// メールで送ったワンタイムコードでのサインインの進行状況
+// idをクライアントに渡すセッションとし、プロバイダーのセッションはクライアントに渡さない
+#[derive(Debug, Clone)]
+pub struct SignInCodeRequest {
+    pub id: Uuid,
+    pub email: String,
+    pub provider_session: Option<String>,
+    // 誤ったコードを入力した回数
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    // サインインの完了や試行回数の超過で使えなくなった日時
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SignInCodeRequest {
+    pub fn is_consumed(&self) -> bool {
+        self.consumed_at.is_some()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue;
+use uuid::Uuid;
+
+use crate::infrastructure::database::models::sign_up_compensation;
+
+// サインアップ時のDB保存に失敗し、認証プロバイダー側のユーザー削除もできなかった記録
+#[derive(Debug, Clone)]
+pub struct NewSignUpCompensation {
+    pub provider: String,
+    pub username: String,
+    pub sub: String,
+    pub last_error: String,
+}
+
+impl From<NewSignUpCompensation> for sign_up_compensation::ActiveModel {
+    fn from(compensation: NewSignUpCompensation) -> Self {
+        sign_up_compensation::ActiveModel {
+            id: ActiveValue::NotSet,
+            provider: ActiveValue::Set(compensation.provider),
+            username: ActiveValue::Set(compensation.username),
+            sub: ActiveValue::Set(compensation.sub),
+            attempts: ActiveValue::Set(1),
+            last_error: ActiveValue::Set(compensation.last_error),
+            resolved_at: ActiveValue::Set(None),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignUpCompensation {
+    pub id: Uuid,
+    pub provider: String,
+    pub username: String,
+    pub sub: String,
+    pub attempts: i32,
+    pub last_error: String,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -18,7 +18,10 @@ pub struct NewUser {
 
 impl From<SignUpInputDTO> for NewUser {
     fn from(input: SignUpInputDTO) -> Self {
-        Self { name: input.name }
+        Self {
+            name: input.name,
+            role: UserRole::default(),
+        }
     }
 }
 
@@ -27,6 +30,7 @@ impl From<NewUser> for user::ActiveModel {
         user::ActiveModel {
             id: ActiveValue::NotSet,
             name: ActiveValue::Set(user.name),
+            role: ActiveValue::Set(user.role.to_string()),
             ..Default::default()
         }
     }
@@ -36,6 +40,8 @@ impl From<NewUser> for user::ActiveModel {
 pub struct User {
     pub id: Uuid,
     pub name: String,
+    pub role: UserRole,
+    pub mfa_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub identity_links: Vec<IdentityLink>,
@@ -43,15 +49,32 @@ pub struct User {
 
 impl From<CreateUserDto> for NewUser {
     fn from(input: CreateUserDto) -> Self {
-        Self { name: input.name }
+        Self {
+            name: input.name,
+            role: UserRole::default(),
+        }
     }
 }
 
-impl From<User> for user::ActiveModel {
-    fn from(user: User) -> user::ActiveModel {
+// ユーザーの更新内容（Noneの項目は変更しない）
+// 読み込んだ時点のUserをそのまま保存すると、並行して変更されたロールなどを古い値で上書きしてしまう
+#[derive(Debug, Clone, Default)]
+pub struct UserChanges {
+    pub name: Option<String>,
+    pub role: Option<UserRole>,
+    pub mfa_enabled: Option<bool>,
+}
+
+impl From<UserChanges> for user::ActiveModel {
+    fn from(changes: UserChanges) -> user::ActiveModel {
+        fn set_if_some<T: Into<sea_orm::Value>>(value: Option<T>) -> ActiveValue<T> {
+            value.map_or(ActiveValue::NotSet, ActiveValue::Set)
+        }
+
         user::ActiveModel {
-            id: ActiveValue::Set(user.id),
-            name: ActiveValue::Set(user.name.clone()),
+            name: set_if_some(changes.name),
+            role: set_if_some(changes.role.map(|role| role.to_string())),
+            mfa_enabled: set_if_some(changes.mfa_enabled),
             ..Default::default()
         }
     }
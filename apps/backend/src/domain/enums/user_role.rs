@@ -1,17 +1,14 @@
+use async_graphql::Enum;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+// ユーザーのロール（usersテーブルのrole列に小文字の文字列で保存する）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
 pub enum UserRole {
+    #[default]
     User,
     Admin,
 }
 
-impl Default for UserRole {
-    fn default() -> Self {
-        UserRole::User
-    }
-}
-
 impl UserRole {
     pub fn is_admin(&self) -> bool {
         matches!(self, UserRole::Admin)
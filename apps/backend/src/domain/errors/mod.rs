@@ -0,0 +1,118 @@
+use thiserror::Error;
+
+// リポジトリ（永続化層）のエラー定義
+#[derive(Debug, Error)]
+pub enum RepositoryError {
+    // 見つからなかったエンティティの種類
+    #[error("{0} not found")]
+    NotFound(String),
+
+    // 一意制約違反など、既存のデータと競合した
+    #[error("conflict: {0}")]
+    Conflict(String),
+
+    #[error("database error: {0}")]
+    Database(String),
+}
+
+// 認証プロバイダーのエラー定義
+// parameterには、エラーの原因となった認証サービスの引数名を保持する
+#[derive(Debug, Error)]
+pub enum AuthenticationError {
+    // トークンの署名・有効期限・用途などが不正
+    #[error("invalid token: {0}")]
+    InvalidToken(String),
+
+    // 署名は正しいが有効期限が切れている（クライアントはトークンを更新すればよい）
+    #[error("expired token: {0}")]
+    ExpiredToken(String),
+
+    // 資格情報の不一致など、認証に失敗した
+    #[error("not authorized: {0}")]
+    NotAuthorized(String),
+
+    #[error("user not found: {0}")]
+    UserNotFound(String),
+
+    #[error("username exists: {0}")]
+    UsernameExists(String),
+
+    // サインアップの確認が済んでいない
+    #[error("user not confirmed: {0}")]
+    UserNotConfirmed(String),
+
+    #[error("password reset required: {0}")]
+    PasswordResetRequired(String),
+
+    // パスワードがポリシーや履歴の条件を満たさない
+    #[error("invalid password ({parameter}): {message}")]
+    InvalidPassword {
+        parameter: &'static str,
+        message: String,
+    },
+
+    #[error("code mismatch ({parameter}): {message}")]
+    CodeMismatch {
+        parameter: &'static str,
+        message: String,
+    },
+
+    #[error("expired code ({parameter}): {message}")]
+    ExpiredCode {
+        parameter: &'static str,
+        message: String,
+    },
+
+    // リクエスト数や試行回数の上限に達した
+    #[error("too many requests: {0}")]
+    TooManyRequests(String),
+
+    // 確認コードやチャレンジなど、リクエストの内容が不正
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    #[error("unsupported operation: {0}")]
+    Unsupported(String),
+
+    // 認証プロバイダーとの通信失敗など、クライアントに詳細を返すべきでないエラー
+    #[error("authentication provider error: {0}")]
+    Provider(String),
+}
+
+impl AuthenticationError {
+    pub fn message(&self) -> &str {
+        match self {
+            AuthenticationError::InvalidToken(message)
+            | AuthenticationError::ExpiredToken(message)
+            | AuthenticationError::NotAuthorized(message)
+            | AuthenticationError::UserNotFound(message)
+            | AuthenticationError::UsernameExists(message)
+            | AuthenticationError::UserNotConfirmed(message)
+            | AuthenticationError::PasswordResetRequired(message)
+            | AuthenticationError::TooManyRequests(message)
+            | AuthenticationError::InvalidParameter(message)
+            | AuthenticationError::Unsupported(message)
+            | AuthenticationError::Provider(message) => message,
+            AuthenticationError::InvalidPassword { message, .. }
+            | AuthenticationError::CodeMismatch { message, .. }
+            | AuthenticationError::ExpiredCode { message, .. } => message,
+        }
+    }
+
+    // エラーの原因となった引数名（ユーザーに関するエラーはメールアドレス）
+    pub fn parameter(&self) -> Option<&'static str> {
+        match self {
+            AuthenticationError::UserNotFound(_)
+            | AuthenticationError::UsernameExists(_)
+            | AuthenticationError::UserNotConfirmed(_) => Some("email"),
+            AuthenticationError::InvalidPassword { parameter, .. }
+            | AuthenticationError::CodeMismatch { parameter, .. }
+            | AuthenticationError::ExpiredCode { parameter, .. } => Some(*parameter),
+            _ => None,
+        }
+    }
+}
+
+// Result型のエイリアス
+pub type RepositoryResult<T> = Result<T, RepositoryError>;
+pub type AuthenticationResult<T> = Result<T, AuthenticationError>;
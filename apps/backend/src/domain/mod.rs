@@ -1,5 +1,6 @@
 pub mod entities;
 pub mod enums;
+pub mod errors;
 pub mod repositories;
 pub mod services;
 pub mod value_objects;
@@ -0,0 +1,15 @@
+use crate::domain::entities::api_token::{ApiToken, NewApiToken};
+use crate::domain::errors::RepositoryResult;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait ApiTokenRepository: Send + Sync + 'static {
+    async fn create(&self, token: NewApiToken) -> RepositoryResult<ApiToken>;
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<ApiToken>>;
+    async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<ApiToken>>;
+    // 失効していないトークンを新しい順に返す（期限切れのトークンも含む）
+    async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ApiToken>>;
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+}
@@ -1,8 +1,14 @@
 use crate::domain::entities::identity_link::{IdentityLink, NewIdentityLink};
+use crate::domain::errors::RepositoryResult;
 use async_trait::async_trait;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait IdentityLinkRepository: Send + Sync + 'static {
-    async fn create(&self, user: NewIdentityLink) -> anyhow::Result<IdentityLink>;
-    async fn find_by_sub(&self, sub: &str) -> anyhow::Result<IdentityLink>;
+    async fn create(&self, user: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+    // subはプロバイダーごとに一意なため、プロバイダー名と組み合わせて探す
+    async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+    async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+    // 最後の1つは削除せずConflictを返す。件数の確認と削除は同時に解除されても0件にならないよう1つのトランザクションで行う
+    async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
 }
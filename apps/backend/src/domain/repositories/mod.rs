@@ -2,5 +2,11 @@
 // これらのトレイトは、永続化ストレージとのインターフェースを提供します。
 
 // リポジトリトレイト
+pub mod api_token_repository;
 pub mod identity_link_repository;
+pub mod permission_repository;
+pub mod revoked_token_repository;
+pub mod session_repository;
+pub mod sign_in_code_request_repository;
+pub mod sign_up_compensation_repository;
 pub mod user_repository;
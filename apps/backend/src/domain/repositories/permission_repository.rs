@@ -0,0 +1,26 @@
+use crate::domain::entities::permission::Permission;
+use crate::domain::entities::role::{NewRole, Role};
+use crate::domain::errors::RepositoryResult;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait PermissionRepository: Send + Sync + 'static {
+    // ユーザーに割り当てられたすべてのロールの権限名（重複なし）
+    async fn find_permissions_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<String>>;
+    async fn find_all_permissions(&self) -> RepositoryResult<Vec<Permission>>;
+    async fn find_all_roles(&self) -> RepositoryResult<Vec<Role>>;
+    async fn find_roles_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Role>>;
+    // 存在しない権限名が含まれる場合はNotFoundを返す
+    async fn create_role(&self, role: NewRole) -> RepositoryResult<Role>;
+    // ロールの権限を指定された権限で置き換える
+    async fn set_role_permissions(
+        &self,
+        role_id: Uuid,
+        permissions: Vec<String>,
+    ) -> RepositoryResult<Role>;
+    async fn delete_role(&self, role_id: Uuid) -> RepositoryResult<()>;
+    // 割り当て済みの場合は何もしない
+    async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+    async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+}
@@ -0,0 +1,14 @@
+use crate::domain::entities::revoked_token::RevokedToken;
+use crate::domain::errors::RepositoryResult;
+use async_trait::async_trait;
+
+// 失効させたアクセストークンの保存先（既定はPostgres、テストではメモリ上）
+#[async_trait]
+pub trait RevokedTokenRepository: Send + Sync + 'static {
+    // 同じIDが記録済みの場合は有効期限を更新する
+    async fn add(&self, token: RevokedToken) -> RepositoryResult<()>;
+    // 有効期限内の記録にいずれかのIDが含まれるか
+    async fn contains_any(&self, jtis: &[String]) -> RepositoryResult<bool>;
+    // 期限切れの記録を削除し、削除した件数を返す
+    async fn purge_expired(&self) -> RepositoryResult<u64>;
+}
@@ -0,0 +1,16 @@
+use crate::domain::entities::session::{NewSession, Session};
+use crate::domain::errors::RepositoryResult;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait SessionRepository: Send + Sync + 'static {
+    async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+    async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+    // 失効していないセッションを新しい順に返す
+    async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+    async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+}
@@ -0,0 +1,34 @@
+use crate::domain::entities::sign_in_code_request::{NewSignInCodeRequest, SignInCodeRequest};
+use crate::domain::errors::RepositoryResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[async_trait]
+pub trait SignInCodeRequestRepository: Send + Sync + 'static {
+    async fn create(&self, request: NewSignInCodeRequest) -> RepositoryResult<SignInCodeRequest>;
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<SignInCodeRequest>>;
+    // 指定した日時以降にメールアドレスへ送った要求の数（使用済みのものも含む）
+    async fn count_by_email_since(
+        &self,
+        email: &str,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<u64>;
+    // 未使用で期限内、かつ試行回数が上限未満の要求だけ、試行回数を1つ増やして返す（それ以外はNone）
+    // 同時に送られたコードも上限を超えて検証しないよう、確認と加算を1つのUPDATEで行う
+    async fn start_attempt(
+        &self,
+        id: Uuid,
+        max_attempts: i32,
+        now: DateTime<Utc>,
+    ) -> RepositoryResult<Option<SignInCodeRequest>>;
+    // プロバイダーが発行し直したセッションに置き換える
+    async fn update_provider_session(
+        &self,
+        id: Uuid,
+        provider_session: Option<String>,
+    ) -> RepositoryResult<()>;
+    // 未使用の要求だけを使用済みにする（使用済みの場合はNotFound）
+    async fn consume(&self, id: Uuid) -> RepositoryResult<()>;
+    async fn delete_created_before(&self, before: DateTime<Utc>) -> RepositoryResult<u64>;
+}
@@ -0,0 +1,15 @@
+use crate::domain::entities::sign_up_compensation::{NewSignUpCompensation, SignUpCompensation};
+use crate::domain::errors::RepositoryResult;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait SignUpCompensationRepository: Send + Sync + 'static {
+    async fn create(
+        &self,
+        compensation: NewSignUpCompensation,
+    ) -> RepositoryResult<SignUpCompensation>;
+    async fn find_unresolved(&self) -> RepositoryResult<Vec<SignUpCompensation>>;
+    async fn mark_resolved(&self, id: Uuid) -> RepositoryResult<()>;
+    async fn record_failure(&self, id: Uuid, error: &str) -> RepositoryResult<()>;
+}
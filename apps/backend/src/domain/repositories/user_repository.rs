@@ -1,12 +1,23 @@
-use crate::domain::entities::user::{NewUser, User};
+use crate::domain::entities::identity_link::IdentityLink;
+use crate::domain::entities::user::{NewUser, User, UserChanges};
+use crate::domain::errors::RepositoryResult;
 use async_trait::async_trait;
 use uuid::Uuid;
 
 #[async_trait]
 pub trait UserRepository: Send + Sync + 'static {
-    async fn create(&self, user: NewUser) -> anyhow::Result<User>;
-    async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<User>>;
-    async fn find_all(&self) -> anyhow::Result<Vec<User>>;
-    async fn update(&self, user: User) -> anyhow::Result<User>;
-    async fn delete(&self, id: Uuid) -> anyhow::Result<()>;
+    async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+    // ユーザーとIdentityLinkを1つのトランザクションで作成する
+    async fn create_with_identity_link(
+        &self,
+        user: NewUser,
+        provider: String,
+        sub: String,
+    ) -> RepositoryResult<(User, IdentityLink)>;
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+    async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+    async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+    async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+    // sourceのIdentityLink・セッション・APIトークン・ロールの割り当てをtargetに移し、sourceを削除する
+    async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
 }
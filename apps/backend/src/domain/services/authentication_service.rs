@@ -1,13 +1,79 @@
-use anyhow::Result;
 use async_trait::async_trait;
+use std::collections::HashMap;
 
-use crate::domain::value_objects::authentication::{Claims, SignInOutput, SignUpOutput};
+use crate::domain::errors::AuthenticationResult;
+use crate::domain::value_objects::authentication::{
+    AuthenticationTokens, Claims, CodeDeliveryDetails, SignInCodeChallenge, SignInCodeVerification,
+    SignInOutput, SignUpOutput, SoftwareTokenAssociation,
+};
 
+// ユニットテストではモック（MockAuthenticationService）を共有する
+#[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait AuthenticationService: Send + Sync + 'static {
     fn provider_name(&self) -> String;
-    async fn sign_up(&self, email: &str, password: &str) -> Result<SignUpOutput>;
-    async fn sign_in(&self, email: &str, password: &str) -> Result<SignInOutput>;
-    async fn sign_out(&self, username: &str) -> Result<()>;
-    async fn verify_token(&self, access_token: &str) -> Result<Claims>;
+    async fn sign_up(&self, email: &str, password: &str) -> AuthenticationResult<SignUpOutput>;
+    async fn confirm_sign_up(
+        &self,
+        email: &str,
+        confirmation_code: &str,
+    ) -> AuthenticationResult<()>;
+    async fn resend_confirmation_code(
+        &self,
+        email: &str,
+    ) -> AuthenticationResult<CodeDeliveryDetails>;
+    async fn sign_in(&self, email: &str, password: &str) -> AuthenticationResult<SignInOutput>;
+    async fn respond_to_auth_challenge(
+        &self,
+        email: &str,
+        challenge_name: &str,
+        session: Option<String>,
+        responses: &HashMap<String, String>,
+    ) -> AuthenticationResult<SignInOutput>;
+    // パスワードの代わりに、メールで送るワンタイムコードでのサインインを開始する
+    async fn request_sign_in_code(&self, email: &str) -> AuthenticationResult<SignInCodeChallenge>;
+    async fn verify_sign_in_code(
+        &self,
+        email: &str,
+        session: Option<String>,
+        code: &str,
+    ) -> AuthenticationResult<SignInCodeVerification>;
+    async fn refresh_tokens(
+        &self,
+        refresh_token: &str,
+    ) -> AuthenticationResult<AuthenticationTokens>;
+    async fn forgot_password(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+    async fn confirm_forgot_password(
+        &self,
+        email: &str,
+        confirmation_code: &str,
+        new_password: &str,
+    ) -> AuthenticationResult<()>;
+    async fn change_password(
+        &self,
+        access_token: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> AuthenticationResult<()>;
+    async fn associate_software_token(
+        &self,
+        access_token: &str,
+    ) -> AuthenticationResult<SoftwareTokenAssociation>;
+    async fn verify_software_token(
+        &self,
+        access_token: &str,
+        user_code: &str,
+        friendly_device_name: Option<String>,
+    ) -> AuthenticationResult<()>;
+    async fn set_mfa_preference(
+        &self,
+        access_token: &str,
+        enabled: bool,
+    ) -> AuthenticationResult<()>;
+    // ユーザーのすべてのセッションをサインアウトする
+    async fn sign_out(&self, username: &str) -> AuthenticationResult<()>;
+    // リフレッシュトークンと、そこから発行されたトークンだけを失効させる
+    async fn revoke_token(&self, refresh_token: &str) -> AuthenticationResult<()>;
+    async fn delete_user(&self, username: &str) -> AuthenticationResult<()>;
+    async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims>;
 }
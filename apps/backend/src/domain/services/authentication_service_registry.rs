@@ -0,0 +1,188 @@
+use anyhow::{Result, ensure};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::domain::services::authentication_service::AuthenticationService;
+
+// 署名を検証する前のトークンからiss（発行者）を読む関数
+// トークンの形式はインフラ層が知っているため、構築時に渡す
+pub type IssuerReader = fn(&str) -> Option<String>;
+
+// provider_name()をキーにした認証サービスの一覧
+// サインアップやパスワードでのサインインは既定のプロバイダーが担い、
+// アクセストークンはiss（発行者）に対応するプロバイダーで検証する
+pub struct AuthenticationServiceRegistry {
+    default_provider: String,
+    services: HashMap<String, Arc<dyn AuthenticationService>>,
+    // iss -> provider_name
+    issuers: HashMap<String, String>,
+    // グループの対応付け（AUTH_GROUP_ROLESなど）を適用するプロバイダー
+    group_mapping_providers: HashSet<String>,
+    read_issuer: IssuerReader,
+}
+
+impl AuthenticationServiceRegistry {
+    // 既定のプロバイダーのグループには、常にグループの対応付けを適用する
+    pub fn new(
+        issuer: impl Into<String>,
+        default_service: Arc<dyn AuthenticationService>,
+        read_issuer: IssuerReader,
+    ) -> Self {
+        let default_provider = default_service.provider_name();
+        Self {
+            issuers: HashMap::from([(issuer.into(), default_provider.clone())]),
+            services: HashMap::from([(default_provider.clone(), default_service)]),
+            group_mapping_providers: HashSet::from([default_provider.clone()]),
+            default_provider,
+            read_issuer,
+        }
+    }
+
+    // 同じプロバイダー名や発行者を二重に登録すると、どちらで検証するか決まらないためエラーにする
+    // 外部のIdPのグループ名はこのアプリケーションが管理していないため、map_groupsで明示した場合だけ対応付ける
+    pub fn register(
+        &mut self,
+        issuer: impl Into<String>,
+        service: Arc<dyn AuthenticationService>,
+        map_groups: bool,
+    ) -> Result<()> {
+        let issuer = issuer.into();
+        let provider = service.provider_name();
+        ensure!(
+            !self.services.contains_key(&provider),
+            "Authentication provider '{}' is already registered",
+            provider
+        );
+        ensure!(
+            !self.issuers.contains_key(&issuer),
+            "Issuer '{}' is already registered",
+            issuer
+        );
+        if map_groups {
+            self.group_mapping_providers.insert(provider.clone());
+        }
+        self.issuers.insert(issuer, provider.clone());
+        self.services.insert(provider, service);
+        Ok(())
+    }
+
+    pub fn default_service(&self) -> Arc<dyn AuthenticationService> {
+        self.services[&self.default_provider].clone()
+    }
+
+    pub fn get(&self, provider: &str) -> Option<Arc<dyn AuthenticationService>> {
+        self.services.get(provider).cloned()
+    }
+
+    pub fn find_by_issuer(&self, issuer: &str) -> Option<Arc<dyn AuthenticationService>> {
+        self.issuers
+            .get(issuer)
+            .and_then(|provider| self.get(provider))
+    }
+
+    // トークンのissに対応するプロバイダーを返す（issを読めないトークンや未登録の発行者ならNone）
+    pub fn verifier_for(&self, token: &str) -> Option<Arc<dyn AuthenticationService>> {
+        (self.read_issuer)(token).and_then(|issuer| self.find_by_issuer(&issuer))
+    }
+
+    pub fn maps_groups(&self, provider: &str) -> bool {
+        self.group_mapping_providers.contains(provider)
+    }
+
+    pub fn provider_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.services.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::authentication_service::MockAuthenticationService;
+
+    fn service(name: &'static str) -> Arc<dyn AuthenticationService> {
+        let mut service = MockAuthenticationService::new();
+        service
+            .expect_provider_name()
+            .returning(move || name.to_string());
+        Arc::new(service)
+    }
+
+    // テストでは「issued-by:<iss>」をトークンとして扱う
+    fn read_issuer(token: &str) -> Option<String> {
+        token.strip_prefix("issued-by:").map(str::to_string)
+    }
+
+    fn registry() -> AuthenticationServiceRegistry {
+        let mut registry = AuthenticationServiceRegistry::new(
+            "https://cognito.example.com",
+            service("cognito"),
+            read_issuer,
+        );
+        registry
+            .register("https://accounts.google.com", service("google"), false)
+            .unwrap();
+        registry
+            .register("https://sso.example.com", service("keycloak"), true)
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_verifier_for_selects_provider_by_issuer() {
+        let registry = registry();
+
+        let verifier = registry
+            .verifier_for("issued-by:https://accounts.google.com")
+            .unwrap();
+        assert_eq!(verifier.provider_name(), "google");
+
+        let verifier = registry
+            .verifier_for("issued-by:https://cognito.example.com")
+            .unwrap();
+        assert_eq!(verifier.provider_name(), "cognito");
+    }
+
+    #[test]
+    fn test_verifier_for_rejects_unknown_issuer() {
+        let registry = registry();
+
+        assert!(
+            registry
+                .verifier_for("issued-by:https://attacker.example.com")
+                .is_none()
+        );
+        // issを読めないトークンは、どのプロバイダーにも渡さない
+        assert!(registry.verifier_for("not-a-jwt").is_none());
+    }
+
+    #[test]
+    fn test_register_rejects_duplicates() {
+        let mut registry = registry();
+
+        assert!(
+            registry
+                .register("https://other.example.com", service("google"), false)
+                .is_err()
+        );
+        assert!(
+            registry
+                .register("https://accounts.google.com", service("auth0"), false)
+                .is_err()
+        );
+        assert_eq!(
+            registry.provider_names(),
+            vec!["cognito", "google", "keycloak"]
+        );
+    }
+
+    #[test]
+    fn test_group_mapping_applies_to_default_and_opted_in_providers() {
+        let registry = registry();
+
+        assert!(registry.maps_groups("cognito"));
+        assert!(registry.maps_groups("keycloak"));
+        assert!(!registry.maps_groups("google"));
+    }
+}
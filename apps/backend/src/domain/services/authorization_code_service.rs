@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::domain::errors::AuthenticationResult;
+use crate::domain::value_objects::{
+    authentication::AuthorizationCodeTokens, authorization_request::AuthorizationRequest,
+};
+
+// OAuth2の認可コードフロー（PKCE）でサインインさせる認可サーバー（Cognito Hosted UIなど）
+#[async_trait]
+pub trait AuthorizationCodeService: Send + Sync + 'static {
+    // ブラウザをリダイレクトさせる認可エンドポイントのURL
+    // identity_providerを指定すると、ログイン画面を経由せずにGoogleなどの外部プロバイダーへ進む
+    fn authorization_url(
+        &self,
+        request: &AuthorizationRequest,
+        identity_provider: Option<&str>,
+    ) -> String;
+    // 認可コードをトークンエンドポイントでトークンと交換する
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> AuthenticationResult<AuthorizationCodeTokens>;
+}
@@ -1 +1,3 @@
 pub mod authentication_service;
+pub mod authentication_service_registry;
+pub mod authorization_code_service;
@@ -0,0 +1,74 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// パーソナルアクセストークンの先頭。JWTのアクセストークンと見分けるために使う
+pub const API_TOKEN_PREFIX: &str = "morrow_pat_";
+
+// 一覧に表示する、プレフィックスに続くランダム部分の文字数
+const DISPLAY_PREFIX_LENGTH: usize = 8;
+
+// パーソナルアクセストークンの平文。作成時に一度だけ返し、データベースにはハッシュだけを保存する
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiTokenSecret(String);
+
+impl ApiTokenSecret {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        Self(format!("{}{}", API_TOKEN_PREFIX, to_hex(&bytes)))
+    }
+
+    // プレフィックスが無ければパーソナルアクセストークンではない
+    pub fn parse(token: &str) -> Option<Self> {
+        token
+            .starts_with(API_TOKEN_PREFIX)
+            .then(|| Self(token.to_string()))
+    }
+
+    // 照合に使うSHA-256のハッシュ。トークンは十分に長いランダム値のため、ソルトは使わない
+    pub fn hash(&self) -> String {
+        to_hex(&Sha256::digest(self.0.as_bytes()))
+    }
+
+    // 一覧でトークンを見分けるための先頭部分（例: "morrow_pat_1a2b3c4d"）
+    pub fn display_prefix(&self) -> String {
+        self.0
+            .chars()
+            .take(API_TOKEN_PREFIX.len() + DISPLAY_PREFIX_LENGTH)
+            .collect()
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_token_can_be_parsed_and_hashed() {
+        let secret = ApiTokenSecret::generate();
+        let token = secret.clone().into_string();
+
+        assert!(token.starts_with(API_TOKEN_PREFIX));
+        assert_eq!(token.len(), API_TOKEN_PREFIX.len() + 64);
+        assert_eq!(ApiTokenSecret::parse(&token), Some(secret.clone()));
+        assert_eq!(secret.hash().len(), 64);
+        assert_eq!(
+            secret.display_prefix(),
+            token[..API_TOKEN_PREFIX.len() + DISPLAY_PREFIX_LENGTH]
+        );
+        assert_ne!(ApiTokenSecret::generate().hash(), secret.hash());
+    }
+
+    #[test]
+    fn test_parse_rejects_other_tokens() {
+        assert_eq!(ApiTokenSecret::parse("eyJhbGciOiJSUzI1NiJ9.e30.sig"), None);
+    }
+}
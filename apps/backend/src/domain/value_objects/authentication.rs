@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 pub struct SignUpOutput {
     pub user_sub: String,
@@ -6,14 +7,82 @@ pub struct SignUpOutput {
     pub session: Option<String>,
 }
 
-pub struct SignInOutput {
+pub struct CodeDeliveryDetails {
+    pub destination: Option<String>,
+    pub delivery_medium: Option<String>,
+    pub attribute_name: Option<String>,
+}
+
+pub struct AuthenticationTokens {
     pub id_token: String,
     pub access_token: String,
     pub refresh_token: String,
     pub expires_in: i32,
 }
 
-#[derive(Deserialize)] // <- Deserialize を derive
+// 認可コードとの交換で得たトークン
+pub struct AuthorizationCodeTokens {
+    pub tokens: AuthenticationTokens,
+    pub id_token: IdTokenClaims,
+}
+
+// IDトークンのうち、サインインの完了とユーザーの作成に使うクレーム
+#[derive(Debug, Clone, Default)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    pub name: Option<String>,
+}
+
+// MFAやパスワード変更など、サインインを完了するために追加の応答が必要な状態
+pub struct AuthChallenge {
+    pub name: String,
+    pub session: Option<String>,
+    pub parameters: HashMap<String, String>,
+}
+
+pub enum SignInOutput {
+    Authenticated(AuthenticationTokens),
+    Challenge(AuthChallenge),
+}
+
+// メールで送ったワンタイムコードでのサインインを開始した状態
+pub struct SignInCodeChallenge {
+    // コードの検証時にプロバイダーへ渡すセッション
+    pub session: Option<String>,
+    pub code_delivery_details: CodeDeliveryDetails,
+}
+
+// ワンタイムコードの検証結果
+pub enum SignInCodeVerification {
+    Authenticated(AuthenticationTokens),
+    // コードが誤っていたが、新しいセッションで再入力できる
+    Retry { session: Option<String> },
+}
+
+// 認証アプリに登録するTOTPの共有シークレット
+pub struct SoftwareTokenAssociation {
+    pub secret_code: String,
+    pub session: Option<String>,
+}
+
+// 検証済みアクセストークンのクレーム（プロバイダーが発行しない項目は空のまま）
+#[derive(Debug, Clone, Default)]
 pub struct Claims {
     pub sub: String,
+    pub username: Option<String>,
+    pub client_id: Option<String>,
+    // cognito:groups
+    pub groups: Vec<String>,
+    // scopeクレームを空白で区切ったもの
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub auth_time: Option<DateTime<Utc>>,
+    // トークンごとに一意なID
+    pub jti: Option<String>,
+    // 同じサインインから（リフレッシュを含めて）発行されたトークンに共通のID
+    pub origin_jti: Option<String>,
+    // トークンを検証したプロバイダーのprovider_name
+    pub provider: String,
 }
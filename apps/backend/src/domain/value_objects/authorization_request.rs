@@ -0,0 +1,65 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use sha2::{Digest, Sha256};
+
+// 認可コードフローの開始時に生成し、コールバックで照合する値
+// state: コールバックが自分の開始したリクエストへの応答であることの確認（ログインCSRF対策）
+// nonce: IDトークンが今回のリクエストに対して発行されたことの確認（リプレイ対策）
+// code_verifier: 認可コードを横取りされても、トークンと交換できないようにする（PKCE）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorizationRequest {
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+impl AuthorizationRequest {
+    pub fn generate() -> Self {
+        Self {
+            state: random_token(),
+            nonce: random_token(),
+            code_verifier: random_token(),
+        }
+    }
+
+    // RFC 7636のS256方式のcode_challenge
+    pub fn code_challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.code_verifier.as_bytes()))
+    }
+}
+
+// 32バイトの乱数をbase64url（43文字）で表す。code_verifierに使える文字だけで構成される
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge_matches_rfc7636_example() {
+        let request = AuthorizationRequest {
+            state: "state".to_string(),
+            nonce: "nonce".to_string(),
+            code_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+        };
+
+        assert_eq!(
+            request.code_challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_generated_values_are_unique() {
+        let request = AuthorizationRequest::generate();
+
+        assert_eq!(request.code_verifier.len(), 43);
+        assert_ne!(request.state, request.nonce);
+        assert_ne!(request, AuthorizationRequest::generate());
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::enums::user_role::UserRole;
+
+// IdP（Cognitoなど）のグループをロール・権限に対応付ける設定
+// グループによる付与はリクエスト単位で、usersテーブルやロール割り当ては変更しない
+#[derive(Debug, Clone, Default)]
+pub struct GroupMapping {
+    roles: HashMap<String, UserRole>,
+    permissions: HashMap<String, Vec<String>>,
+}
+
+impl GroupMapping {
+    // roles: "admins=admin;staff=user"
+    // permissions: "support=users:read,users:update;auditors=users:read"
+    pub fn parse(roles: &str, permissions: &str) -> Self {
+        let roles = parse_entries(roles)
+            .map(|(group, role)| (group, UserRole::from(role)))
+            .collect();
+        let permissions = parse_entries(permissions)
+            .map(|(group, permissions)| {
+                let permissions = permissions
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|permission| !permission.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                (group, permissions)
+            })
+            .collect();
+        Self { roles, permissions }
+    }
+
+    // 所属グループから得られる最も強いロール
+    pub fn role_for(&self, groups: &[String]) -> Option<UserRole> {
+        groups
+            .iter()
+            .filter_map(|group| self.roles.get(group).copied())
+            .max_by_key(|role| role.is_admin())
+    }
+
+    pub fn permissions_for(&self, groups: &[String]) -> HashSet<String> {
+        groups
+            .iter()
+            .filter_map(|group| self.permissions.get(group))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+fn parse_entries(value: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    value.split(';').filter_map(|entry| {
+        let (group, value) = entry.split_once('=')?;
+        let group = group.trim();
+        (!group.is_empty()).then(|| (group.to_string(), value.trim().to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_role_for_prefers_admin() {
+        let mapping = GroupMapping::parse("staff=user; admins=admin", "");
+
+        assert_eq!(
+            mapping.role_for(&groups(&["staff", "admins"])),
+            Some(UserRole::Admin)
+        );
+        assert_eq!(mapping.role_for(&groups(&["staff"])), Some(UserRole::User));
+        assert_eq!(mapping.role_for(&groups(&["unknown"])), None);
+    }
+
+    #[test]
+    fn test_permissions_for_merges_groups() {
+        let mapping = GroupMapping::parse(
+            "",
+            "support=users:read, users:update;auditors=users:read;broken",
+        );
+
+        let permissions = mapping.permissions_for(&groups(&["support", "auditors"]));
+        assert_eq!(permissions.len(), 2);
+        assert!(permissions.contains("users:read"));
+        assert!(permissions.contains("users:update"));
+        assert!(mapping.permissions_for(&groups(&["broken"])).is_empty());
+    }
+
+    #[test]
+    fn test_empty_mapping_grants_nothing() {
+        let mapping = GroupMapping::default();
+
+        assert_eq!(mapping.role_for(&groups(&["admins"])), None);
+        assert!(mapping.permissions_for(&groups(&["admins"])).is_empty());
+    }
+}
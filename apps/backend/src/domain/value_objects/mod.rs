@@ -4,4 +4,8 @@
 // サンプル値オブジェクトをここに追加します
 // pub mod email;
 // pub mod password;
+pub mod api_token_secret;
 pub mod authentication;
+pub mod authorization_request;
+pub mod group_mapping;
+pub mod permission;
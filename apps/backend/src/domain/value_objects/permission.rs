@@ -0,0 +1,6 @@
+// アプリケーションが参照する権限名（permissionsテーブルの初期データと揃える）
+pub const USERS_READ: &str = "users:read";
+pub const USERS_CREATE: &str = "users:create";
+pub const USERS_UPDATE: &str = "users:update";
+pub const USERS_DELETE: &str = "users:delete";
+pub const ROLES_MANAGE: &str = "roles:manage";
@@ -1,20 +1,541 @@
-use anyhow::{Result, format_err};
 use async_trait::async_trait;
-use jsonwebtokens_cognito::KeySet;
+use aws_sdk_cognitoidentityprovider::error::ProvideErrorMetadata;
+use aws_sdk_cognitoidentityprovider::operation::{
+    admin_delete_user::AdminDeleteUserError,
+    admin_user_global_sign_out::AdminUserGlobalSignOutError,
+    associate_software_token::AssociateSoftwareTokenError, change_password::ChangePasswordError,
+    confirm_forgot_password::ConfirmForgotPasswordError, confirm_sign_up::ConfirmSignUpError,
+    forgot_password::ForgotPasswordError, initiate_auth::InitiateAuthError,
+    resend_confirmation_code::ResendConfirmationCodeError,
+    respond_to_auth_challenge::RespondToAuthChallengeError, revoke_token::RevokeTokenError,
+    set_user_mfa_preference::SetUserMFAPreferenceError, sign_up::SignUpError,
+    verify_software_token::VerifySoftwareTokenError,
+};
+use aws_sdk_cognitoidentityprovider::types::{
+    AuthFlowType, AuthenticationResultType, ChallengeNameType, CodeDeliveryDetailsType,
+    SoftwareTokenMfaSettingsType, VerifySoftwareTokenResponseType,
+};
+use jsonwebtoken::{Algorithm, Validation};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::domain::{
+    errors::{AuthenticationError, AuthenticationResult},
     services::authentication_service::AuthenticationService,
-    value_objects::authentication::{Claims, SignInOutput, SignUpOutput},
+    value_objects::authentication::{
+        AuthChallenge, AuthenticationTokens, Claims, CodeDeliveryDetails, SignInCodeChallenge,
+        SignInCodeVerification, SignInOutput, SignUpOutput, SoftwareTokenAssociation,
+    },
 };
+use crate::infrastructure::authentication::classify_token_error;
+use crate::infrastructure::authentication::jwks_cache::JwksCache;
+use crate::infrastructure::config::app_config::CognitoConfig;
+
+const ACCESS_TOKEN_USE: &str = "access";
+// ワンタイムコードの送信先として、チャレンジ作成のLambdaが公開パラメーターに含める値
+const CODE_DESTINATION_PARAMETER: &str = "destination";
+
+// Cognitoのアクセストークンのクレーム
+#[derive(Deserialize)]
+struct CognitoAccessTokenClaims {
+    sub: String,
+    token_use: String,
+    client_id: String,
+    exp: i64,
+    #[serde(default)]
+    auth_time: Option<i64>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(rename = "cognito:groups", default)]
+    groups: Vec<String>,
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    jti: Option<String>,
+    #[serde(default)]
+    origin_jti: Option<String>,
+}
+
+impl From<CognitoAccessTokenClaims> for Claims {
+    fn from(claims: CognitoAccessTokenClaims) -> Self {
+        Self {
+            sub: claims.sub,
+            username: claims.username,
+            client_id: Some(claims.client_id),
+            groups: claims.groups,
+            scopes: claims
+                .scope
+                .map(|scope| scope.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            expires_at: chrono::DateTime::from_timestamp(claims.exp, 0),
+            auth_time: claims
+                .auth_time
+                .and_then(|auth_time| chrono::DateTime::from_timestamp(auth_time, 0)),
+            jti: claims.jti,
+            origin_jti: claims.origin_jti,
+            ..Default::default()
+        }
+    }
+}
 
 pub struct CognitoService {
     client: aws_sdk_cognitoidentityprovider::Client,
+    config: CognitoConfig,
+    jwks_cache: JwksCache,
+    validation: Validation,
 }
 
 impl CognitoService {
-    pub fn new(aws_config: &aws_config::SdkConfig) -> Self {
+    pub fn new(aws_config: &aws_config::SdkConfig, config: CognitoConfig) -> Self {
         let client = aws_sdk_cognitoidentityprovider::Client::new(aws_config);
-        Self { client }
+        let jwks_cache = JwksCache::new(
+            config.jwks_url.clone(),
+            Duration::from_secs(config.jwks_cache_ttl),
+        );
+        // アクセストークンにはaudが無いため、client_idは検証後に照合する
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[config.issuer()]);
+        validation.validate_aud = false;
+        Self {
+            client,
+            config,
+            jwks_cache,
+            validation,
+        }
+    }
+}
+
+impl From<&CodeDeliveryDetailsType> for CodeDeliveryDetails {
+    fn from(details: &CodeDeliveryDetailsType) -> Self {
+        Self {
+            destination: details.destination().map(|value| value.to_string()),
+            delivery_medium: details
+                .delivery_medium()
+                .map(|value| value.as_str().to_string()),
+            attribute_name: details.attribute_name().map(|value| value.to_string()),
+        }
+    }
+}
+
+fn error_message<E: ProvideErrorMetadata>(error: &E) -> String {
+    error.message().unwrap_or_default().to_string()
+}
+
+// 分類できないエラーは詳細をクライアントに返さないよう、プロバイダーエラーとして扱う
+fn provider_error<E: std::fmt::Display>(error: E) -> AuthenticationError {
+    AuthenticationError::Provider(error.to_string())
+}
+
+// 以下、SDKのサービスエラーを操作ごとにドメインのエラーへ分類する
+
+fn classify_sign_up_error(error: SignUpError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        SignUpError::UsernameExistsException(_) => AuthenticationError::UsernameExists(message),
+        SignUpError::InvalidPasswordException(_) => AuthenticationError::InvalidPassword {
+            parameter: "password",
+            message,
+        },
+        SignUpError::InvalidParameterException(_) => AuthenticationError::InvalidParameter(message),
+        SignUpError::TooManyRequestsException(_) | SignUpError::LimitExceededException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_confirm_sign_up_error(error: ConfirmSignUpError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        ConfirmSignUpError::CodeMismatchException(_) => AuthenticationError::CodeMismatch {
+            parameter: "confirmation_code",
+            message,
+        },
+        ConfirmSignUpError::ExpiredCodeException(_) => AuthenticationError::ExpiredCode {
+            parameter: "confirmation_code",
+            message,
+        },
+        ConfirmSignUpError::UserNotFoundException(_) => AuthenticationError::UserNotFound(message),
+        ConfirmSignUpError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        ConfirmSignUpError::AliasExistsException(_) => AuthenticationError::UsernameExists(message),
+        ConfirmSignUpError::TooManyFailedAttemptsException(_)
+        | ConfirmSignUpError::TooManyRequestsException(_)
+        | ConfirmSignUpError::LimitExceededException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        ConfirmSignUpError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_resend_confirmation_code_error(
+    error: ResendConfirmationCodeError,
+) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        ResendConfirmationCodeError::UserNotFoundException(_) => {
+            AuthenticationError::UserNotFound(message)
+        }
+        ResendConfirmationCodeError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        ResendConfirmationCodeError::TooManyRequestsException(_)
+        | ResendConfirmationCodeError::LimitExceededException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        // 確認済みのユーザーに対する再送はInvalidParameterExceptionになる
+        ResendConfirmationCodeError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_initiate_auth_error(error: InitiateAuthError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        InitiateAuthError::NotAuthorizedException(_) => AuthenticationError::NotAuthorized(message),
+        InitiateAuthError::UserNotFoundException(_) => AuthenticationError::UserNotFound(message),
+        InitiateAuthError::UserNotConfirmedException(_) => {
+            AuthenticationError::UserNotConfirmed(message)
+        }
+        InitiateAuthError::PasswordResetRequiredException(_) => {
+            AuthenticationError::PasswordResetRequired(message)
+        }
+        InitiateAuthError::TooManyRequestsException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        InitiateAuthError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_respond_to_auth_challenge_error(
+    error: RespondToAuthChallengeError,
+) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        RespondToAuthChallengeError::CodeMismatchException(_) => {
+            AuthenticationError::CodeMismatch {
+                parameter: "responses",
+                message,
+            }
+        }
+        RespondToAuthChallengeError::ExpiredCodeException(_) => AuthenticationError::ExpiredCode {
+            parameter: "responses",
+            message,
+        },
+        // NEW_PASSWORD_REQUIREDチャレンジで新しいパスワードがポリシーを満たさない場合
+        RespondToAuthChallengeError::InvalidPasswordException(_)
+        | RespondToAuthChallengeError::PasswordHistoryPolicyViolationException(_) => {
+            AuthenticationError::InvalidPassword {
+                parameter: "responses",
+                message,
+            }
+        }
+        RespondToAuthChallengeError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        RespondToAuthChallengeError::UserNotFoundException(_) => {
+            AuthenticationError::UserNotFound(message)
+        }
+        RespondToAuthChallengeError::UserNotConfirmedException(_) => {
+            AuthenticationError::UserNotConfirmed(message)
+        }
+        RespondToAuthChallengeError::PasswordResetRequiredException(_) => {
+            AuthenticationError::PasswordResetRequired(message)
+        }
+        RespondToAuthChallengeError::TooManyRequestsException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        RespondToAuthChallengeError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_forgot_password_error(error: ForgotPasswordError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        ForgotPasswordError::UserNotFoundException(_) => AuthenticationError::UserNotFound(message),
+        ForgotPasswordError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        ForgotPasswordError::TooManyRequestsException(_)
+        | ForgotPasswordError::LimitExceededException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        ForgotPasswordError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_confirm_forgot_password_error(
+    error: ConfirmForgotPasswordError,
+) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        ConfirmForgotPasswordError::CodeMismatchException(_) => AuthenticationError::CodeMismatch {
+            parameter: "confirmation_code",
+            message,
+        },
+        ConfirmForgotPasswordError::ExpiredCodeException(_) => AuthenticationError::ExpiredCode {
+            parameter: "confirmation_code",
+            message,
+        },
+        ConfirmForgotPasswordError::InvalidPasswordException(_)
+        | ConfirmForgotPasswordError::PasswordHistoryPolicyViolationException(_) => {
+            AuthenticationError::InvalidPassword {
+                parameter: "new_password",
+                message,
+            }
+        }
+        ConfirmForgotPasswordError::UserNotFoundException(_) => {
+            AuthenticationError::UserNotFound(message)
+        }
+        ConfirmForgotPasswordError::UserNotConfirmedException(_) => {
+            AuthenticationError::UserNotConfirmed(message)
+        }
+        ConfirmForgotPasswordError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        ConfirmForgotPasswordError::TooManyFailedAttemptsException(_)
+        | ConfirmForgotPasswordError::TooManyRequestsException(_)
+        | ConfirmForgotPasswordError::LimitExceededException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        ConfirmForgotPasswordError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_change_password_error(error: ChangePasswordError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        // 現在のパスワードの誤りと、アクセストークンの失効のどちらでも返される
+        ChangePasswordError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        ChangePasswordError::InvalidPasswordException(_)
+        | ChangePasswordError::PasswordHistoryPolicyViolationException(_) => {
+            AuthenticationError::InvalidPassword {
+                parameter: "new_password",
+                message,
+            }
+        }
+        ChangePasswordError::PasswordResetRequiredException(_) => {
+            AuthenticationError::PasswordResetRequired(message)
+        }
+        ChangePasswordError::UserNotFoundException(_) => AuthenticationError::UserNotFound(message),
+        ChangePasswordError::UserNotConfirmedException(_) => {
+            AuthenticationError::UserNotConfirmed(message)
+        }
+        ChangePasswordError::TooManyRequestsException(_)
+        | ChangePasswordError::LimitExceededException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        ChangePasswordError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_associate_software_token_error(
+    error: AssociateSoftwareTokenError,
+) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        AssociateSoftwareTokenError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        AssociateSoftwareTokenError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_verify_software_token_error(error: VerifySoftwareTokenError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        VerifySoftwareTokenError::CodeMismatchException(_)
+        | VerifySoftwareTokenError::EnableSoftwareTokenMfaException(_) => {
+            AuthenticationError::CodeMismatch {
+                parameter: "user_code",
+                message,
+            }
+        }
+        VerifySoftwareTokenError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        VerifySoftwareTokenError::TooManyRequestsException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        VerifySoftwareTokenError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_set_user_mfa_preference_error(error: SetUserMFAPreferenceError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        SetUserMFAPreferenceError::NotAuthorizedException(_) => {
+            AuthenticationError::NotAuthorized(message)
+        }
+        SetUserMFAPreferenceError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_admin_user_global_sign_out_error(
+    error: AdminUserGlobalSignOutError,
+) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        AdminUserGlobalSignOutError::UserNotFoundException(_) => {
+            AuthenticationError::UserNotFound(message)
+        }
+        AdminUserGlobalSignOutError::TooManyRequestsException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_revoke_token_error(error: RevokeTokenError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        RevokeTokenError::UnauthorizedException(_) => AuthenticationError::NotAuthorized(message),
+        RevokeTokenError::UnsupportedTokenTypeException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        RevokeTokenError::InvalidParameterException(_) => {
+            AuthenticationError::InvalidParameter(message)
+        }
+        RevokeTokenError::TooManyRequestsException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+fn classify_admin_delete_user_error(error: AdminDeleteUserError) -> AuthenticationError {
+    let message = error_message(&error);
+    match error {
+        AdminDeleteUserError::UserNotFoundException(_) => {
+            AuthenticationError::UserNotFound(message)
+        }
+        AdminDeleteUserError::TooManyRequestsException(_) => {
+            AuthenticationError::TooManyRequests(message)
+        }
+        error => provider_error(error),
+    }
+}
+
+// 認証結果をトークンセットに変換する
+// REFRESH_TOKEN_AUTHではリフレッシュトークンが返らないため、呼び出し元のトークンを引き継ぐ
+fn to_authentication_tokens(
+    authentication_result: Option<AuthenticationResultType>,
+    current_refresh_token: Option<&str>,
+) -> AuthenticationResult<AuthenticationTokens> {
+    let authentication_result = authentication_result.ok_or_else(|| {
+        AuthenticationError::Provider("Authentication result not returned".to_string())
+    })?;
+    let refresh_token = authentication_result
+        .refresh_token
+        .or_else(|| current_refresh_token.map(|token| token.to_string()));
+    if let (Some(access_token), Some(refresh_token), Some(id_token)) = (
+        authentication_result.access_token,
+        refresh_token,
+        authentication_result.id_token,
+    ) {
+        Ok(AuthenticationTokens {
+            id_token,
+            access_token,
+            refresh_token,
+            expires_in: authentication_result.expires_in,
+        })
+    } else {
+        Err(AuthenticationError::Provider(
+            "Authentication result is missing tokens".to_string(),
+        ))
+    }
+}
+
+// チャレンジが返された場合はトークンの代わりにチャレンジを返す
+fn to_sign_in_output(
+    authentication_result: Option<AuthenticationResultType>,
+    challenge_name: Option<ChallengeNameType>,
+    session: Option<String>,
+    challenge_parameters: Option<HashMap<String, String>>,
+) -> AuthenticationResult<SignInOutput> {
+    match challenge_name {
+        Some(challenge_name) => Ok(SignInOutput::Challenge(AuthChallenge {
+            name: challenge_name.as_str().to_string(),
+            session,
+            parameters: challenge_parameters.unwrap_or_default(),
+        })),
+        None => {
+            to_authentication_tokens(authentication_result, None).map(SignInOutput::Authenticated)
+        }
+    }
+}
+
+// CUSTOM_AUTHの開始結果をワンタイムコードのチャレンジに変換する
+// コードの生成と送信は、ユーザープールのチャレンジ作成のLambdaが行う
+fn to_sign_in_code_challenge(
+    challenge_name: Option<ChallengeNameType>,
+    session: Option<String>,
+    challenge_parameters: Option<HashMap<String, String>>,
+) -> AuthenticationResult<SignInCodeChallenge> {
+    match challenge_name {
+        Some(ChallengeNameType::CustomChallenge) => Ok(SignInCodeChallenge {
+            session,
+            code_delivery_details: CodeDeliveryDetails {
+                destination: challenge_parameters
+                    .and_then(|mut parameters| parameters.remove(CODE_DESTINATION_PARAMETER)),
+                delivery_medium: Some("EMAIL".to_string()),
+                attribute_name: Some("email".to_string()),
+            },
+        }),
+        challenge_name => Err(AuthenticationError::Provider(format!(
+            "Unexpected challenge for sign in code: {:?}",
+            challenge_name
+        ))),
+    }
+}
+
+// 誤ったコードに対しては、Lambdaが同じチャレンジを新しいセッションで発行し直す
+fn to_sign_in_code_verification(
+    authentication_result: Option<AuthenticationResultType>,
+    challenge_name: Option<ChallengeNameType>,
+    session: Option<String>,
+) -> AuthenticationResult<SignInCodeVerification> {
+    match challenge_name {
+        Some(ChallengeNameType::CustomChallenge) => Ok(SignInCodeVerification::Retry { session }),
+        Some(challenge_name) => Err(AuthenticationError::Provider(format!(
+            "Unexpected challenge for sign in code: {}",
+            challenge_name.as_str()
+        ))),
+        None => to_authentication_tokens(authentication_result, None)
+            .map(SignInCodeVerification::Authenticated),
     }
 }
 
@@ -24,10 +545,10 @@ impl AuthenticationService for CognitoService {
         "cognito".to_string()
     }
 
-    async fn sign_up(&self, email: &str, password: &str) -> Result<SignUpOutput> {
+    async fn sign_up(&self, email: &str, password: &str) -> AuthenticationResult<SignUpOutput> {
         self.client
             .sign_up()
-            .client_id(std::env::var("AWS_COGNITO_USER_POOL_CLIENT_ID").unwrap())
+            .client_id(&self.config.client_id)
             .username(email)
             .password(password)
             .send()
@@ -39,68 +560,603 @@ impl AuthenticationService for CognitoService {
                     session: output.session,
                 })
             })
-            .map_err(|e| format_err!(e.into_service_error()))?
+            .map_err(|e| classify_sign_up_error(e.into_service_error()))?
+    }
+
+    async fn confirm_sign_up(
+        &self,
+        email: &str,
+        confirmation_code: &str,
+    ) -> AuthenticationResult<()> {
+        self.client
+            .confirm_sign_up()
+            .client_id(&self.config.client_id)
+            .username(email)
+            .confirmation_code(confirmation_code)
+            .send()
+            .await
+            .map(|_| Ok(()))
+            .map_err(|e| classify_confirm_sign_up_error(e.into_service_error()))?
+    }
+
+    async fn resend_confirmation_code(
+        &self,
+        email: &str,
+    ) -> AuthenticationResult<CodeDeliveryDetails> {
+        self.client
+            .resend_confirmation_code()
+            .client_id(&self.config.client_id)
+            .username(email)
+            .send()
+            .await
+            .map(|output| {
+                output
+                    .code_delivery_details()
+                    .map(CodeDeliveryDetails::from)
+                    .ok_or_else(|| {
+                        AuthenticationError::Provider(
+                            "Code delivery details not returned".to_string(),
+                        )
+                    })
+            })
+            .map_err(|e| classify_resend_confirmation_code_error(e.into_service_error()))?
     }
 
-    async fn sign_in(&self, email: &str, password: &str) -> Result<SignInOutput> {
+    async fn sign_in(&self, email: &str, password: &str) -> AuthenticationResult<SignInOutput> {
         self.client
             .initiate_auth()
-            .client_id(std::env::var("AWS_COGNITO_USER_POOL_CLIENT_ID").unwrap())
-            .auth_flow(aws_sdk_cognitoidentityprovider::types::AuthFlowType::UserPasswordAuth)
+            .client_id(&self.config.client_id)
+            .auth_flow(AuthFlowType::UserPasswordAuth)
             .auth_parameters("USERNAME", email)
             .auth_parameters("PASSWORD", password)
             .send()
             .await
             .map(|output| {
-                if let Some(authentication_result) = output.authentication_result {
-                    if let (Some(access_token), Some(refresh_token), Some(id_token)) = (
-                        authentication_result.access_token,
-                        authentication_result.refresh_token,
-                        authentication_result.id_token,
-                    ) {
-                        Ok(SignInOutput {
-                            id_token,
-                            access_token,
-                            refresh_token,
-                            expires_in: authentication_result.expires_in,
-                        })
-                    } else {
-                        Err(format_err!("Authentication failed"))
-                    }
-                } else {
-                    Err(format_err!("Authentication failed"))
-                }
+                to_sign_in_output(
+                    output.authentication_result,
+                    output.challenge_name,
+                    output.session,
+                    output.challenge_parameters,
+                )
+            })
+            .map_err(|e| classify_initiate_auth_error(e.into_service_error()))?
+    }
+
+    async fn respond_to_auth_challenge(
+        &self,
+        email: &str,
+        challenge_name: &str,
+        session: Option<String>,
+        responses: &HashMap<String, String>,
+    ) -> AuthenticationResult<SignInOutput> {
+        // Cognitoはすべてのチャレンジ応答にUSERNAMEを要求する
+        let mut challenge_responses = responses.clone();
+        challenge_responses
+            .entry("USERNAME".to_string())
+            .or_insert_with(|| email.to_string());
+        self.client
+            .respond_to_auth_challenge()
+            .client_id(&self.config.client_id)
+            .challenge_name(ChallengeNameType::from(challenge_name))
+            .set_session(session)
+            .set_challenge_responses(Some(challenge_responses))
+            .send()
+            .await
+            .map(|output| {
+                to_sign_in_output(
+                    output.authentication_result,
+                    output.challenge_name,
+                    output.session,
+                    output.challenge_parameters,
+                )
+            })
+            .map_err(|e| classify_respond_to_auth_challenge_error(e.into_service_error()))?
+    }
+
+    async fn request_sign_in_code(&self, email: &str) -> AuthenticationResult<SignInCodeChallenge> {
+        self.client
+            .initiate_auth()
+            .client_id(&self.config.client_id)
+            .auth_flow(AuthFlowType::CustomAuth)
+            .auth_parameters("USERNAME", email)
+            .send()
+            .await
+            .map(|output| {
+                to_sign_in_code_challenge(
+                    output.challenge_name,
+                    output.session,
+                    output.challenge_parameters,
+                )
+            })
+            .map_err(|e| classify_initiate_auth_error(e.into_service_error()))?
+    }
+
+    async fn verify_sign_in_code(
+        &self,
+        email: &str,
+        session: Option<String>,
+        code: &str,
+    ) -> AuthenticationResult<SignInCodeVerification> {
+        self.client
+            .respond_to_auth_challenge()
+            .client_id(&self.config.client_id)
+            .challenge_name(ChallengeNameType::CustomChallenge)
+            .set_session(session)
+            .challenge_responses("USERNAME", email)
+            .challenge_responses("ANSWER", code)
+            .send()
+            .await
+            .map(|output| {
+                to_sign_in_code_verification(
+                    output.authentication_result,
+                    output.challenge_name,
+                    output.session,
+                )
             })
-            .map_err(|e| format_err!(e.into_service_error()))?
+            .map_err(|e| classify_respond_to_auth_challenge_error(e.into_service_error()))?
     }
 
-    async fn sign_out(&self, username: &str) -> Result<()> {
+    async fn refresh_tokens(
+        &self,
+        refresh_token: &str,
+    ) -> AuthenticationResult<AuthenticationTokens> {
+        self.client
+            .initiate_auth()
+            .client_id(&self.config.client_id)
+            .auth_flow(AuthFlowType::RefreshTokenAuth)
+            .auth_parameters("REFRESH_TOKEN", refresh_token)
+            .send()
+            .await
+            .map(|output| {
+                to_authentication_tokens(output.authentication_result, Some(refresh_token))
+            })
+            .map_err(|e| classify_initiate_auth_error(e.into_service_error()))?
+    }
+
+    async fn forgot_password(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails> {
+        self.client
+            .forgot_password()
+            .client_id(&self.config.client_id)
+            .username(email)
+            .send()
+            .await
+            .map(|output| {
+                output
+                    .code_delivery_details()
+                    .map(CodeDeliveryDetails::from)
+                    .ok_or_else(|| {
+                        AuthenticationError::Provider(
+                            "Code delivery details not returned".to_string(),
+                        )
+                    })
+            })
+            .map_err(|e| classify_forgot_password_error(e.into_service_error()))?
+    }
+
+    async fn confirm_forgot_password(
+        &self,
+        email: &str,
+        confirmation_code: &str,
+        new_password: &str,
+    ) -> AuthenticationResult<()> {
+        self.client
+            .confirm_forgot_password()
+            .client_id(&self.config.client_id)
+            .username(email)
+            .confirmation_code(confirmation_code)
+            .password(new_password)
+            .send()
+            .await
+            .map(|_| Ok(()))
+            .map_err(|e| classify_confirm_forgot_password_error(e.into_service_error()))?
+    }
+
+    async fn change_password(
+        &self,
+        access_token: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> AuthenticationResult<()> {
+        self.client
+            .change_password()
+            .access_token(access_token)
+            .previous_password(old_password)
+            .proposed_password(new_password)
+            .send()
+            .await
+            .map(|_| Ok(()))
+            .map_err(|e| classify_change_password_error(e.into_service_error()))?
+    }
+
+    async fn associate_software_token(
+        &self,
+        access_token: &str,
+    ) -> AuthenticationResult<SoftwareTokenAssociation> {
+        self.client
+            .associate_software_token()
+            .access_token(access_token)
+            .send()
+            .await
+            .map(|output| {
+                output
+                    .secret_code
+                    .map(|secret_code| SoftwareTokenAssociation {
+                        secret_code,
+                        session: output.session,
+                    })
+                    .ok_or_else(|| {
+                        AuthenticationError::Provider("Secret code not returned".to_string())
+                    })
+            })
+            .map_err(|e| classify_associate_software_token_error(e.into_service_error()))?
+    }
+
+    async fn verify_software_token(
+        &self,
+        access_token: &str,
+        user_code: &str,
+        friendly_device_name: Option<String>,
+    ) -> AuthenticationResult<()> {
+        self.client
+            .verify_software_token()
+            .access_token(access_token)
+            .user_code(user_code)
+            .set_friendly_device_name(friendly_device_name)
+            .send()
+            .await
+            .map(|output| match output.status {
+                Some(VerifySoftwareTokenResponseType::Success) => Ok(()),
+                _ => Err(AuthenticationError::CodeMismatch {
+                    parameter: "user_code",
+                    message: "Software token verification failed".to_string(),
+                }),
+            })
+            .map_err(|e| classify_verify_software_token_error(e.into_service_error()))?
+    }
+
+    async fn set_mfa_preference(
+        &self,
+        access_token: &str,
+        enabled: bool,
+    ) -> AuthenticationResult<()> {
+        let settings = SoftwareTokenMfaSettingsType::builder()
+            .enabled(enabled)
+            .preferred_mfa(enabled)
+            .build();
+        self.client
+            .set_user_mfa_preference()
+            .access_token(access_token)
+            .software_token_mfa_settings(settings)
+            .send()
+            .await
+            .map(|_| Ok(()))
+            .map_err(|e| classify_set_user_mfa_preference_error(e.into_service_error()))?
+    }
+
+    async fn sign_out(&self, username: &str) -> AuthenticationResult<()> {
         self.client
             .admin_user_global_sign_out()
-            .user_pool_id(std::env::var("AWS_COGNITO_USER_POOL_ID").unwrap())
+            .user_pool_id(&self.config.user_pool_id)
+            .username(username)
+            .send()
+            .await
+            .map(|_| Ok(()))
+            .map_err(|e| classify_admin_user_global_sign_out_error(e.into_service_error()))?
+    }
+
+    async fn revoke_token(&self, refresh_token: &str) -> AuthenticationResult<()> {
+        // アプリクライアントでトークンの失効が有効になっている必要がある
+        self.client
+            .revoke_token()
+            .client_id(&self.config.client_id)
+            .token(refresh_token)
+            .send()
+            .await
+            .map(|_| Ok(()))
+            .map_err(|e| classify_revoke_token_error(e.into_service_error()))?
+    }
+
+    async fn delete_user(&self, username: &str) -> AuthenticationResult<()> {
+        self.client
+            .admin_delete_user()
+            .user_pool_id(&self.config.user_pool_id)
             .username(username)
             .send()
             .await
             .map(|_| Ok(()))
-            .map_err(|e| format_err!(e.into_service_error()))?
+            .map_err(|e| classify_admin_delete_user_error(e.into_service_error()))?
     }
 
-    async fn verify_token(&self, access_token: &str) -> Result<Claims> {
-        let key_set = KeySet::new(
-            &std::env::var("AWS_REGION").unwrap(),
-            &std::env::var("AWS_COGNITO_USER_POOL_ID").unwrap(),
+    async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims> {
+        let kid = jsonwebtoken::decode_header(access_token)
+            .map_err(|e| AuthenticationError::InvalidToken(e.to_string()))?
+            .kid
+            .ok_or_else(|| AuthenticationError::InvalidToken("Token has no key id".to_string()))?;
+        let decoding_key = self.jwks_cache.decoding_key(&kid).await?;
+        let claims = jsonwebtoken::decode::<CognitoAccessTokenClaims>(
+            access_token,
+            &decoding_key,
+            &self.validation,
+        )
+        .map_err(classify_token_error)?
+        .claims;
+        if claims.token_use != ACCESS_TOKEN_USE {
+            return Err(AuthenticationError::InvalidToken(format!(
+                "Invalid token use: {}",
+                claims.token_use
+            )));
+        }
+        if claims.client_id != self.config.client_id {
+            return Err(AuthenticationError::InvalidToken(format!(
+                "Invalid client id: {}",
+                claims.client_id
+            )));
+        }
+        Ok(Claims::from(claims))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_cognitoidentityprovider::error::ErrorMetadata;
+    use aws_sdk_cognitoidentityprovider::types::error::{
+        CodeMismatchException, InternalErrorException, InvalidPasswordException,
+        UserNotConfirmedException, UsernameExistsException,
+    };
+
+    fn authentication_result(refresh_token: Option<&str>) -> AuthenticationResultType {
+        AuthenticationResultType::builder()
+            .access_token("new-access-token")
+            .id_token("new-id-token")
+            .set_refresh_token(refresh_token.map(|token| token.to_string()))
+            .expires_in(3600)
+            .build()
+    }
+
+    #[test]
+    fn test_to_authentication_tokens_keeps_refresh_token_when_not_rotated() {
+        let output = to_authentication_tokens(
+            Some(authentication_result(None)),
+            Some("current-refresh-token"),
         )
         .unwrap();
-        let verifier = key_set
-            .new_access_token_verifier(
-                &[&std::env::var("AWS_COGNITO_USER_POOL_CLIENT_ID").unwrap()],
-            )
-            .build()?;
-        let claims_json = key_set
-            .verify(&access_token, &verifier)
-            .await
-            .map_err(|e| format_err!(e.to_string()))?;
-        let claims: Claims = serde_json::from_value(claims_json)?;
-        Ok(claims)
+
+        assert_eq!(output.access_token, "new-access-token");
+        assert_eq!(output.refresh_token, "current-refresh-token");
+    }
+
+    #[test]
+    fn test_to_authentication_tokens_prefers_rotated_refresh_token() {
+        let output = to_authentication_tokens(
+            Some(authentication_result(Some("rotated-refresh-token"))),
+            Some("current-refresh-token"),
+        )
+        .unwrap();
+
+        assert_eq!(output.refresh_token, "rotated-refresh-token");
+    }
+
+    #[test]
+    fn test_to_authentication_tokens_requires_refresh_token_on_sign_in() {
+        let result = to_authentication_tokens(Some(authentication_result(None)), None);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_to_authentication_tokens_without_authentication_result() {
+        let result = to_authentication_tokens(None, None);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_to_sign_in_output_returns_challenge() {
+        let parameters =
+            HashMap::from([("USER_ID_FOR_SRP".to_string(), "test-sub-123".to_string())]);
+        let output = to_sign_in_output(
+            None,
+            Some(ChallengeNameType::SoftwareTokenMfa),
+            Some("challenge-session".to_string()),
+            Some(parameters),
+        )
+        .unwrap();
+
+        match output {
+            SignInOutput::Challenge(challenge) => {
+                assert_eq!(challenge.name, "SOFTWARE_TOKEN_MFA");
+                assert_eq!(challenge.session.as_deref(), Some("challenge-session"));
+                assert_eq!(challenge.parameters["USER_ID_FOR_SRP"], "test-sub-123");
+            }
+            SignInOutput::Authenticated(_) => panic!("expected a challenge"),
+        }
+    }
+
+    #[test]
+    fn test_to_sign_in_output_returns_tokens() {
+        let output = to_sign_in_output(
+            Some(authentication_result(Some("refresh-token"))),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(matches!(output, SignInOutput::Authenticated(_)));
+    }
+
+    #[test]
+    fn test_to_sign_in_code_challenge_reads_destination() {
+        let parameters = HashMap::from([(
+            CODE_DESTINATION_PARAMETER.to_string(),
+            "u***@example.com".to_string(),
+        )]);
+        let challenge = to_sign_in_code_challenge(
+            Some(ChallengeNameType::CustomChallenge),
+            Some("custom-session".to_string()),
+            Some(parameters),
+        )
+        .unwrap();
+
+        assert_eq!(challenge.session.as_deref(), Some("custom-session"));
+        assert_eq!(
+            challenge.code_delivery_details.destination.as_deref(),
+            Some("u***@example.com")
+        );
+    }
+
+    #[test]
+    fn test_to_sign_in_code_verification_retries_on_reissued_challenge() {
+        let verification = to_sign_in_code_verification(
+            None,
+            Some(ChallengeNameType::CustomChallenge),
+            Some("next-session".to_string()),
+        )
+        .unwrap();
+
+        match verification {
+            SignInCodeVerification::Retry { session } => {
+                assert_eq!(session.as_deref(), Some("next-session"));
+            }
+            SignInCodeVerification::Authenticated(_) => panic!("expected a retry"),
+        }
+    }
+
+    #[test]
+    fn test_to_sign_in_code_verification_rejects_other_challenges() {
+        let result = to_sign_in_code_verification(
+            None,
+            Some(ChallengeNameType::SoftwareTokenMfa),
+            Some("mfa-session".to_string()),
+        );
+
+        assert!(matches!(result, Err(AuthenticationError::Provider(_))));
+    }
+
+    #[test]
+    fn test_classify_sign_up_error_username_exists() {
+        let error = classify_sign_up_error(SignUpError::UsernameExistsException(
+            UsernameExistsException::builder()
+                .message("User already exists")
+                .meta(
+                    ErrorMetadata::builder()
+                        .code("UsernameExistsException")
+                        .message("User already exists")
+                        .build(),
+                )
+                .build(),
+        ));
+
+        assert!(matches!(error, AuthenticationError::UsernameExists(_)));
+        assert_eq!(error.message(), "User already exists");
+        assert_eq!(error.parameter(), Some("email"));
+    }
+
+    #[test]
+    fn test_classify_sign_up_error_invalid_password() {
+        let error = classify_sign_up_error(SignUpError::InvalidPasswordException(
+            InvalidPasswordException::builder()
+                .message("Password did not conform with policy")
+                .build(),
+        ));
+
+        assert_eq!(error.parameter(), Some("password"));
+    }
+
+    #[test]
+    fn test_classify_initiate_auth_error_user_not_confirmed() {
+        let error = classify_initiate_auth_error(InitiateAuthError::UserNotConfirmedException(
+            UserNotConfirmedException::builder()
+                .message("User is not confirmed.")
+                .build(),
+        ));
+
+        assert!(matches!(error, AuthenticationError::UserNotConfirmed(_)));
+    }
+
+    #[test]
+    fn test_classify_confirm_forgot_password_error_code_mismatch() {
+        let error = classify_confirm_forgot_password_error(
+            ConfirmForgotPasswordError::CodeMismatchException(
+                CodeMismatchException::builder()
+                    .message("Invalid verification code provided, please try again.")
+                    .build(),
+            ),
+        );
+
+        assert!(matches!(error, AuthenticationError::CodeMismatch { .. }));
+        assert_eq!(error.parameter(), Some("confirmation_code"));
+    }
+
+    #[test]
+    fn test_classify_change_password_error_invalid_new_password() {
+        let error = classify_change_password_error(ChangePasswordError::InvalidPasswordException(
+            InvalidPasswordException::builder()
+                .message("Password did not conform with policy")
+                .build(),
+        ));
+
+        assert_eq!(error.parameter(), Some("new_password"));
+    }
+
+    #[test]
+    fn test_classify_unexpected_error_as_provider_error() {
+        let error = classify_sign_up_error(SignUpError::InternalErrorException(
+            InternalErrorException::builder()
+                .message("Internal error")
+                .build(),
+        ));
+
+        assert!(matches!(error, AuthenticationError::Provider(_)));
+    }
+
+    #[test]
+    fn test_access_token_claims_map_groups_and_scopes() {
+        let claims: CognitoAccessTokenClaims = serde_json::from_value(serde_json::json!({
+            "sub": "test-sub-123",
+            "token_use": "access",
+            "client_id": "test-client-id",
+            "exp": 1_700_003_600,
+            "auth_time": 1_700_000_000,
+            "username": "test-user",
+            "cognito:groups": ["admins", "support"],
+            "scope": "aws.cognito.signin.user.admin openid",
+            "jti": "test-jti",
+            "origin_jti": "test-origin-jti",
+        }))
+        .unwrap();
+
+        let claims = Claims::from(claims);
+
+        assert_eq!(claims.sub, "test-sub-123");
+        assert_eq!(claims.username.as_deref(), Some("test-user"));
+        assert_eq!(claims.client_id.as_deref(), Some("test-client-id"));
+        assert_eq!(claims.groups, vec!["admins", "support"]);
+        assert_eq!(
+            claims.scopes,
+            vec!["aws.cognito.signin.user.admin", "openid"]
+        );
+        assert_eq!(claims.expires_at.unwrap().timestamp(), 1_700_003_600);
+        assert_eq!(claims.auth_time.unwrap().timestamp(), 1_700_000_000);
+        assert_eq!(claims.jti.as_deref(), Some("test-jti"));
+        assert_eq!(claims.origin_jti.as_deref(), Some("test-origin-jti"));
+    }
+
+    #[test]
+    fn test_access_token_claims_without_groups() {
+        let claims: CognitoAccessTokenClaims = serde_json::from_value(serde_json::json!({
+            "sub": "test-sub-123",
+            "token_use": "access",
+            "client_id": "test-client-id",
+            "exp": 1_700_003_600,
+        }))
+        .unwrap();
+
+        let claims = Claims::from(claims);
+
+        assert!(claims.groups.is_empty());
+        assert!(claims.scopes.is_empty());
+        assert!(claims.auth_time.is_none());
     }
 }
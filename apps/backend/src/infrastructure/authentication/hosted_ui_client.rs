@@ -0,0 +1,272 @@
+use async_trait::async_trait;
+use jsonwebtoken::{DecodingKey, Validation};
+use serde::Deserialize;
+
+use crate::domain::{
+    errors::{AuthenticationError, AuthenticationResult},
+    services::authorization_code_service::AuthorizationCodeService,
+    value_objects::{
+        authentication::{AuthenticationTokens, AuthorizationCodeTokens, IdTokenClaims},
+        authorization_request::AuthorizationRequest,
+    },
+};
+use crate::infrastructure::config::app_config::HostedUiConfig;
+
+// トークンエンドポイントの応答
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+    access_token: String,
+    #[serde(default)]
+    refresh_token: String,
+    expires_in: i32,
+}
+
+// トークンエンドポイントのエラー応答（RFC 6749 5.2）
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawIdTokenClaims {
+    sub: String,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+// Cognito Hosted UIの認可エンドポイントとトークンエンドポイントを使う
+pub struct HostedUiClient {
+    config: HostedUiConfig,
+    http_client: reqwest::Client,
+}
+
+impl HostedUiClient {
+    pub fn new(config: HostedUiConfig) -> Self {
+        Self {
+            config,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    // IDトークンはTLSでトークンエンドポイントから直接受け取るため、署名は検証しない（OIDC Core 3.1.3.7）
+    // 認可に使うアクセストークンは、呼び出し側が認証サービスで署名を検証する
+    fn read_id_token(&self, id_token: &str) -> AuthenticationResult<IdTokenClaims> {
+        let mut validation = Validation::default();
+        validation.insecure_disable_signature_validation();
+        validation.set_audience(&[self.config.client_id.as_str()]);
+        let claims = jsonwebtoken::decode::<RawIdTokenClaims>(
+            id_token,
+            &DecodingKey::from_secret(&[]),
+            &validation,
+        )
+        .map_err(|e| AuthenticationError::InvalidToken(e.to_string()))?
+        .claims;
+        Ok(IdTokenClaims {
+            sub: claims.sub,
+            nonce: claims.nonce,
+            email: claims.email,
+            name: claims.name,
+        })
+    }
+}
+
+#[async_trait]
+impl AuthorizationCodeService for HostedUiClient {
+    fn authorization_url(
+        &self,
+        request: &AuthorizationRequest,
+        identity_provider: Option<&str>,
+    ) -> String {
+        let code_challenge = request.code_challenge();
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", self.config.client_id.as_str()),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("scope", self.config.scopes.as_str()),
+            ("state", request.state.as_str()),
+            ("nonce", request.nonce.as_str()),
+            ("code_challenge", code_challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        if let Some(identity_provider) = identity_provider {
+            params.push(("identity_provider", identity_provider));
+        }
+        match reqwest::Url::parse_with_params(&self.config.authorize_url, &params) {
+            Ok(url) => url.to_string(),
+            // 設定の誤りは起動後の最初のリクエストで気づけるよう、そのままのURLを返す
+            Err(e) => {
+                tracing::error!("Invalid authorize URL {}: {}", self.config.authorize_url, e);
+                self.config.authorize_url.clone()
+            }
+        }
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> AuthenticationResult<AuthorizationCodeTokens> {
+        let mut request = self.http_client.post(&self.config.token_url).form(&[
+            ("grant_type", "authorization_code"),
+            ("client_id", self.config.client_id.as_str()),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("code_verifier", code_verifier),
+        ]);
+        if let Some(client_secret) = &self.config.client_secret {
+            request = request.basic_auth(&self.config.client_id, Some(client_secret));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AuthenticationError::Provider(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            // 使用済み・期限切れの認可コードや、code_verifierの不一致はinvalid_grantになる
+            return Err(match response.json::<TokenErrorResponse>().await {
+                Ok(error) if status.is_client_error() => AuthenticationError::NotAuthorized(
+                    error.error_description.unwrap_or(error.error),
+                ),
+                _ => AuthenticationError::Provider(format!("Token endpoint returned {}", status)),
+            });
+        }
+        let tokens = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(|e| AuthenticationError::Provider(e.to_string()))?;
+
+        Ok(AuthorizationCodeTokens {
+            id_token: self.read_id_token(&tokens.id_token)?,
+            tokens: AuthenticationTokens {
+                id_token: tokens.id_token,
+                access_token: tokens.access_token,
+                refresh_token: tokens.refresh_token,
+                expires_in: tokens.expires_in,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Form, Json, Router, http::StatusCode, routing::post};
+    use jsonwebtoken::{EncodingKey, Header};
+    use std::collections::HashMap;
+
+    fn config(token_url: String) -> HostedUiConfig {
+        HostedUiConfig {
+            authorize_url: "https://morrow.auth.ap-northeast-1.amazoncognito.com/oauth2/authorize"
+                .to_string(),
+            token_url,
+            client_id: "test-client-id".to_string(),
+            client_secret: None,
+            redirect_uri: "https://api.example.com/auth/callback".to_string(),
+            scopes: "openid email".to_string(),
+            post_login_redirect: "/".to_string(),
+            secure_cookie: true,
+        }
+    }
+
+    fn id_token(nonce: &str) -> String {
+        jsonwebtoken::encode(
+            &Header::default(),
+            &serde_json::json!({
+                "sub": "google-sub-123",
+                "aud": "test-client-id",
+                "exp": 4_102_444_800_i64,
+                "nonce": nonce,
+                "email": "user@example.com",
+            }),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap()
+    }
+
+    // code_verifierが"valid-verifier"の場合だけトークンを返すトークンエンドポイントのスタブ
+    async fn start_token_endpoint() -> String {
+        let router = Router::new().route(
+            "/oauth2/token",
+            post(|Form(form): Form<HashMap<String, String>>| async move {
+                if form.get("grant_type").map(String::as_str) != Some("authorization_code")
+                    || form.get("code_verifier").map(String::as_str) != Some("valid-verifier")
+                {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": "invalid_grant" })),
+                    );
+                }
+                (
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "id_token": id_token("test-nonce"),
+                        "access_token": "access-token",
+                        "refresh_token": "refresh-token",
+                        "expires_in": 3600,
+                        "token_type": "Bearer",
+                    })),
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}/oauth2/token", addr)
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_returns_tokens_and_id_token_claims() {
+        let client = HostedUiClient::new(config(start_token_endpoint().await));
+
+        let output = client
+            .exchange_code("auth-code", "valid-verifier")
+            .await
+            .unwrap();
+
+        assert_eq!(output.tokens.access_token, "access-token");
+        assert_eq!(output.tokens.refresh_token, "refresh-token");
+        assert_eq!(output.tokens.expires_in, 3600);
+        assert_eq!(output.id_token.sub, "google-sub-123");
+        assert_eq!(output.id_token.nonce.as_deref(), Some("test-nonce"));
+        assert_eq!(output.id_token.email.as_deref(), Some("user@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_code_rejects_invalid_grant() {
+        let client = HostedUiClient::new(config(start_token_endpoint().await));
+
+        let result = client.exchange_code("auth-code", "other-verifier").await;
+
+        assert!(matches!(result, Err(AuthenticationError::NotAuthorized(_))));
+    }
+
+    #[test]
+    fn test_authorization_url_includes_pkce_and_state() {
+        let client = HostedUiClient::new(config("http://127.0.0.1/oauth2/token".to_string()));
+        let request = AuthorizationRequest::generate();
+
+        let url = reqwest::Url::parse(&client.authorization_url(&request, Some("Google"))).unwrap();
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.path(), "/oauth2/authorize");
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], "test-client-id");
+        assert_eq!(
+            params["redirect_uri"],
+            "https://api.example.com/auth/callback"
+        );
+        assert_eq!(params["state"], request.state);
+        assert_eq!(params["nonce"], request.nonce);
+        assert_eq!(params["code_challenge"], request.code_challenge());
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["identity_provider"], "Google");
+    }
+}
@@ -0,0 +1,271 @@
+use crate::domain::errors::AuthenticationError;
+use jsonwebtoken::{DecodingKey, jwk::JwkSet};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::{Mutex, RwLock};
+
+const DEFAULT_MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum JwksError {
+    // JWKSに該当するkidの鍵がない（トークン側の問題）
+    #[error("Unknown key id: {0}")]
+    UnknownKeyId(String),
+
+    // JWKSの取得に失敗し、使える鍵もない（プロバイダー側の問題）
+    #[error("Failed to fetch JWKS: {0}")]
+    Fetch(anyhow::Error),
+}
+
+impl From<JwksError> for AuthenticationError {
+    fn from(error: JwksError) -> Self {
+        match error {
+            JwksError::UnknownKeyId(_) => AuthenticationError::InvalidToken(error.to_string()),
+            JwksError::Fetch(_) => AuthenticationError::Provider(error.to_string()),
+        }
+    }
+}
+
+// 取得した公開鍵をkidごとに保持するJWKSキャッシュ
+// TTLが切れた時、または未知のkidを受け取った時に再取得する
+// 再取得に失敗した場合は古い鍵を使い続け、min_refetch_intervalの間は再取得しない
+pub struct JwksCache {
+    jwks_url: String,
+    ttl: Duration,
+    min_refetch_interval: Duration,
+    client: reqwest::Client,
+    state: RwLock<CacheState>,
+    // 再取得を1つに絞るためのロック（取得中もstateの読み取りはブロックしない）
+    fetch_lock: Mutex<()>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Option<Instant>,
+    failed_at: Option<Instant>,
+}
+
+fn is_older_than(instant: Option<Instant>, duration: Duration) -> bool {
+    instant
+        .map(|instant| instant.elapsed() >= duration)
+        .unwrap_or(true)
+}
+
+impl CacheState {
+    fn needs_refetch(&self, kid: &str, ttl: Duration, min_refetch_interval: Duration) -> bool {
+        let backing_off = !is_older_than(self.failed_at, min_refetch_interval);
+        if self.keys.contains_key(kid) {
+            is_older_than(self.fetched_at, ttl) && !backing_off
+        } else {
+            is_older_than(self.fetched_at, min_refetch_interval) && !backing_off
+        }
+    }
+}
+
+impl JwksCache {
+    pub fn new(jwks_url: impl Into<String>, ttl: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build the JWKS HTTP client");
+        Self {
+            jwks_url: jwks_url.into(),
+            ttl,
+            min_refetch_interval: DEFAULT_MIN_REFETCH_INTERVAL,
+            client,
+            state: RwLock::new(CacheState::default()),
+            fetch_lock: Mutex::new(()),
+        }
+    }
+
+    // 未知のkidによる再取得や、取得失敗後の再試行の最短間隔（取得が連発されるのを防ぐ）
+    pub fn with_min_refetch_interval(mut self, interval: Duration) -> Self {
+        self.min_refetch_interval = interval;
+        self
+    }
+
+    pub async fn decoding_key(&self, kid: &str) -> Result<DecodingKey, JwksError> {
+        if let Some(key) = self.cached_key(kid).await? {
+            return Ok(key);
+        }
+
+        let _fetching = self.fetch_lock.lock().await;
+        // ロック待ちの間に他のリクエストが再取得している場合は、その結果を使う
+        if let Some(key) = self.cached_key(kid).await? {
+            return Ok(key);
+        }
+
+        let fetched = self.fetch().await;
+        let mut state = self.state.write().await;
+        match fetched {
+            Ok(keys) => {
+                state.keys = keys;
+                state.fetched_at = Some(Instant::now());
+                state.failed_at = None;
+            }
+            Err(e) => {
+                state.failed_at = Some(Instant::now());
+                tracing::warn!("Failed to fetch JWKS from {}: {}", self.jwks_url, e);
+                // 取得できなくても、期限切れの鍵があればそれを使う
+                return state.keys.get(kid).cloned().ok_or(JwksError::Fetch(e));
+            }
+        }
+        state
+            .keys
+            .get(kid)
+            .cloned()
+            .ok_or_else(|| JwksError::UnknownKeyId(kid.to_string()))
+    }
+
+    // 再取得が不要ならキャッシュの鍵（または未知のkidのエラー）を返し、必要ならNoneを返す
+    async fn cached_key(&self, kid: &str) -> Result<Option<DecodingKey>, JwksError> {
+        let state = self.state.read().await;
+        if state.needs_refetch(kid, self.ttl, self.min_refetch_interval) {
+            return Ok(None);
+        }
+        state
+            .keys
+            .get(kid)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| JwksError::UnknownKeyId(kid.to_string()))
+    }
+
+    async fn fetch(&self) -> anyhow::Result<HashMap<String, DecodingKey>> {
+        // テストではfile://でローカルのJWKSファイルを指定できる
+        let jwks: JwkSet = match self.jwks_url.strip_prefix("file://") {
+            Some(path) => serde_json::from_str(&tokio::fs::read_to_string(path).await?)?,
+            None => {
+                self.client
+                    .get(&self.jwks_url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?
+            }
+        };
+
+        let mut keys = HashMap::new();
+        for jwk in jwks.keys {
+            if let Some(kid) = jwk.common.key_id.clone() {
+                keys.insert(kid, DecodingKey::from_jwk(&jwk)?);
+            }
+        }
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write_jwks(path: &PathBuf, kids: &[&str]) {
+        let keys: Vec<serde_json::Value> = kids
+            .iter()
+            .map(|kid| serde_json::json!({ "kty": "oct", "kid": kid, "k": "c2VjcmV0" }))
+            .collect();
+        std::fs::write(path, serde_json::json!({ "keys": keys }).to_string()).unwrap();
+    }
+
+    fn jwks_path() -> PathBuf {
+        std::env::temp_dir().join(format!("jwks-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_decoding_key_is_served_from_cache() {
+        let path = jwks_path();
+        write_jwks(&path, &["key-1"]);
+        let cache = JwksCache::new(
+            format!("file://{}", path.display()),
+            Duration::from_secs(60),
+        );
+
+        assert!(cache.decoding_key("key-1").await.is_ok());
+        std::fs::remove_file(&path).unwrap();
+        assert!(cache.decoding_key("key-1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_forces_refetch() {
+        let path = jwks_path();
+        write_jwks(&path, &["key-1"]);
+        let cache = JwksCache::new(
+            format!("file://{}", path.display()),
+            Duration::from_secs(60),
+        )
+        .with_min_refetch_interval(Duration::ZERO);
+
+        assert!(cache.decoding_key("key-1").await.is_ok());
+        write_jwks(&path, &["key-1", "key-2"]);
+        assert!(cache.decoding_key("key-2").await.is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_is_not_refetched_within_min_interval() {
+        let path = jwks_path();
+        write_jwks(&path, &["key-1"]);
+        let cache = JwksCache::new(
+            format!("file://{}", path.display()),
+            Duration::from_secs(60),
+        );
+
+        assert!(cache.decoding_key("key-1").await.is_ok());
+        write_jwks(&path, &["key-1", "key-2"]);
+        assert!(cache.decoding_key("key-2").await.is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_expired_cache_is_refetched() {
+        let path = jwks_path();
+        write_jwks(&path, &["key-1"]);
+        let cache = JwksCache::new(format!("file://{}", path.display()), Duration::ZERO);
+
+        assert!(cache.decoding_key("key-1").await.is_ok());
+        write_jwks(&path, &["key-2"]);
+        assert!(cache.decoding_key("key-1").await.is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+    #[tokio::test]
+    async fn test_stale_keys_are_served_when_refetch_fails() {
+        let path = jwks_path();
+        write_jwks(&path, &["key-1"]);
+        let cache = JwksCache::new(format!("file://{}", path.display()), Duration::ZERO);
+
+        assert!(cache.decoding_key("key-1").await.is_ok());
+        std::fs::remove_file(&path).unwrap();
+        assert!(cache.decoding_key("key-1").await.is_ok());
+        // 取得に失敗した直後は再取得せず、未知のkidはトークンの問題として扱う
+        write_jwks(&path, &["key-1", "key-2"]);
+        assert!(matches!(
+            cache.decoding_key("key-2").await,
+            Err(JwksError::UnknownKeyId(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_failure_without_cached_keys_is_fetch_error() {
+        let cache = JwksCache::new(
+            format!("file://{}", jwks_path().display()),
+            Duration::from_secs(60),
+        );
+
+        let Err(error) = cache.decoding_key("key-1").await else {
+            panic!("JWKS fetch should fail");
+        };
+        assert!(matches!(error, JwksError::Fetch(_)));
+        assert!(matches!(
+            AuthenticationError::from(error),
+            AuthenticationError::Provider(_)
+        ));
+    }
+}
@@ -0,0 +1,906 @@
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{
+        SaltString,
+        rand_core::{OsRng, RngCore},
+    },
+};
+use async_trait::async_trait;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::{
+    errors::{AuthenticationError, AuthenticationResult},
+    services::authentication_service::AuthenticationService,
+    value_objects::authentication::{
+        AuthenticationTokens, Claims, CodeDeliveryDetails, SignInCodeChallenge,
+        SignInCodeVerification, SignInOutput, SignUpOutput, SoftwareTokenAssociation,
+    },
+};
+use crate::infrastructure::authentication::classify_token_error;
+use crate::infrastructure::config::app_config::LocalAuthenticationConfig;
+use crate::infrastructure::database::models::local_credential::{
+    self, Entity as LocalCredentialEntity,
+};
+
+const ACCESS_TOKEN_USE: &str = "access";
+const ID_TOKEN_USE: &str = "id";
+const REFRESH_TOKEN_USE: &str = "refresh";
+const PASSWORD_RESET_CODE_TTL: i64 = 60 * 60;
+// Cognitoのチャレンジのセッションと同じ有効期間
+const SIGN_IN_CODE_TTL: i64 = 3 * 60;
+const USER_NOT_FOUND_MESSAGE: &str = "User does not exist.";
+const INCORRECT_CREDENTIALS_MESSAGE: &str = "Incorrect username or password.";
+const MFA_NOT_SUPPORTED_MESSAGE: &str = "MFA is not supported by the local provider";
+
+// ローカル認証で発行するJWTのクレーム
+#[derive(Debug, Serialize, Deserialize)]
+struct LocalTokenClaims {
+    iss: String,
+    sub: String,
+    jti: String,
+    iat: i64,
+    exp: i64,
+    token_use: String,
+    token_version: i32,
+    // サインインごとに発行し、リフレッシュで発行したトークンにも引き継ぐ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin_jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+}
+
+// Cognitoを使わずにPostgres上で認証を完結させる開発・テスト用の認証サービス
+pub struct LocalAuthenticationService {
+    connection: Arc<DatabaseConnection>,
+    config: LocalAuthenticationConfig,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+}
+
+impl LocalAuthenticationService {
+    pub fn new(connection: Arc<DatabaseConnection>, config: LocalAuthenticationConfig) -> Self {
+        let encoding_key = EncodingKey::from_secret(config.jwt_secret.as_bytes());
+        let decoding_key = DecodingKey::from_secret(config.jwt_secret.as_bytes());
+        Self {
+            connection,
+            config,
+            encoding_key,
+            decoding_key,
+        }
+    }
+
+    async fn find_by_email(
+        &self,
+        email: &str,
+    ) -> AuthenticationResult<Option<local_credential::Model>> {
+        let credential = LocalCredentialEntity::find()
+            .filter(local_credential::Column::Email.eq(email))
+            .one(self.connection.as_ref())
+            .await?;
+        Ok(credential)
+    }
+
+    async fn find_by_sub(&self, sub: &str) -> AuthenticationResult<local_credential::Model> {
+        let id = Uuid::parse_str(sub)
+            .map_err(|_| AuthenticationError::InvalidToken(format!("Invalid subject: {}", sub)))?;
+        LocalCredentialEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?
+            .ok_or_else(|| AuthenticationError::UserNotFound(USER_NOT_FOUND_MESSAGE.to_string()))
+    }
+
+    fn issue_token(
+        &self,
+        credential: &local_credential::Model,
+        token_use: &str,
+        ttl: i64,
+        origin_jti: &str,
+    ) -> AuthenticationResult<String> {
+        let now = chrono::Utc::now().timestamp();
+        let claims = LocalTokenClaims {
+            iss: self.config.issuer.clone(),
+            sub: credential.id.to_string(),
+            jti: Uuid::new_v4().to_string(),
+            iat: now,
+            exp: now + ttl,
+            token_use: token_use.to_string(),
+            token_version: credential.token_version,
+            origin_jti: Some(origin_jti.to_string()),
+            email: (token_use == ID_TOKEN_USE).then(|| credential.email.clone()),
+        };
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)
+            .map_err(|e| AuthenticationError::Provider(e.to_string()))
+    }
+
+    fn issue_tokens(
+        &self,
+        credential: &local_credential::Model,
+        origin_jti: &str,
+    ) -> AuthenticationResult<AuthenticationTokens> {
+        Ok(AuthenticationTokens {
+            id_token: self.issue_token(
+                credential,
+                ID_TOKEN_USE,
+                self.config.access_token_ttl,
+                origin_jti,
+            )?,
+            access_token: self.issue_token(
+                credential,
+                ACCESS_TOKEN_USE,
+                self.config.access_token_ttl,
+                origin_jti,
+            )?,
+            refresh_token: self.issue_token(
+                credential,
+                REFRESH_TOKEN_USE,
+                self.config.refresh_token_ttl,
+                origin_jti,
+            )?,
+            expires_in: self.config.access_token_ttl as i32,
+        })
+    }
+
+    // 署名・発行者・有効期限・用途を検証し、サインアウト済みのトークンでないことを確認する
+    async fn decode_token(
+        &self,
+        token: &str,
+        token_use: &str,
+    ) -> AuthenticationResult<LocalTokenClaims> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[&self.config.issuer]);
+        let claims =
+            jsonwebtoken::decode::<LocalTokenClaims>(token, &self.decoding_key, &validation)
+                .map_err(classify_token_error)?
+                .claims;
+        if claims.token_use != token_use {
+            return Err(AuthenticationError::InvalidToken(format!(
+                "Invalid token use: {}",
+                claims.token_use
+            )));
+        }
+        let credential = self.find_by_sub(&claims.sub).await?;
+        if claims.token_version != credential.token_version {
+            return Err(AuthenticationError::InvalidToken(
+                "Token has been revoked".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
+}
+
+// ローカル認証のDBエラーはクライアントに返さない内部エラーとして扱う
+impl From<DbErr> for AuthenticationError {
+    fn from(error: DbErr) -> Self {
+        AuthenticationError::Provider(error.to_string())
+    }
+}
+
+fn hash_password(password: &str) -> AuthenticationResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| AuthenticationError::Provider(e.to_string()))?;
+    Ok(hash.to_string())
+}
+
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+// メール送信の代わりにログへ出力する6桁の確認コード
+fn generate_confirmation_code() -> String {
+    format!("{:06}", OsRng.next_u32() % 1_000_000)
+}
+
+#[async_trait]
+impl AuthenticationService for LocalAuthenticationService {
+    fn provider_name(&self) -> String {
+        "local".to_string()
+    }
+
+    async fn sign_up(&self, email: &str, password: &str) -> AuthenticationResult<SignUpOutput> {
+        if self.find_by_email(email).await?.is_some() {
+            return Err(AuthenticationError::UsernameExists(
+                "User already exists".to_string(),
+            ));
+        }
+        let credential = local_credential::ActiveModel {
+            id: ActiveValue::NotSet,
+            email: ActiveValue::Set(email.to_string()),
+            password_hash: ActiveValue::Set(hash_password(password)?),
+            token_version: ActiveValue::Set(0),
+            ..Default::default()
+        }
+        .insert(self.connection.as_ref())
+        .await?;
+        Ok(SignUpOutput {
+            user_sub: credential.id.to_string(),
+            user_confirmed: true,
+            session: None,
+        })
+    }
+
+    async fn confirm_sign_up(
+        &self,
+        email: &str,
+        _confirmation_code: &str,
+    ) -> AuthenticationResult<()> {
+        // ローカル認証ではサインアップ時に確認済みとなるため、ユーザーの存在のみ確認する
+        self.find_by_email(email)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| AuthenticationError::UserNotFound(USER_NOT_FOUND_MESSAGE.to_string()))
+    }
+
+    async fn resend_confirmation_code(
+        &self,
+        email: &str,
+    ) -> AuthenticationResult<CodeDeliveryDetails> {
+        match self.find_by_email(email).await? {
+            Some(_) => Err(AuthenticationError::InvalidParameter(
+                "User is already confirmed.".to_string(),
+            )),
+            None => Err(AuthenticationError::UserNotFound(
+                USER_NOT_FOUND_MESSAGE.to_string(),
+            )),
+        }
+    }
+
+    async fn sign_in(&self, email: &str, password: &str) -> AuthenticationResult<SignInOutput> {
+        match self.find_by_email(email).await? {
+            Some(credential) if verify_password(password, &credential.password_hash) => self
+                .issue_tokens(&credential, &Uuid::new_v4().to_string())
+                .map(SignInOutput::Authenticated),
+            _ => Err(AuthenticationError::NotAuthorized(
+                INCORRECT_CREDENTIALS_MESSAGE.to_string(),
+            )),
+        }
+    }
+
+    async fn respond_to_auth_challenge(
+        &self,
+        _email: &str,
+        challenge_name: &str,
+        _session: Option<String>,
+        _responses: &HashMap<String, String>,
+    ) -> AuthenticationResult<SignInOutput> {
+        // ローカル認証はチャレンジを発行しないため、応答を受け付けない
+        Err(AuthenticationError::Unsupported(format!(
+            "Unsupported challenge: {}",
+            challenge_name
+        )))
+    }
+
+    async fn request_sign_in_code(&self, email: &str) -> AuthenticationResult<SignInCodeChallenge> {
+        let credential = self
+            .find_by_email(email)
+            .await?
+            .ok_or_else(|| AuthenticationError::UserNotFound(USER_NOT_FOUND_MESSAGE.to_string()))?;
+        // 6桁のコードはハッシュでも総当たりで戻せるため、クライアントにはランダムなIDだけを渡す
+        let code = generate_confirmation_code();
+        let session = Uuid::new_v4().to_string();
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(SIGN_IN_CODE_TTL);
+        let mut active_model: local_credential::ActiveModel = credential.into();
+        active_model.sign_in_code_session = ActiveValue::Set(Some(session.clone()));
+        active_model.sign_in_code_hash = ActiveValue::Set(Some(hash_password(&code)?));
+        active_model.sign_in_code_expires_at = ActiveValue::Set(Some(expires_at));
+        active_model.update(self.connection.as_ref()).await?;
+        if self.config.log_codes {
+            tracing::debug!("Sign in code for {}: {}", email, code);
+        }
+        Ok(SignInCodeChallenge {
+            session: Some(session),
+            code_delivery_details: CodeDeliveryDetails {
+                destination: Some(email.to_string()),
+                delivery_medium: Some("EMAIL".to_string()),
+                attribute_name: Some("email".to_string()),
+            },
+        })
+    }
+
+    async fn verify_sign_in_code(
+        &self,
+        email: &str,
+        session: Option<String>,
+        code: &str,
+    ) -> AuthenticationResult<SignInCodeVerification> {
+        let session = session.ok_or_else(|| {
+            AuthenticationError::InvalidParameter("Sign in code session is required".to_string())
+        })?;
+        let credential = self.find_by_email(email).await?.ok_or_else(|| {
+            AuthenticationError::NotAuthorized(INCORRECT_CREDENTIALS_MESSAGE.to_string())
+        })?;
+        match (
+            &credential.sign_in_code_session,
+            &credential.sign_in_code_hash,
+            credential.sign_in_code_expires_at,
+        ) {
+            (Some(current_session), Some(code_hash), Some(expires_at))
+                if *current_session == session =>
+            {
+                if expires_at <= chrono::Utc::now() {
+                    return Err(AuthenticationError::ExpiredCode {
+                        parameter: "code",
+                        message: "Sign in code has expired, please request a new code.".to_string(),
+                    });
+                }
+                // 試行回数の上限は呼び出し元で管理する
+                if !verify_password(code, code_hash) {
+                    return Ok(SignInCodeVerification::Retry {
+                        session: Some(session),
+                    });
+                }
+            }
+            _ => {
+                return Err(AuthenticationError::InvalidParameter(
+                    "Invalid sign in code session".to_string(),
+                ));
+            }
+        }
+        // コードは1回だけ使えるよう、サインインしたら消す
+        let tokens = self.issue_tokens(&credential, &Uuid::new_v4().to_string())?;
+        let mut active_model: local_credential::ActiveModel = credential.into();
+        active_model.sign_in_code_session = ActiveValue::Set(None);
+        active_model.sign_in_code_hash = ActiveValue::Set(None);
+        active_model.sign_in_code_expires_at = ActiveValue::Set(None);
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(SignInCodeVerification::Authenticated(tokens))
+    }
+
+    async fn refresh_tokens(
+        &self,
+        refresh_token: &str,
+    ) -> AuthenticationResult<AuthenticationTokens> {
+        let claims = self.decode_token(refresh_token, REFRESH_TOKEN_USE).await?;
+        let credential = self.find_by_sub(&claims.sub).await?;
+        let origin_jti = claims
+            .origin_jti
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        // Cognitoと同様に、リフレッシュトークンはローテーションせずそのまま返す
+        Ok(AuthenticationTokens {
+            refresh_token: refresh_token.to_string(),
+            ..self.issue_tokens(&credential, &origin_jti)?
+        })
+    }
+
+    async fn forgot_password(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails> {
+        let confirmation_code = generate_confirmation_code();
+        let Some(credential) = self.find_by_email(email).await? else {
+            // 応答時間から登録の有無を推測されないよう、存在しないユーザーでも同じハッシュ計算を行う
+            hash_password(&confirmation_code)?;
+            return Err(AuthenticationError::UserNotFound(
+                USER_NOT_FOUND_MESSAGE.to_string(),
+            ));
+        };
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(PASSWORD_RESET_CODE_TTL);
+        let mut active_model: local_credential::ActiveModel = credential.into();
+        active_model.password_reset_code_hash =
+            ActiveValue::Set(Some(hash_password(&confirmation_code)?));
+        active_model.password_reset_expires_at = ActiveValue::Set(Some(expires_at));
+        active_model.update(self.connection.as_ref()).await?;
+        if self.config.log_codes {
+            tracing::debug!("Password reset code for {}: {}", email, confirmation_code);
+        }
+        Ok(CodeDeliveryDetails {
+            destination: Some(email.to_string()),
+            delivery_medium: Some("EMAIL".to_string()),
+            attribute_name: Some("email".to_string()),
+        })
+    }
+
+    async fn confirm_forgot_password(
+        &self,
+        email: &str,
+        confirmation_code: &str,
+        new_password: &str,
+    ) -> AuthenticationResult<()> {
+        let credential = self
+            .find_by_email(email)
+            .await?
+            .ok_or_else(|| AuthenticationError::UserNotFound(USER_NOT_FOUND_MESSAGE.to_string()))?;
+        match (
+            &credential.password_reset_code_hash,
+            credential.password_reset_expires_at,
+        ) {
+            (Some(code_hash), Some(expires_at))
+                if verify_password(confirmation_code, code_hash) =>
+            {
+                if expires_at <= chrono::Utc::now() {
+                    return Err(AuthenticationError::ExpiredCode {
+                        parameter: "confirmation_code",
+                        message: "Invalid code provided, please request a code again.".to_string(),
+                    });
+                }
+            }
+            _ => {
+                return Err(AuthenticationError::CodeMismatch {
+                    parameter: "confirmation_code",
+                    message: "Invalid verification code provided, please try again.".to_string(),
+                });
+            }
+        }
+        let token_version = credential.token_version + 1;
+        let mut active_model: local_credential::ActiveModel = credential.into();
+        active_model.password_hash = ActiveValue::Set(hash_password(new_password)?);
+        active_model.password_reset_code_hash = ActiveValue::Set(None);
+        active_model.password_reset_expires_at = ActiveValue::Set(None);
+        active_model.token_version = ActiveValue::Set(token_version);
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn change_password(
+        &self,
+        access_token: &str,
+        old_password: &str,
+        new_password: &str,
+    ) -> AuthenticationResult<()> {
+        let claims = self.decode_token(access_token, ACCESS_TOKEN_USE).await?;
+        let credential = self.find_by_sub(&claims.sub).await?;
+        if !verify_password(old_password, &credential.password_hash) {
+            return Err(AuthenticationError::NotAuthorized(
+                INCORRECT_CREDENTIALS_MESSAGE.to_string(),
+            ));
+        }
+        // 古いパスワードで発行したトークンも使えないよう、トークンバージョンを進める
+        let token_version = credential.token_version + 1;
+        let mut active_model: local_credential::ActiveModel = credential.into();
+        active_model.password_hash = ActiveValue::Set(hash_password(new_password)?);
+        active_model.token_version = ActiveValue::Set(token_version);
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    // ローカル認証はTOTPによるMFAに対応していない
+    async fn associate_software_token(
+        &self,
+        _access_token: &str,
+    ) -> AuthenticationResult<SoftwareTokenAssociation> {
+        Err(AuthenticationError::Unsupported(
+            MFA_NOT_SUPPORTED_MESSAGE.to_string(),
+        ))
+    }
+
+    async fn verify_software_token(
+        &self,
+        _access_token: &str,
+        _user_code: &str,
+        _friendly_device_name: Option<String>,
+    ) -> AuthenticationResult<()> {
+        Err(AuthenticationError::Unsupported(
+            MFA_NOT_SUPPORTED_MESSAGE.to_string(),
+        ))
+    }
+
+    async fn set_mfa_preference(
+        &self,
+        _access_token: &str,
+        _enabled: bool,
+    ) -> AuthenticationResult<()> {
+        Err(AuthenticationError::Unsupported(
+            MFA_NOT_SUPPORTED_MESSAGE.to_string(),
+        ))
+    }
+
+    async fn sign_out(&self, username: &str) -> AuthenticationResult<()> {
+        // トークンバージョンを進めて、発行済みの全トークンを無効化する
+        let credential = self.find_by_sub(username).await?;
+        let token_version = credential.token_version + 1;
+        let mut active_model: local_credential::ActiveModel = credential.into();
+        active_model.token_version = ActiveValue::Set(token_version);
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn revoke_token(&self, refresh_token: &str) -> AuthenticationResult<()> {
+        // ローカル認証はトークンごとの状態を持たないため、失効はsessionsテーブルで判定する
+        self.decode_token(refresh_token, REFRESH_TOKEN_USE)
+            .await
+            .map(|_| ())
+    }
+
+    async fn delete_user(&self, username: &str) -> AuthenticationResult<()> {
+        LocalCredentialEntity::delete_many()
+            .filter(local_credential::Column::Email.eq(username))
+            .exec(self.connection.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims> {
+        let claims = self.decode_token(access_token, ACCESS_TOKEN_USE).await?;
+        // ローカル認証にはグループやスコープが無い
+        Ok(Claims {
+            sub: claims.sub,
+            expires_at: chrono::DateTime::from_timestamp(claims.exp, 0),
+            jti: Some(claims.jti),
+            origin_jti: claims.origin_jti,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    const SECRET: &str = "test-secret";
+
+    fn config(jwt_secret: &str, access_token_ttl: i64) -> LocalAuthenticationConfig {
+        LocalAuthenticationConfig {
+            jwt_secret: jwt_secret.to_string(),
+            issuer: "morrow-local".to_string(),
+            access_token_ttl,
+            refresh_token_ttl: 3600,
+            log_codes: false,
+        }
+    }
+
+    fn credential(password: &str, token_version: i32) -> local_credential::Model {
+        let now = chrono::Utc::now();
+        local_credential::Model {
+            id: Uuid::new_v4(),
+            email: "user@example.com".to_string(),
+            password_hash: hash_password(password).unwrap(),
+            token_version,
+            password_reset_code_hash: None,
+            password_reset_expires_at: None,
+            sign_in_code_session: None,
+            sign_in_code_hash: None,
+            sign_in_code_expires_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // クエリの結果を順に返すモックDBを使うサービス
+    fn service(
+        results: Vec<Vec<local_credential::Model>>,
+        config: LocalAuthenticationConfig,
+    ) -> LocalAuthenticationService {
+        let connection = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(results)
+            .into_connection();
+        LocalAuthenticationService::new(Arc::new(connection), config)
+    }
+
+    fn access_token(output: SignInOutput) -> String {
+        match output {
+            SignInOutput::Authenticated(tokens) => tokens.access_token,
+            _ => panic!("Expected tokens"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_creates_confirmed_credential() {
+        let created = credential("password123", 0);
+        let service = service(vec![vec![], vec![created.clone()]], config(SECRET, 3600));
+
+        let output = service
+            .sign_up("user@example.com", "password123")
+            .await
+            .unwrap();
+
+        assert_eq!(output.user_sub, created.id.to_string());
+        assert!(output.user_confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_with_existing_email() {
+        let service = service(
+            vec![vec![credential("password123", 0)]],
+            config(SECRET, 3600),
+        );
+
+        let result = service.sign_up("user@example.com", "password123").await;
+
+        assert!(matches!(
+            result,
+            Err(AuthenticationError::UsernameExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_and_verify_token() {
+        let credential = credential("password123", 0);
+        let service = service(
+            vec![vec![credential.clone()], vec![credential.clone()]],
+            config(SECRET, 3600),
+        );
+
+        let output = service
+            .sign_in("user@example.com", "password123")
+            .await
+            .unwrap();
+        let claims = service.verify_token(&access_token(output)).await.unwrap();
+
+        assert_eq!(claims.sub, credential.id.to_string());
+        assert!(claims.jti.is_some());
+        assert!(claims.origin_jti.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_with_wrong_password() {
+        let service = service(
+            vec![vec![credential("password123", 0)]],
+            config(SECRET, 3600),
+        );
+
+        let result = service.sign_in("user@example.com", "wrong-password").await;
+
+        assert!(matches!(result, Err(AuthenticationError::NotAuthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_with_unknown_email() {
+        let service = service(vec![vec![]], config(SECRET, 3600));
+
+        let result = service.sign_in("unknown@example.com", "password123").await;
+
+        assert!(matches!(result, Err(AuthenticationError::NotAuthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_expired() {
+        // jsonwebtokenの既定の猶予（60秒）を超えて期限切れのトークンを発行する
+        let service = service(
+            vec![vec![credential("password123", 0)]],
+            config(SECRET, -120),
+        );
+        let output = service
+            .sign_in("user@example.com", "password123")
+            .await
+            .unwrap();
+
+        let result = service.verify_token(&access_token(output)).await;
+
+        assert!(matches!(result, Err(AuthenticationError::ExpiredToken(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_with_wrong_secret() {
+        let issuer = service(
+            vec![vec![credential("password123", 0)]],
+            config("other-secret", 3600),
+        );
+        let output = issuer
+            .sign_in("user@example.com", "password123")
+            .await
+            .unwrap();
+        let verifier = service(vec![], config(SECRET, 3600));
+
+        let result = verifier.verify_token(&access_token(output)).await;
+
+        assert!(matches!(result, Err(AuthenticationError::InvalidToken(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_rejects_refresh_token() {
+        let service = service(
+            vec![vec![credential("password123", 0)]],
+            config(SECRET, 3600),
+        );
+        let output = service
+            .sign_in("user@example.com", "password123")
+            .await
+            .unwrap();
+        let SignInOutput::Authenticated(tokens) = output else {
+            panic!("Expected tokens");
+        };
+
+        let result = service.verify_token(&tokens.refresh_token).await;
+
+        assert!(matches!(result, Err(AuthenticationError::InvalidToken(_))));
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_revokes_issued_tokens() {
+        let credential = credential("password123", 0);
+        let signed_out = local_credential::Model {
+            token_version: 1,
+            ..credential.clone()
+        };
+        let service = service(
+            vec![
+                // sign_in
+                vec![credential.clone()],
+                // sign_outでの取得と更新
+                vec![credential.clone()],
+                vec![signed_out.clone()],
+                // verify_token
+                vec![signed_out],
+            ],
+            config(SECRET, 3600),
+        );
+        let access_token = access_token(
+            service
+                .sign_in("user@example.com", "password123")
+                .await
+                .unwrap(),
+        );
+
+        service.sign_out(&credential.id.to_string()).await.unwrap();
+        let result = service.verify_token(&access_token).await;
+
+        assert!(
+            matches!(result, Err(AuthenticationError::InvalidToken(message)) if message == "Token has been revoked")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_password_revokes_issued_tokens() {
+        let credential = credential("password123", 0);
+        let changed = local_credential::Model {
+            token_version: 1,
+            ..credential.clone()
+        };
+        let service = service(
+            vec![
+                // sign_in
+                vec![credential.clone()],
+                // change_passwordでのトークンの検証、取得と更新
+                vec![credential.clone()],
+                vec![credential.clone()],
+                vec![changed.clone()],
+                // verify_token
+                vec![changed],
+            ],
+            config(SECRET, 3600),
+        );
+        let access_token = access_token(
+            service
+                .sign_in("user@example.com", "password123")
+                .await
+                .unwrap(),
+        );
+
+        service
+            .change_password(&access_token, "password123", "new-password456")
+            .await
+            .unwrap();
+        let result = service.verify_token(&access_token).await;
+
+        assert!(
+            matches!(result, Err(AuthenticationError::InvalidToken(message)) if message == "Token has been revoked")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forgot_password_with_unknown_email() {
+        let service = service(vec![vec![]], config(SECRET, 3600));
+
+        let result = service.forgot_password("unknown@example.com").await;
+
+        assert!(matches!(result, Err(AuthenticationError::UserNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_request_sign_in_code_returns_opaque_session() {
+        let credential = credential("password123", 0);
+        let service = service(
+            vec![vec![credential.clone()], vec![credential]],
+            config(SECRET, 3600),
+        );
+
+        let challenge = service
+            .request_sign_in_code("user@example.com")
+            .await
+            .unwrap();
+
+        // セッションはコードのハッシュを含まないランダムなID
+        let session = challenge.session.unwrap();
+        assert!(Uuid::parse_str(&session).is_ok());
+    }
+
+    // "123456"のコードを要求済みのクレデンシャル
+    fn credential_with_sign_in_code(expires_in: i64) -> local_credential::Model {
+        local_credential::Model {
+            sign_in_code_session: Some("sign-in-code-session".to_string()),
+            sign_in_code_hash: Some(hash_password("123456").unwrap()),
+            sign_in_code_expires_at: Some(
+                chrono::Utc::now() + chrono::Duration::seconds(expires_in),
+            ),
+            ..credential("password123", 0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_sign_in_code_checks_stored_hash() {
+        let credential = credential_with_sign_in_code(180);
+        let service = service(
+            vec![
+                vec![credential.clone()],
+                vec![credential.clone()],
+                vec![credential],
+            ],
+            config(SECRET, 3600),
+        );
+        let session = Some("sign-in-code-session".to_string());
+
+        let retry = service
+            .verify_sign_in_code("user@example.com", session.clone(), "654321")
+            .await
+            .unwrap();
+        let authenticated = service
+            .verify_sign_in_code("user@example.com", session, "123456")
+            .await
+            .unwrap();
+
+        assert!(matches!(retry, SignInCodeVerification::Retry { .. }));
+        assert!(matches!(
+            authenticated,
+            SignInCodeVerification::Authenticated(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_sign_in_code_rejects_other_or_expired_session() {
+        let service = service(
+            vec![
+                vec![credential_with_sign_in_code(180)],
+                vec![credential_with_sign_in_code(-1)],
+            ],
+            config(SECRET, 3600),
+        );
+
+        let other = service
+            .verify_sign_in_code(
+                "user@example.com",
+                Some("other-session".to_string()),
+                "123456",
+            )
+            .await;
+        let expired = service
+            .verify_sign_in_code(
+                "user@example.com",
+                Some("sign-in-code-session".to_string()),
+                "123456",
+            )
+            .await;
+
+        assert!(matches!(
+            other,
+            Err(AuthenticationError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            expired,
+            Err(AuthenticationError::ExpiredCode { .. })
+        ));
+    }
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let hash = hash_password("password123").unwrap();
+
+        assert_ne!(hash, "password123");
+        assert!(verify_password("password123", &hash));
+        assert!(!verify_password("wrong-password", &hash));
+    }
+
+    #[test]
+    fn test_generate_confirmation_code() {
+        let code = generate_confirmation_code();
+
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_verify_password_with_malformed_hash() {
+        assert!(!verify_password("password123", "not-a-hash"));
+    }
+}
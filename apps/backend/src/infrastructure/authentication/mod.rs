@@ -1 +1,173 @@
+use jsonwebtoken::{DecodingKey, Validation};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::domain::errors::AuthenticationError;
+use crate::domain::services::authentication_service_registry::AuthenticationServiceRegistry;
+use crate::infrastructure::config::app_config::{AppConfig, AuthenticationProvider};
+
 pub mod cognito_service;
+pub mod hosted_ui_client;
+pub mod jwks_cache;
+pub mod local_authentication_service;
+pub mod oidc_service;
+
+// エクスポート
+pub use cognito_service::CognitoService;
+pub use hosted_ui_client::HostedUiClient;
+pub use local_authentication_service::LocalAuthenticationService;
+pub use oidc_service::OidcService;
+
+// JWTの検証エラーを、期限切れとそれ以外の不正なトークンに分類する
+pub(crate) fn classify_token_error(error: jsonwebtoken::errors::Error) -> AuthenticationError {
+    match error.kind() {
+        jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
+            AuthenticationError::ExpiredToken(error.to_string())
+        }
+        _ => AuthenticationError::InvalidToken(error.to_string()),
+    }
+}
+
+// 検証前のトークンから、検証に使うプロバイダーを選ぶためだけに読むクレーム
+#[derive(Deserialize)]
+struct IssuerClaim {
+    iss: String,
+}
+
+// 署名を検証せずにissだけを読む。ここで得た値はプロバイダーの選択にしか使わない
+pub fn unverified_issuer(token: &str) -> Option<String> {
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+    jsonwebtoken::decode::<IssuerClaim>(token, &DecodingKey::from_secret(&[]), &validation)
+        .ok()
+        .map(|data| data.claims.iss)
+}
+
+// 設定に応じて認証サービスを初期化する関数
+// AUTHENTICATION_PROVIDERのプロバイダーを既定とし、OIDC_PROVIDERSのプロバイダーを発行者ごとに登録する
+pub async fn init_authentication_services(
+    config: &AppConfig,
+    connection: Arc<DatabaseConnection>,
+) -> anyhow::Result<Arc<AuthenticationServiceRegistry>> {
+    let mut registry = match config.authentication_provider {
+        AuthenticationProvider::Cognito => {
+            let cognito_config = config
+                .cognito
+                .clone()
+                .expect("Cognito config must be loaded for the cognito provider");
+            let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+            AuthenticationServiceRegistry::new(
+                cognito_config.issuer(),
+                Arc::new(CognitoService::new(&sdk_config, cognito_config)),
+                unverified_issuer,
+            )
+        }
+        AuthenticationProvider::Local => {
+            let local_config = config
+                .local_authentication
+                .clone()
+                .expect("Local authentication config must be loaded for the local provider");
+            AuthenticationServiceRegistry::new(
+                local_config.issuer.clone(),
+                Arc::new(LocalAuthenticationService::new(connection, local_config)),
+                unverified_issuer,
+            )
+        }
+    };
+    for oidc_config in &config.oidc_providers {
+        let issuer = oidc_config.issuer.clone();
+        let service = OidcService::discover(oidc_config.clone())
+            .await
+            .map_err(|e| {
+                anyhow::format_err!(
+                    "Failed to initialize OIDC provider '{}': {}",
+                    oidc_config.name,
+                    e
+                )
+            })?;
+        registry.register(issuer, Arc::new(service), oidc_config.map_groups)?;
+    }
+    Ok(Arc::new(registry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct TestClaims {
+        sub: String,
+        exp: i64,
+    }
+
+    fn decode(exp: i64, secret: &str) -> jsonwebtoken::errors::Error {
+        let token = jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &TestClaims {
+                sub: "test-sub-123".to_string(),
+                exp,
+            },
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        jsonwebtoken::decode::<TestClaims>(
+            &token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .err()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_classify_expired_signature_as_expired_token() {
+        let expired = chrono::Utc::now().timestamp() - 3600;
+
+        assert!(matches!(
+            classify_token_error(decode(expired, "secret")),
+            AuthenticationError::ExpiredToken(_)
+        ));
+    }
+
+    #[test]
+    fn test_classify_bad_signature_as_invalid_token() {
+        let valid = chrono::Utc::now().timestamp() + 3600;
+
+        assert!(matches!(
+            classify_token_error(decode(valid, "other-secret")),
+            AuthenticationError::InvalidToken(_)
+        ));
+    }
+
+    fn token(claims: serde_json::Value) -> String {
+        jsonwebtoken::encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_unverified_issuer_reads_iss_without_verifying() {
+        assert_eq!(
+            unverified_issuer(&token(serde_json::json!({
+                "iss": "https://accounts.google.com",
+                "sub": "test-sub-123",
+            }))),
+            Some("https://accounts.google.com".to_string())
+        );
+        // issを読めないトークンは、どのプロバイダーにも渡さない
+        assert_eq!(unverified_issuer("not-a-jwt"), None);
+        assert_eq!(
+            unverified_issuer(&token(serde_json::json!({ "sub": "test-sub-123" }))),
+            None
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use jsonwebtoken::{Algorithm, Validation};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::domain::{
+    errors::{AuthenticationError, AuthenticationResult},
+    services::authentication_service::AuthenticationService,
+    value_objects::authentication::{
+        AuthenticationTokens, Claims, CodeDeliveryDetails, SignInCodeChallenge,
+        SignInCodeVerification, SignInOutput, SignUpOutput, SoftwareTokenAssociation,
+    },
+};
+use crate::infrastructure::authentication::classify_token_error;
+use crate::infrastructure::authentication::jwks_cache::JwksCache;
+use crate::infrastructure::config::app_config::OidcProviderConfig;
+
+// 公開鍵で署名されたトークンだけを受け付ける（HS256などの共有鍵による署名は拒否する）
+const ALLOWED_ALGORITHMS: [Algorithm; 6] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+#[derive(Deserialize)]
+struct DiscoveryDocument {
+    jwks_uri: String,
+}
+
+// OIDCプロバイダーが発行するトークンのクレーム
+#[derive(Deserialize)]
+struct OidcTokenClaims {
+    sub: String,
+    exp: i64,
+    #[serde(default)]
+    auth_time: Option<i64>,
+    #[serde(default)]
+    azp: Option<String>,
+    #[serde(default)]
+    client_id: Option<String>,
+    #[serde(default)]
+    preferred_username: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    jti: Option<String>,
+    // グループのクレーム名はプロバイダーごとに異なるため、残りのクレームから設定した名前で取り出す
+    #[serde(flatten)]
+    other: HashMap<String, serde_json::Value>,
+}
+
+impl OidcTokenClaims {
+    fn into_claims(mut self, groups_claim: &str) -> Claims {
+        let groups = match self.other.remove(groups_claim) {
+            Some(serde_json::Value::Array(groups)) => groups
+                .into_iter()
+                .filter_map(|group| group.as_str().map(str::to_string))
+                .collect(),
+            Some(serde_json::Value::String(group)) => vec![group],
+            _ => vec![],
+        };
+        Claims {
+            sub: self.sub,
+            username: self.preferred_username.or(self.email),
+            client_id: self.client_id.or(self.azp),
+            groups,
+            scopes: self
+                .scope
+                .map(|scope| scope.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            expires_at: chrono::DateTime::from_timestamp(self.exp, 0),
+            auth_time: self
+                .auth_time
+                .and_then(|auth_time| chrono::DateTime::from_timestamp(auth_time, 0)),
+            jti: self.jti,
+            ..Default::default()
+        }
+    }
+}
+
+// 発行者のURLとオーディエンスで設定する汎用のOIDCプロバイダー
+// アカウントの管理はプロバイダー側で行うため、このアプリケーションではトークンの検証だけを担う
+pub struct OidcService {
+    config: OidcProviderConfig,
+    jwks_cache: JwksCache,
+    validation: Validation,
+}
+
+impl OidcService {
+    pub fn new(config: OidcProviderConfig, jwks_url: String) -> Self {
+        let jwks_cache = JwksCache::new(jwks_url, Duration::from_secs(config.jwks_cache_ttl));
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.algorithms = ALLOWED_ALGORITHMS.to_vec();
+        validation.set_issuer(&[config.issuer.as_str()]);
+        validation.set_audience(&[config.audience.as_str()]);
+        Self {
+            config,
+            jwks_cache,
+            validation,
+        }
+    }
+
+    // JWKSのURLが設定されていなければ、ディスカバリードキュメントから取得して初期化する
+    pub async fn discover(config: OidcProviderConfig) -> Result<Self> {
+        let jwks_url = match &config.jwks_url {
+            Some(jwks_url) => jwks_url.clone(),
+            None => {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    config.issuer.trim_end_matches('/')
+                );
+                reqwest::get(&url)
+                    .await?
+                    .error_for_status()?
+                    .json::<DiscoveryDocument>()
+                    .await?
+                    .jwks_uri
+            }
+        };
+        Ok(Self::new(config, jwks_url))
+    }
+
+    fn unsupported<T>(&self, operation: &str) -> AuthenticationResult<T> {
+        Err(AuthenticationError::Unsupported(format!(
+            "{} is not supported by the {} provider",
+            operation, self.config.name
+        )))
+    }
+}
+
+#[async_trait]
+impl AuthenticationService for OidcService {
+    fn provider_name(&self) -> String {
+        self.config.name.clone()
+    }
+
+    async fn sign_up(&self, _email: &str, _password: &str) -> AuthenticationResult<SignUpOutput> {
+        self.unsupported("Sign up")
+    }
+
+    async fn confirm_sign_up(
+        &self,
+        _email: &str,
+        _confirmation_code: &str,
+    ) -> AuthenticationResult<()> {
+        self.unsupported("Sign up")
+    }
+
+    async fn resend_confirmation_code(
+        &self,
+        _email: &str,
+    ) -> AuthenticationResult<CodeDeliveryDetails> {
+        self.unsupported("Sign up")
+    }
+
+    async fn sign_in(&self, _email: &str, _password: &str) -> AuthenticationResult<SignInOutput> {
+        self.unsupported("Password sign in")
+    }
+
+    async fn respond_to_auth_challenge(
+        &self,
+        _email: &str,
+        _challenge_name: &str,
+        _session: Option<String>,
+        _responses: &HashMap<String, String>,
+    ) -> AuthenticationResult<SignInOutput> {
+        self.unsupported("Password sign in")
+    }
+
+    async fn request_sign_in_code(
+        &self,
+        _email: &str,
+    ) -> AuthenticationResult<SignInCodeChallenge> {
+        self.unsupported("Sign in code")
+    }
+
+    async fn verify_sign_in_code(
+        &self,
+        _email: &str,
+        _session: Option<String>,
+        _code: &str,
+    ) -> AuthenticationResult<SignInCodeVerification> {
+        self.unsupported("Sign in code")
+    }
+
+    async fn refresh_tokens(
+        &self,
+        _refresh_token: &str,
+    ) -> AuthenticationResult<AuthenticationTokens> {
+        self.unsupported("Token refresh")
+    }
+
+    async fn forgot_password(&self, _email: &str) -> AuthenticationResult<CodeDeliveryDetails> {
+        self.unsupported("Password reset")
+    }
+
+    async fn confirm_forgot_password(
+        &self,
+        _email: &str,
+        _confirmation_code: &str,
+        _new_password: &str,
+    ) -> AuthenticationResult<()> {
+        self.unsupported("Password reset")
+    }
+
+    async fn change_password(
+        &self,
+        _access_token: &str,
+        _old_password: &str,
+        _new_password: &str,
+    ) -> AuthenticationResult<()> {
+        self.unsupported("Password change")
+    }
+
+    async fn associate_software_token(
+        &self,
+        _access_token: &str,
+    ) -> AuthenticationResult<SoftwareTokenAssociation> {
+        self.unsupported("MFA")
+    }
+
+    async fn verify_software_token(
+        &self,
+        _access_token: &str,
+        _user_code: &str,
+        _friendly_device_name: Option<String>,
+    ) -> AuthenticationResult<()> {
+        self.unsupported("MFA")
+    }
+
+    async fn set_mfa_preference(
+        &self,
+        _access_token: &str,
+        _enabled: bool,
+    ) -> AuthenticationResult<()> {
+        self.unsupported("MFA")
+    }
+
+    // プロバイダー側のセッションはこのアプリケーションからは終了できない
+    async fn sign_out(&self, _username: &str) -> AuthenticationResult<()> {
+        self.unsupported("Global sign out")
+    }
+
+    async fn revoke_token(&self, _refresh_token: &str) -> AuthenticationResult<()> {
+        self.unsupported("Token revocation")
+    }
+
+    async fn delete_user(&self, _username: &str) -> AuthenticationResult<()> {
+        self.unsupported("User deletion")
+    }
+
+    async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims> {
+        let kid = jsonwebtoken::decode_header(access_token)
+            .map_err(|e| AuthenticationError::InvalidToken(e.to_string()))?
+            .kid
+            .ok_or_else(|| AuthenticationError::InvalidToken("Token has no key id".to_string()))?;
+        let decoding_key = self.jwks_cache.decoding_key(&kid).await?;
+        let claims =
+            jsonwebtoken::decode::<OidcTokenClaims>(access_token, &decoding_key, &self.validation)
+                .map_err(classify_token_error)?
+                .claims;
+        Ok(claims.into_claims(&self.config.groups_claim))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(value: serde_json::Value) -> OidcTokenClaims {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_token_claims_read_configured_groups_claim() {
+        let claims = claims(serde_json::json!({
+            "sub": "auth0|123",
+            "exp": 1_700_003_600,
+            "azp": "spa-client",
+            "email": "user@example.com",
+            "scope": "openid profile",
+            "https://morrow.example.com/groups": ["admins", "support"],
+        }))
+        .into_claims("https://morrow.example.com/groups");
+
+        assert_eq!(claims.sub, "auth0|123");
+        assert_eq!(claims.username.as_deref(), Some("user@example.com"));
+        assert_eq!(claims.client_id.as_deref(), Some("spa-client"));
+        assert_eq!(claims.groups, vec!["admins", "support"]);
+        assert_eq!(claims.scopes, vec!["openid", "profile"]);
+        assert_eq!(claims.expires_at.unwrap().timestamp(), 1_700_003_600);
+    }
+
+    #[test]
+    fn test_token_claims_without_groups() {
+        let claims = claims(serde_json::json!({
+            "sub": "google-sub-123",
+            "exp": 1_700_003_600,
+            "preferred_username": "user",
+            "email": "user@example.com",
+        }))
+        .into_claims("groups");
+
+        assert!(claims.groups.is_empty());
+        assert_eq!(claims.username.as_deref(), Some("user"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_rejects_shared_secret_signature() {
+        // JWKSに共有鍵が含まれていても、その鍵で署名されたトークンは受け付けない
+        let path = std::env::temp_dir().join(format!("jwks-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            serde_json::json!({ "keys": [{ "kty": "oct", "kid": "key-1", "k": "c2VjcmV0" }] })
+                .to_string(),
+        )
+        .unwrap();
+        let service = OidcService::new(
+            OidcProviderConfig {
+                name: "keycloak".to_string(),
+                issuer: "https://sso.example.com/realms/morrow".to_string(),
+                audience: "morrow".to_string(),
+                jwks_url: None,
+                jwks_cache_ttl: 60,
+                groups_claim: "groups".to_string(),
+                map_groups: false,
+            },
+            format!("file://{}", path.display()),
+        );
+        let mut header = jsonwebtoken::Header::new(Algorithm::HS256);
+        header.kid = Some("key-1".to_string());
+        let token = jsonwebtoken::encode(
+            &header,
+            &serde_json::json!({ "sub": "test-sub-123", "exp": 4_102_444_800_i64 }),
+            &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            service.verify_token(&token).await,
+            Err(AuthenticationError::InvalidToken(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_is_unsupported() {
+        // 呼び出し側は、このアプリケーションのトークンの拒否だけを行う
+        let service = OidcService::new(
+            OidcProviderConfig {
+                name: "google".to_string(),
+                issuer: "https://accounts.google.com".to_string(),
+                audience: "morrow".to_string(),
+                jwks_url: None,
+                jwks_cache_ttl: 60,
+                groups_claim: "groups".to_string(),
+                map_groups: false,
+            },
+            "https://www.googleapis.com/oauth2/v3/certs".to_string(),
+        );
+
+        assert!(matches!(
+            service.sign_out("google-sub-123").await,
+            Err(AuthenticationError::Unsupported(_))
+        ));
+    }
+}
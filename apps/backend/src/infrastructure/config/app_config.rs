@@ -1,4 +1,337 @@
+use ipnet::IpNet;
 use std::env;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::domain::value_objects::group_mapping::GroupMapping;
+
+// 設定値の読み込みエラー
+// 打ち間違えた値を既定値として扱うと意図しない構成で起動してしまうため、起動時に失敗させる
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    MissingVariable(#[from] env::VarError),
+    #[error("Invalid value for {name}: {value}")]
+    InvalidValue { name: &'static str, value: String },
+}
+
+// 認証プロバイダーの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticationProvider {
+    Cognito,
+    Local,
+}
+
+impl FromStr for AuthenticationProvider {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "cognito" => Ok(AuthenticationProvider::Cognito),
+            "local" => Ok(AuthenticationProvider::Local),
+            _ => Err(ConfigError::InvalidValue {
+                name: "AUTHENTICATION_PROVIDER",
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+// 失効させたアクセストークンの保存先
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenDenylistStore {
+    Postgres,
+    // プロセス内だけで共有されるため、単一インスタンスの開発環境やテスト向け
+    Memory,
+}
+
+impl FromStr for TokenDenylistStore {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "postgres" => Ok(TokenDenylistStore::Postgres),
+            "memory" => Ok(TokenDenylistStore::Memory),
+            _ => Err(ConfigError::InvalidValue {
+                name: "AUTH_TOKEN_DENYLIST_STORE",
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+// ローカル認証プロバイダーの設定
+#[derive(Debug, Clone)]
+pub struct LocalAuthenticationConfig {
+    pub jwt_secret: String,
+    pub issuer: String,
+    pub access_token_ttl: i64,
+    pub refresh_token_ttl: i64,
+    // メールを送れない開発環境のため、送るはずのコード（サインイン、パスワードの再設定）をdebugレベルでログに出す
+    // コードを知ればサインインやパスワードの再設定ができるため、開発環境以外では有効にしない
+    pub log_codes: bool,
+}
+
+impl LocalAuthenticationConfig {
+    pub fn from_env() -> Result<Self, env::VarError> {
+        Ok(Self {
+            jwt_secret: env::var("LOCAL_AUTH_JWT_SECRET")?,
+            issuer: env::var("LOCAL_AUTH_ISSUER").unwrap_or_else(|_| "morrow-local".to_string()),
+            access_token_ttl: env::var("LOCAL_AUTH_ACCESS_TOKEN_TTL")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(3600),
+            refresh_token_ttl: env::var("LOCAL_AUTH_REFRESH_TOKEN_TTL")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(60 * 60 * 24 * 30),
+            log_codes: env::var("LOCAL_AUTH_LOG_CODES")
+                .map(|value| value.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        })
+    }
+}
+
+// Cognito認証プロバイダーの設定
+#[derive(Debug, Clone)]
+pub struct CognitoConfig {
+    pub region: String,
+    pub user_pool_id: String,
+    pub client_id: String,
+    pub jwks_url: String,
+    pub jwks_cache_ttl: u64,
+}
+
+impl CognitoConfig {
+    pub fn from_env() -> Result<Self, env::VarError> {
+        let user_pool_id = env::var("AWS_COGNITO_USER_POOL_ID")?;
+        // ユーザープールIDは "<region>_<id>" 形式のため、未設定時はそこからリージョンを得る
+        let region = env::var("AWS_REGION").unwrap_or_else(|_| {
+            user_pool_id
+                .split_once('_')
+                .map(|(region, _)| region.to_string())
+                .unwrap_or_default()
+        });
+        let jwks_url = env::var("AWS_COGNITO_JWKS_URL").unwrap_or_else(|_| {
+            format!(
+                "https://cognito-idp.{}.amazonaws.com/{}/.well-known/jwks.json",
+                region, user_pool_id
+            )
+        });
+
+        Ok(Self {
+            client_id: env::var("AWS_COGNITO_USER_POOL_CLIENT_ID")?,
+            jwks_cache_ttl: env::var("AWS_COGNITO_JWKS_CACHE_TTL")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(60 * 60),
+            region,
+            user_pool_id,
+            jwks_url,
+        })
+    }
+
+    // トークンのissクレームと一致するユーザープールのURL
+    pub fn issuer(&self) -> String {
+        format!(
+            "https://cognito-idp.{}.amazonaws.com/{}",
+            self.region, self.user_pool_id
+        )
+    }
+}
+
+// Cognito Hosted UIを使った認可コードフローの設定
+#[derive(Debug, Clone)]
+pub struct HostedUiConfig {
+    pub authorize_url: String,
+    // テストではローカルのスタブに向ける
+    pub token_url: String,
+    pub client_id: String,
+    // シークレットを持つアプリクライアントの場合だけ設定する
+    pub client_secret: Option<String>,
+    // このアプリケーションの/auth/callbackの公開URL（アプリクライアントのコールバックURLに登録する）
+    pub redirect_uri: String,
+    pub scopes: String,
+    // Cookieモードでサインインした後にブラウザを戻す先
+    pub post_login_redirect: String,
+    // 認可リクエストのCookieのSecure属性
+    pub secure_cookie: bool,
+}
+
+impl HostedUiConfig {
+    // AWS_COGNITO_DOMAINが設定されている場合だけ有効
+    pub fn from_env() -> Result<Option<Self>, env::VarError> {
+        let Ok(domain) = env::var("AWS_COGNITO_DOMAIN") else {
+            return Ok(None);
+        };
+        let domain = domain.trim_end_matches('/');
+        Ok(Some(Self {
+            authorize_url: env::var("AWS_COGNITO_AUTHORIZE_URL")
+                .unwrap_or_else(|_| format!("{}/oauth2/authorize", domain)),
+            token_url: env::var("AWS_COGNITO_TOKEN_URL")
+                .unwrap_or_else(|_| format!("{}/oauth2/token", domain)),
+            client_id: env::var("AWS_COGNITO_USER_POOL_CLIENT_ID")?,
+            client_secret: env::var("AWS_COGNITO_USER_POOL_CLIENT_SECRET").ok(),
+            redirect_uri: env::var("AUTH_OAUTH_REDIRECT_URI")?,
+            scopes: env::var("AUTH_OAUTH_SCOPES")
+                .unwrap_or_else(|_| "openid email profile".to_string()),
+            post_login_redirect: env::var("AUTH_OAUTH_POST_LOGIN_REDIRECT")
+                .unwrap_or_else(|_| "/".to_string()),
+            secure_cookie: env::var("AUTH_COOKIE_SECURE")
+                .map(|value| !value.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+        }))
+    }
+}
+
+// Google WorkspaceやAuth0、Keycloakなど、発行者のURLとオーディエンスで設定する汎用のOIDCプロバイダー
+#[derive(Debug, Clone)]
+pub struct OidcProviderConfig {
+    // provider_name（IdentityLinkのproviderにも使う）
+    pub name: String,
+    pub issuer: String,
+    pub audience: String,
+    // 未設定の場合は、発行者のディスカバリードキュメントから取得する
+    pub jwks_url: Option<String>,
+    pub jwks_cache_ttl: u64,
+    // グループの一覧が入るクレーム名（AUTH_GROUP_ROLESなどのグループの対応付けに使う）
+    pub groups_claim: String,
+    // trueの場合だけ、このプロバイダーのグループにもAUTH_GROUP_ROLESなどを適用する
+    pub map_groups: bool,
+}
+
+impl OidcProviderConfig {
+    // OIDC_PROVIDERS=google,auth0 の場合、OIDC_GOOGLE_ISSUERのように名前ごとの環境変数を読む
+    pub fn from_env() -> Result<Vec<Self>, env::VarError> {
+        env::var("OIDC_PROVIDERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                let var = |key: &str| {
+                    env::var(format!(
+                        "OIDC_{}_{}",
+                        name.to_uppercase().replace('-', "_"),
+                        key
+                    ))
+                };
+                Ok(Self {
+                    name: name.to_lowercase(),
+                    issuer: var("ISSUER")?,
+                    audience: var("AUDIENCE")?,
+                    jwks_url: var("JWKS_URL").ok(),
+                    jwks_cache_ttl: var("JWKS_CACHE_TTL")
+                        .ok()
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or(60 * 60),
+                    groups_claim: var("GROUPS_CLAIM").unwrap_or_else(|_| "groups".to_string()),
+                    map_groups: var("MAP_GROUPS")
+                        .map(|value| value.eq_ignore_ascii_case("true"))
+                        .unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+}
+
+// CookieのSameSite属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl From<String> for SameSite {
+    fn from(value: String) -> Self {
+        match value.to_lowercase().as_str() {
+            "strict" => SameSite::Strict,
+            "none" => SameSite::None,
+            _ => SameSite::Lax,
+        }
+    }
+}
+
+impl std::fmt::Display for SameSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SameSite::Strict => write!(f, "Strict"),
+            SameSite::Lax => write!(f, "Lax"),
+            SameSite::None => write!(f, "None"),
+        }
+    }
+}
+
+// ブラウザ向けにトークンをHttpOnly Cookieで扱うモードの設定
+#[derive(Debug, Clone)]
+pub struct CookieConfig {
+    pub domain: Option<String>,
+    // HTTPで動かすローカル開発環境以外では有効にしておく
+    pub secure: bool,
+    pub same_site: SameSite,
+    pub refresh_token_max_age: i64,
+}
+
+impl CookieConfig {
+    // AUTH_COOKIE_MODE=trueの場合だけ有効
+    pub fn from_env() -> Option<Self> {
+        let enabled = env::var("AUTH_COOKIE_MODE")
+            .map(|value| value.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        enabled.then(|| Self {
+            domain: env::var("AUTH_COOKIE_DOMAIN").ok(),
+            secure: env::var("AUTH_COOKIE_SECURE")
+                .map(|value| !value.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            same_site: SameSite::from(
+                env::var("AUTH_COOKIE_SAME_SITE").unwrap_or_else(|_| "lax".to_string()),
+            ),
+            refresh_token_max_age: env::var("AUTH_COOKIE_REFRESH_MAX_AGE")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(60 * 60 * 24 * 30),
+        })
+    }
+}
+
+// X-Forwarded-Forを付けるリバースプロキシ（ロードバランサー）のアドレス
+// 空の場合はX-Forwarded-Forを信頼せず、TCP接続の相手を接続元とする
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpNet>);
+
+impl TrustedProxies {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(ip))
+    }
+}
+
+// カンマ区切りのIPアドレスまたはCIDR（例: 10.0.0.0/8,192.0.2.1）
+impl FromStr for TrustedProxies {
+    type Err = ConfigError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| ConfigError::InvalidValue {
+                        name: "TRUSTED_PROXIES",
+                        value: entry.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -6,10 +339,46 @@ pub struct AppConfig {
     pub port: u16,
     pub database_url: String,
     pub aws_cognito_user_pool_client_id: String,
+    pub authentication_provider: AuthenticationProvider,
+    pub cognito: Option<CognitoConfig>,
+    // 設定されている場合は/auth/authorizeと/auth/callbackを提供する
+    pub hosted_ui: Option<HostedUiConfig>,
+    pub local_authentication: Option<LocalAuthenticationConfig>,
+    // 既定のプロバイダーに加えて、アクセストークンを受け付けるプロバイダー
+    pub oidc_providers: Vec<OidcProviderConfig>,
+    pub group_mapping: GroupMapping,
+    pub cookies: Option<CookieConfig>,
+    pub token_denylist_store: TokenDenylistStore,
+    // 失効させたアクセストークンを拒否し続ける秒数（発行されるアクセストークンの有効期間以上）
+    pub access_token_lifetime: i64,
+    pub trusted_proxies: TrustedProxies,
 }
 
 impl AppConfig {
-    pub fn from_env() -> Result<Self, env::VarError> {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let authentication_provider = env::var("AUTHENTICATION_PROVIDER")
+            .unwrap_or_else(|_| "cognito".to_string())
+            .parse()?;
+        let (cognito, local_authentication) = match authentication_provider {
+            AuthenticationProvider::Cognito => (Some(CognitoConfig::from_env()?), None),
+            AuthenticationProvider::Local => (None, Some(LocalAuthenticationConfig::from_env()?)),
+        };
+        // Hosted UIが発行するのはCognitoのトークンのため、既定のプロバイダーがCognitoの場合だけ使う
+        let hosted_ui = match authentication_provider {
+            AuthenticationProvider::Cognito => HostedUiConfig::from_env()?,
+            AuthenticationProvider::Local => None,
+        };
+        // Cognitoのアクセストークンの有効期間は既定で1時間
+        let access_token_lifetime = env::var("AUTH_ACCESS_TOKEN_LIFETIME")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .or_else(|| {
+                local_authentication
+                    .as_ref()
+                    .map(|config| config.access_token_ttl)
+            })
+            .unwrap_or(60 * 60);
+
         Ok(Self {
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port: env::var("PORT")
@@ -24,7 +393,77 @@ impl AppConfig {
                 std::env::var("POSTGRES_PORT").unwrap(),
                 std::env::var("POSTGRES_DB").unwrap()
             ),
-            aws_cognito_user_pool_client_id: env::var("AWS_COGNITO_USER_POOL_CLIENT_ID").unwrap(),
+            aws_cognito_user_pool_client_id: env::var("AWS_COGNITO_USER_POOL_CLIENT_ID")
+                .unwrap_or_default(),
+            authentication_provider,
+            cognito,
+            hosted_ui,
+            local_authentication,
+            oidc_providers: OidcProviderConfig::from_env()?,
+            group_mapping: GroupMapping::parse(
+                &env::var("AUTH_GROUP_ROLES").unwrap_or_default(),
+                &env::var("AUTH_GROUP_PERMISSIONS").unwrap_or_default(),
+            ),
+            cookies: CookieConfig::from_env(),
+            token_denylist_store: env::var("AUTH_TOKEN_DENYLIST_STORE")
+                .unwrap_or_else(|_| "postgres".to_string())
+                .parse()?,
+            access_token_lifetime,
+            trusted_proxies: env::var("TRUSTED_PROXIES").unwrap_or_default().parse()?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_authentication_provider() {
+        assert_eq!(
+            "Local".parse::<AuthenticationProvider>().unwrap(),
+            AuthenticationProvider::Local
+        );
+        assert_eq!(
+            "cognito".parse::<AuthenticationProvider>().unwrap(),
+            AuthenticationProvider::Cognito
+        );
+        assert!(matches!(
+            "locl".parse::<AuthenticationProvider>(),
+            Err(ConfigError::InvalidValue {
+                name: "AUTHENTICATION_PROVIDER",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_token_denylist_store() {
+        assert_eq!(
+            "memory".parse::<TokenDenylistStore>().unwrap(),
+            TokenDenylistStore::Memory
+        );
+        assert_eq!(
+            "postgres".parse::<TokenDenylistStore>().unwrap(),
+            TokenDenylistStore::Postgres
+        );
+        assert!("redis".parse::<TokenDenylistStore>().is_err());
+    }
+
+    #[test]
+    fn test_parse_trusted_proxies() {
+        let proxies = "10.0.0.0/8, 192.0.2.1".parse::<TrustedProxies>().unwrap();
+        assert!(proxies.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(proxies.contains(&"192.0.2.1".parse().unwrap()));
+        assert!(!proxies.contains(&"192.0.2.2".parse().unwrap()));
+
+        assert!("".parse::<TrustedProxies>().unwrap().is_empty());
+        assert!(matches!(
+            "10.0.0.0/33".parse::<TrustedProxies>(),
+            Err(ConfigError::InvalidValue {
+                name: "TRUSTED_PROXIES",
+                ..
+            })
+        ));
+    }
+}
@@ -2,5 +2,15 @@
 // これらはデータベースのテーブルとマッピングします。
 
 // データベースモデル
+pub mod api_token;
 pub mod identity_link;
+pub mod local_credential;
+pub mod permission;
+pub mod revoked_token;
+pub mod role;
+pub mod role_permission;
+pub mod session;
+pub mod sign_in_code_request;
+pub mod sign_up_compensation;
 pub mod user;
+pub mod user_role_assignment;
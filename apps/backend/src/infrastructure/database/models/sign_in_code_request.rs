@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::entities::sign_in_code_request::SignInCodeRequest;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "sign_in_code_requests")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub email: String,
+    pub provider_session: Option<String>,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for SignInCodeRequest {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            email: model.email,
+            provider_session: model.provider_session,
+            attempts: model.attempts,
+            expires_at: model.expires_at,
+            consumed_at: model.consumed_at,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
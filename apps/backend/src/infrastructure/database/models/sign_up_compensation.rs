@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+use crate::domain::entities::sign_up_compensation::SignUpCompensation;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "sign_up_compensations")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub provider: String,
+    pub username: String,
+    pub sub: String,
+    pub attempts: i32,
+    pub last_error: String,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for SignUpCompensation {
+    fn from(model: Model) -> Self {
+        Self {
+            id: model.id,
+            provider: model.provider,
+            username: model.username,
+            sub: model.sub,
+            attempts: model.attempts,
+            last_error: model.last_error,
+            resolved_at: model.resolved_at,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
@@ -3,6 +3,7 @@ use sea_orm::entity::prelude::*;
 use uuid::Uuid; // Add this line
 
 use crate::domain::entities::user::User;
+use crate::domain::enums::user_role::UserRole;
 
 use super::identity_link;
 
@@ -12,6 +13,8 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)] // Set auto_increment to false
     pub id: Uuid, // Changed from i32
     pub name: String,
+    pub role: String,
+    pub mfa_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -42,6 +45,8 @@ impl From<Model> for User {
         Self {
             id: model.id,
             name: model.name,
+            role: UserRole::from(model.role),
+            mfa_enabled: model.mfa_enabled,
             created_at: model.created_at,
             updated_at: model.updated_at,
             identity_links: vec![],
@@ -0,0 +1,83 @@
+use async_graphql::async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::entities::api_token::{ApiToken, NewApiToken};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
+use crate::domain::repositories::api_token_repository::ApiTokenRepository;
+use crate::infrastructure::database::models::api_token::{self, Entity as ApiTokenEntity};
+
+pub struct ApiTokenRepositoryImpl {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl ApiTokenRepositoryImpl {
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    async fn find_model(&self, id: Uuid) -> RepositoryResult<api_token::Model> {
+        ApiTokenEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("api token".to_string()))
+    }
+}
+
+#[async_trait]
+impl ApiTokenRepository for ApiTokenRepositoryImpl {
+    async fn create(&self, token: NewApiToken) -> RepositoryResult<ApiToken> {
+        let active_model = api_token::ActiveModel::from(token);
+        let model = active_model.insert(self.connection.as_ref()).await?;
+
+        Ok(ApiToken::from(model))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<ApiToken>> {
+        let model = ApiTokenEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?;
+
+        Ok(model.map(ApiToken::from))
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<ApiToken>> {
+        let model = ApiTokenEntity::find()
+            .filter(api_token::Column::TokenHash.eq(token_hash))
+            .one(self.connection.as_ref())
+            .await?;
+
+        Ok(model.map(ApiToken::from))
+    }
+
+    async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ApiToken>> {
+        let models = ApiTokenEntity::find()
+            .filter(api_token::Column::UserId.eq(user_id))
+            .filter(api_token::Column::RevokedAt.is_null())
+            .order_by_desc(api_token::Column::CreatedAt)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(models.into_iter().map(ApiToken::from).collect())
+    }
+
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()> {
+        let mut active_model: api_token::ActiveModel = self.find_model(id).await?.into();
+        active_model.last_used_at = ActiveValue::Set(Some(chrono::Utc::now()));
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<()> {
+        let mut active_model: api_token::ActiveModel = self.find_model(id).await?.into();
+        active_model.revoked_at = ActiveValue::Set(Some(chrono::Utc::now()));
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+}
@@ -1,12 +1,16 @@
-use anyhow::{Result, format_err};
 use async_graphql::async_trait::async_trait;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait,
+};
 use std::sync::Arc;
+use uuid::Uuid;
 
 use crate::domain::entities::identity_link::{IdentityLink, NewIdentityLink};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
 use crate::domain::repositories::identity_link_repository::IdentityLinkRepository;
-use crate::infrastructure::database::models::identity_link;
 use crate::infrastructure::database::models::identity_link::Entity as IdentityLinkEntity;
+use crate::infrastructure::database::models::{identity_link, user};
 
 pub struct IdentityLinkRepositoryImpl {
     connection: Arc<DatabaseConnection>,
@@ -20,20 +24,144 @@ impl IdentityLinkRepositoryImpl {
 
 #[async_trait]
 impl IdentityLinkRepository for IdentityLinkRepositoryImpl {
-    async fn create(&self, new_oauth_user: NewIdentityLink) -> Result<IdentityLink> {
+    async fn create(&self, new_oauth_user: NewIdentityLink) -> RepositoryResult<IdentityLink> {
         let active_model = identity_link::ActiveModel::from(new_oauth_user);
         let model = active_model.insert(self.connection.as_ref()).await?;
 
         Ok(IdentityLink::from(model))
     }
-    
-    async fn find_by_sub(&self, sub: &str) -> Result<IdentityLink> {
+
+    async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink> {
         let identity_link = IdentityLinkEntity::find()
+            .filter(identity_link::Column::Provider.eq(provider))
             .filter(identity_link::Column::Sub.eq(sub))
             .one(self.connection.as_ref())
             .await?
-            .ok_or_else(|| format_err!("Identity link not found for sub: {}", sub))?;
-            
+            .ok_or_else(|| RepositoryError::NotFound("identity link".to_string()))?;
+
         Ok(IdentityLink::from(identity_link))
     }
+
+    async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>> {
+        let identity_links = IdentityLinkEntity::find()
+            .filter(identity_link::Column::UserId.eq(user_id))
+            .order_by_asc(identity_link::Column::CreatedAt)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(identity_links.into_iter().map(IdentityLink::from).collect())
+    }
+
+    async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()> {
+        let transaction = self.connection.begin().await?;
+        // ユーザーの行をロックし、同時に別の紐付けを解除されて0件になるのを防ぐ
+        user::Entity::find_by_id(user_id)
+            .lock_exclusive()
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("identity link".to_string()))?;
+        let identity_links = IdentityLinkEntity::find()
+            .filter(identity_link::Column::UserId.eq(user_id))
+            .all(&transaction)
+            .await?;
+        if !identity_links
+            .iter()
+            .any(|identity_link| identity_link.id == id)
+        {
+            return Err(RepositoryError::NotFound("identity link".to_string()));
+        }
+        if identity_links.len() == 1 {
+            return Err(RepositoryError::Conflict("last identity link".to_string()));
+        }
+
+        IdentityLinkEntity::delete_by_id(id)
+            .exec(&transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Transaction};
+
+    fn user_model(id: Uuid) -> user::Model {
+        let now = chrono::Utc::now();
+        user::Model {
+            id,
+            name: "user".to_string(),
+            role: "user".to_string(),
+            mfa_enabled: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn identity_link_model(id: Uuid, user_id: Uuid, provider: &str) -> identity_link::Model {
+        let now = chrono::Utc::now();
+        identity_link::Model {
+            id,
+            provider: provider.to_string(),
+            sub: format!("{}-sub-123", provider),
+            user_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // 紐付けの解除を試し、結果と発行されたSQLを返す
+    async fn delete_unless_last(identity_links: usize) -> (RepositoryResult<()>, Vec<Transaction>) {
+        let (user_id, id) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut models = vec![identity_link_model(id, user_id, "cognito")];
+        for _ in 1..identity_links {
+            models.push(identity_link_model(Uuid::new_v4(), user_id, "google"));
+        }
+        let connection = Arc::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([vec![user_model(user_id)]])
+                .append_query_results([models])
+                .append_exec_results([MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                }])
+                .into_connection(),
+        );
+        let repository = IdentityLinkRepositoryImpl::new(connection.clone());
+
+        let result = repository.delete_unless_last(user_id, id).await;
+
+        drop(repository);
+        let log = Arc::try_unwrap(connection).unwrap().into_transaction_log();
+        (result, log)
+    }
+
+    fn statements(log: &[Transaction]) -> Vec<String> {
+        log.iter()
+            .flat_map(|transaction| transaction.statements())
+            .map(|statement| statement.sql.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_delete_unless_last_counts_under_user_lock() {
+        let (result, log) = delete_unless_last(2).await;
+
+        assert!(result.is_ok());
+        // 件数の確認と削除は、ユーザーの行をロックした同じトランザクションで行う
+        assert_eq!(log.len(), 1);
+        let statements = statements(&log);
+        assert!(statements[1].starts_with(r#"SELECT "users"."id""#));
+        assert!(statements[1].ends_with("FOR UPDATE"));
+        assert!(statements[3].starts_with(r#"DELETE FROM "identity_links""#));
+    }
+
+    #[tokio::test]
+    async fn test_delete_unless_last_keeps_last_identity_link() {
+        let (result, log) = delete_unless_last(1).await;
+
+        assert!(matches!(result, Err(RepositoryError::Conflict(_))));
+        assert!(!statements(&log).iter().any(|sql| sql.starts_with("DELETE")));
+    }
 }
@@ -1,21 +1,55 @@
 use crate::application::services::Repositories;
-use sea_orm::DatabaseConnection;
+use crate::domain::errors::RepositoryError;
+use sea_orm::{DatabaseConnection, DbErr, SqlErr};
 use std::sync::Arc;
 
 // リポジトリモジュールのインポート
+pub mod api_token_repository_impl;
 pub mod identity_link_repository_impl;
+pub mod permission_repository_impl;
+pub mod revoked_token_repository_impl;
+pub mod session_repository_impl;
+pub mod sign_in_code_request_repository_impl;
+pub mod sign_up_compensation_repository_impl;
 pub mod user_repository_impl;
 
 // エクスポート
+pub use api_token_repository_impl::ApiTokenRepositoryImpl;
 pub use identity_link_repository_impl::IdentityLinkRepositoryImpl;
+pub use permission_repository_impl::PermissionRepositoryImpl;
+pub use revoked_token_repository_impl::RevokedTokenRepositoryImpl;
+pub use session_repository_impl::SessionRepositoryImpl;
+pub use sign_in_code_request_repository_impl::SignInCodeRequestRepositoryImpl;
+pub use sign_up_compensation_repository_impl::SignUpCompensationRepositoryImpl;
 pub use user_repository_impl::UserRepositoryImpl;
 
 // リポジトリを初期化する関数
-pub fn init_repositories(connection: DatabaseConnection) -> Repositories {
+pub fn init_repositories(connection: Arc<DatabaseConnection>) -> Repositories {
     Repositories {
-        user_repository: Arc::new(UserRepositoryImpl::new(Arc::new(connection.clone()))),
-        identity_link_repository: Arc::new(IdentityLinkRepositoryImpl::new(Arc::new(
+        user_repository: Arc::new(UserRepositoryImpl::new(connection.clone())),
+        identity_link_repository: Arc::new(IdentityLinkRepositoryImpl::new(connection.clone())),
+        sign_up_compensation_repository: Arc::new(SignUpCompensationRepositoryImpl::new(
             connection.clone(),
-        ))),
+        )),
+        permission_repository: Arc::new(PermissionRepositoryImpl::new(connection.clone())),
+        session_repository: Arc::new(SessionRepositoryImpl::new(connection.clone())),
+        revoked_token_repository: Arc::new(RevokedTokenRepositoryImpl::new(connection.clone())),
+        api_token_repository: Arc::new(ApiTokenRepositoryImpl::new(connection.clone())),
+        sign_in_code_request_repository: Arc::new(SignInCodeRequestRepositoryImpl::new(connection)),
+    }
+}
+
+// 一意制約違反は競合として扱い、それ以外のDBエラーは詳細を保持したまま内部エラーとする
+impl From<DbErr> for RepositoryError {
+    fn from(error: DbErr) -> Self {
+        if let Some(SqlErr::UniqueConstraintViolation(message)) = error.sql_err() {
+            return RepositoryError::Conflict(message);
+        }
+        match error {
+            DbErr::RecordNotFound(_) | DbErr::RecordNotUpdated => {
+                RepositoryError::NotFound("record".to_string())
+            }
+            error => RepositoryError::Database(error.to_string()),
+        }
     }
 }
@@ -0,0 +1,225 @@
+use async_graphql::async_trait::async_trait;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    JoinType, ModelTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait,
+};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::entities::permission::Permission;
+use crate::domain::entities::role::{NewRole, Role};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
+use crate::domain::repositories::permission_repository::PermissionRepository;
+use crate::infrastructure::database::models::{
+    permission, role, role_permission, user_role_assignment,
+};
+
+pub struct PermissionRepositoryImpl {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl PermissionRepositoryImpl {
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    async fn find_role_model<C: ConnectionTrait>(
+        connection: &C,
+        role_id: Uuid,
+    ) -> RepositoryResult<role::Model> {
+        role::Entity::find_by_id(role_id)
+            .one(connection)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("role".to_string()))
+    }
+
+    // ロールの権限を置き換える（存在しない権限名が含まれる場合はNotFound）
+    async fn replace_permissions<C: ConnectionTrait>(
+        connection: &C,
+        role_id: Uuid,
+        permissions: Vec<String>,
+    ) -> RepositoryResult<Vec<String>> {
+        let names: BTreeSet<String> = permissions.into_iter().collect();
+        let models = permission::Entity::find()
+            .filter(permission::Column::Name.is_in(names.iter().cloned()))
+            .all(connection)
+            .await?;
+        if models.len() != names.len() {
+            return Err(RepositoryError::NotFound("permission".to_string()));
+        }
+
+        role_permission::Entity::delete_many()
+            .filter(role_permission::Column::RoleId.eq(role_id))
+            .exec(connection)
+            .await?;
+        if !models.is_empty() {
+            role_permission::Entity::insert_many(models.iter().map(|model| {
+                role_permission::ActiveModel {
+                    role_id: ActiveValue::Set(role_id),
+                    permission_id: ActiveValue::Set(model.id),
+                }
+            }))
+            .exec_without_returning(connection)
+            .await?;
+        }
+
+        Ok(names.into_iter().collect())
+    }
+}
+
+// 権限名はロールごとに名前順で返す
+fn to_roles(models: Vec<(role::Model, Vec<permission::Model>)>) -> Vec<Role> {
+    models
+        .into_iter()
+        .map(|(model, permissions)| {
+            let mut permissions: Vec<String> = permissions
+                .into_iter()
+                .map(|permission| permission.name)
+                .collect();
+            permissions.sort();
+            to_role(model, permissions)
+        })
+        .collect()
+}
+
+fn to_role(model: role::Model, permissions: Vec<String>) -> Role {
+    Role {
+        id: model.id,
+        name: model.name,
+        description: model.description,
+        permissions,
+        created_at: model.created_at,
+        updated_at: model.updated_at,
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for PermissionRepositoryImpl {
+    async fn find_permissions_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<String>> {
+        let names = permission::Entity::find()
+            .select_only()
+            .column(permission::Column::Name)
+            .distinct()
+            .join(
+                JoinType::InnerJoin,
+                permission::Relation::RolePermission.def(),
+            )
+            .join(JoinType::InnerJoin, role_permission::Relation::Role.def())
+            .join(
+                JoinType::InnerJoin,
+                role::Relation::UserRoleAssignment.def(),
+            )
+            .filter(user_role_assignment::Column::UserId.eq(user_id))
+            .order_by_asc(permission::Column::Name)
+            .into_tuple::<String>()
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(names)
+    }
+
+    async fn find_all_permissions(&self) -> RepositoryResult<Vec<Permission>> {
+        let models = permission::Entity::find()
+            .order_by_asc(permission::Column::Name)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(models.into_iter().map(Permission::from).collect())
+    }
+
+    async fn find_all_roles(&self) -> RepositoryResult<Vec<Role>> {
+        let models = role::Entity::find()
+            .order_by_asc(role::Column::Name)
+            .find_with_related(permission::Entity)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(to_roles(models))
+    }
+
+    async fn find_roles_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Role>> {
+        let role_ids = user_role_assignment::Entity::find()
+            .filter(user_role_assignment::Column::UserId.eq(user_id))
+            .all(self.connection.as_ref())
+            .await?
+            .into_iter()
+            .map(|assignment| assignment.role_id);
+        let models = role::Entity::find()
+            .filter(role::Column::Id.is_in(role_ids))
+            .order_by_asc(role::Column::Name)
+            .find_with_related(permission::Entity)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(to_roles(models))
+    }
+
+    async fn create_role(&self, new_role: NewRole) -> RepositoryResult<Role> {
+        let permissions = new_role.permissions.clone();
+        let transaction = self.connection.begin().await?;
+        let model = role::ActiveModel::from(new_role)
+            .insert(&transaction)
+            .await?;
+        let permissions = Self::replace_permissions(&transaction, model.id, permissions).await?;
+        transaction.commit().await?;
+
+        Ok(to_role(model, permissions))
+    }
+
+    async fn set_role_permissions(
+        &self,
+        role_id: Uuid,
+        permissions: Vec<String>,
+    ) -> RepositoryResult<Role> {
+        let transaction = self.connection.begin().await?;
+        let model = Self::find_role_model(&transaction, role_id).await?;
+        let permissions = Self::replace_permissions(&transaction, role_id, permissions).await?;
+        let mut active_model: role::ActiveModel = model.into();
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        let model = active_model.update(&transaction).await?;
+        transaction.commit().await?;
+
+        Ok(to_role(model, permissions))
+    }
+
+    async fn delete_role(&self, role_id: Uuid) -> RepositoryResult<()> {
+        let model = Self::find_role_model(self.connection.as_ref(), role_id).await?;
+        // 権限と割り当てはON DELETE CASCADEで削除される
+        model.delete(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()> {
+        Self::find_role_model(self.connection.as_ref(), role_id).await?;
+        user_role_assignment::Entity::insert(user_role_assignment::ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            role_id: ActiveValue::Set(role_id),
+            created_at: ActiveValue::Set(chrono::Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::columns([
+                user_role_assignment::Column::UserId,
+                user_role_assignment::Column::RoleId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(self.connection.as_ref())
+        .await?;
+        Ok(())
+    }
+
+    async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()> {
+        let result = user_role_assignment::Entity::delete_many()
+            .filter(user_role_assignment::Column::UserId.eq(user_id))
+            .filter(user_role_assignment::Column::RoleId.eq(role_id))
+            .exec(self.connection.as_ref())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound("role assignment".to_string()));
+        }
+        Ok(())
+    }
+}
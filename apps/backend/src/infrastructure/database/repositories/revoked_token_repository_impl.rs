@@ -0,0 +1,61 @@
+use async_graphql::async_trait::async_trait;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    sea_query::OnConflict,
+};
+use std::sync::Arc;
+
+use crate::domain::entities::revoked_token::RevokedToken;
+use crate::domain::errors::RepositoryResult;
+use crate::domain::repositories::revoked_token_repository::RevokedTokenRepository;
+use crate::infrastructure::database::models::revoked_token::{self, Entity as RevokedTokenEntity};
+
+pub struct RevokedTokenRepositoryImpl {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl RevokedTokenRepositoryImpl {
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+#[async_trait]
+impl RevokedTokenRepository for RevokedTokenRepositoryImpl {
+    async fn add(&self, token: RevokedToken) -> RepositoryResult<()> {
+        RevokedTokenEntity::insert(revoked_token::ActiveModel::from(token))
+            .on_conflict(
+                OnConflict::column(revoked_token::Column::Jti)
+                    .update_columns([
+                        revoked_token::Column::ExpiresAt,
+                        revoked_token::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(self.connection.as_ref())
+            .await?;
+        Ok(())
+    }
+
+    async fn contains_any(&self, jtis: &[String]) -> RepositoryResult<bool> {
+        if jtis.is_empty() {
+            return Ok(false);
+        }
+        let count = RevokedTokenEntity::find()
+            .filter(revoked_token::Column::Jti.is_in(jtis.iter().cloned()))
+            .filter(revoked_token::Column::ExpiresAt.gt(chrono::Utc::now()))
+            .count(self.connection.as_ref())
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    async fn purge_expired(&self) -> RepositoryResult<u64> {
+        let result = RevokedTokenEntity::delete_many()
+            .filter(revoked_token::Column::ExpiresAt.lte(chrono::Utc::now()))
+            .exec(self.connection.as_ref())
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
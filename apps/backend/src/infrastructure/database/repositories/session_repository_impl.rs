@@ -0,0 +1,94 @@
+use async_graphql::async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::entities::session::{NewSession, Session};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
+use crate::domain::repositories::session_repository::SessionRepository;
+use crate::infrastructure::database::models::session::{self, Entity as SessionEntity};
+
+pub struct SessionRepositoryImpl {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl SessionRepositoryImpl {
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    async fn find_model(&self, id: Uuid) -> RepositoryResult<session::Model> {
+        SessionEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("session".to_string()))
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SessionRepositoryImpl {
+    async fn create(&self, session: NewSession) -> RepositoryResult<Session> {
+        let active_model = session::ActiveModel::from(session);
+        let model = active_model.insert(self.connection.as_ref()).await?;
+
+        Ok(Session::from(model))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>> {
+        let model = SessionEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?;
+
+        Ok(model.map(Session::from))
+    }
+
+    async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>> {
+        let model = SessionEntity::find()
+            .filter(session::Column::OriginJti.eq(origin_jti))
+            .one(self.connection.as_ref())
+            .await?;
+
+        Ok(model.map(Session::from))
+    }
+
+    async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        let models = SessionEntity::find()
+            .filter(session::Column::UserId.eq(user_id))
+            .filter(session::Column::RevokedAt.is_null())
+            .order_by_desc(session::Column::LastUsedAt)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(models.into_iter().map(Session::from).collect())
+    }
+
+    async fn touch(&self, id: Uuid) -> RepositoryResult<()> {
+        let mut active_model: session::ActiveModel = self.find_model(id).await?.into();
+        active_model.last_used_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn revoke(&self, id: Uuid) -> RepositoryResult<()> {
+        let mut active_model: session::ActiveModel = self.find_model(id).await?.into();
+        active_model.revoked_at = ActiveValue::Set(Some(chrono::Utc::now()));
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()> {
+        SessionEntity::update_many()
+            .col_expr(session::Column::RevokedAt, chrono::Utc::now().into())
+            .col_expr(session::Column::UpdatedAt, chrono::Utc::now().into())
+            .filter(session::Column::UserId.eq(user_id))
+            .filter(session::Column::RevokedAt.is_null())
+            .exec(self.connection.as_ref())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use async_graphql::async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    sea_query::Expr,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::entities::sign_in_code_request::{NewSignInCodeRequest, SignInCodeRequest};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
+use crate::domain::repositories::sign_in_code_request_repository::SignInCodeRequestRepository;
+use crate::infrastructure::database::models::sign_in_code_request::{
+    self, Entity as SignInCodeRequestEntity,
+};
+
+pub struct SignInCodeRequestRepositoryImpl {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl SignInCodeRequestRepositoryImpl {
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+}
+
+fn not_found() -> RepositoryError {
+    RepositoryError::NotFound("sign in code request".to_string())
+}
+
+#[async_trait]
+impl SignInCodeRequestRepository for SignInCodeRequestRepositoryImpl {
+    async fn create(&self, request: NewSignInCodeRequest) -> RepositoryResult<SignInCodeRequest> {
+        let active_model = sign_in_code_request::ActiveModel::from(request);
+        let model = active_model.insert(self.connection.as_ref()).await?;
+
+        Ok(SignInCodeRequest::from(model))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<SignInCodeRequest>> {
+        let model = SignInCodeRequestEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?;
+
+        Ok(model.map(SignInCodeRequest::from))
+    }
+
+    async fn count_by_email_since(
+        &self,
+        email: &str,
+        since: DateTime<Utc>,
+    ) -> RepositoryResult<u64> {
+        let count = SignInCodeRequestEntity::find()
+            .filter(sign_in_code_request::Column::Email.eq(email))
+            .filter(sign_in_code_request::Column::CreatedAt.gte(since))
+            .count(self.connection.as_ref())
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn start_attempt(
+        &self,
+        id: Uuid,
+        max_attempts: i32,
+        now: DateTime<Utc>,
+    ) -> RepositoryResult<Option<SignInCodeRequest>> {
+        // 条件の確認と試行回数の加算を同じ行の更新で行い、同時のリクエストでも上限を超えない
+        let models = SignInCodeRequestEntity::update_many()
+            .col_expr(
+                sign_in_code_request::Column::Attempts,
+                Expr::col(sign_in_code_request::Column::Attempts).add(1),
+            )
+            .col_expr(sign_in_code_request::Column::UpdatedAt, Expr::value(now))
+            .filter(sign_in_code_request::Column::Id.eq(id))
+            .filter(sign_in_code_request::Column::ConsumedAt.is_null())
+            .filter(sign_in_code_request::Column::ExpiresAt.gt(now))
+            .filter(sign_in_code_request::Column::Attempts.lt(max_attempts))
+            .exec_with_returning(self.connection.as_ref())
+            .await?;
+
+        Ok(models.into_iter().next().map(SignInCodeRequest::from))
+    }
+
+    async fn update_provider_session(
+        &self,
+        id: Uuid,
+        provider_session: Option<String>,
+    ) -> RepositoryResult<()> {
+        let result = SignInCodeRequestEntity::update_many()
+            .col_expr(
+                sign_in_code_request::Column::ProviderSession,
+                Expr::value(provider_session),
+            )
+            .col_expr(
+                sign_in_code_request::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(sign_in_code_request::Column::Id.eq(id))
+            .exec(self.connection.as_ref())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found());
+        }
+        Ok(())
+    }
+
+    async fn consume(&self, id: Uuid) -> RepositoryResult<()> {
+        // 同じセッションで同時にサインインを完了させない
+        let result = SignInCodeRequestEntity::update_many()
+            .col_expr(
+                sign_in_code_request::Column::ConsumedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .col_expr(
+                sign_in_code_request::Column::UpdatedAt,
+                Expr::value(chrono::Utc::now()),
+            )
+            .filter(sign_in_code_request::Column::Id.eq(id))
+            .filter(sign_in_code_request::Column::ConsumedAt.is_null())
+            .exec(self.connection.as_ref())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(not_found());
+        }
+        Ok(())
+    }
+
+    async fn delete_created_before(&self, before: DateTime<Utc>) -> RepositoryResult<u64> {
+        let result = SignInCodeRequestEntity::delete_many()
+            .filter(sign_in_code_request::Column::CreatedAt.lt(before))
+            .exec(self.connection.as_ref())
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    #[tokio::test]
+    async fn test_start_attempt_checks_and_counts_in_one_update() {
+        let connection = Arc::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([Vec::<sign_in_code_request::Model>::new()])
+                .into_connection(),
+        );
+        let repository = SignInCodeRequestRepositoryImpl::new(connection.clone());
+
+        let request = repository
+            .start_attempt(Uuid::new_v4(), 3, Utc::now())
+            .await
+            .unwrap();
+
+        // 使用済み、期限切れ、上限に達した要求は更新されない
+        assert!(request.is_none());
+        drop(repository);
+        let log = Arc::try_unwrap(connection).unwrap().into_transaction_log();
+        assert_eq!(log.len(), 1);
+        let statements = log[0].statements();
+        assert_eq!(statements.len(), 1);
+        let sql = &statements[0].sql;
+        assert!(
+            sql.starts_with(r#"UPDATE "sign_in_code_requests" SET "attempts" = "attempts" + "#)
+        );
+        assert!(sql.contains(r#""consumed_at" IS NULL"#));
+        assert!(sql.contains(r#""expires_at" > "#));
+        assert!(sql.contains(r#""attempts" < "#));
+        assert!(sql.contains("RETURNING"));
+    }
+}
@@ -0,0 +1,73 @@
+use async_graphql::async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::entities::sign_up_compensation::{NewSignUpCompensation, SignUpCompensation};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
+use crate::domain::repositories::sign_up_compensation_repository::SignUpCompensationRepository;
+use crate::infrastructure::database::models::sign_up_compensation::{
+    self, Entity as SignUpCompensationEntity,
+};
+
+pub struct SignUpCompensationRepositoryImpl {
+    connection: Arc<DatabaseConnection>,
+}
+
+impl SignUpCompensationRepositoryImpl {
+    pub fn new(connection: Arc<DatabaseConnection>) -> Self {
+        Self { connection }
+    }
+
+    async fn find_model(&self, id: Uuid) -> RepositoryResult<sign_up_compensation::Model> {
+        SignUpCompensationEntity::find_by_id(id)
+            .one(self.connection.as_ref())
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("sign up compensation".to_string()))
+    }
+}
+
+#[async_trait]
+impl SignUpCompensationRepository for SignUpCompensationRepositoryImpl {
+    async fn create(
+        &self,
+        compensation: NewSignUpCompensation,
+    ) -> RepositoryResult<SignUpCompensation> {
+        let active_model = sign_up_compensation::ActiveModel::from(compensation);
+        let model = active_model.insert(self.connection.as_ref()).await?;
+
+        Ok(SignUpCompensation::from(model))
+    }
+
+    async fn find_unresolved(&self) -> RepositoryResult<Vec<SignUpCompensation>> {
+        let models = SignUpCompensationEntity::find()
+            .filter(sign_up_compensation::Column::ResolvedAt.is_null())
+            .order_by_asc(sign_up_compensation::Column::CreatedAt)
+            .all(self.connection.as_ref())
+            .await?;
+
+        Ok(models.into_iter().map(SignUpCompensation::from).collect())
+    }
+
+    async fn mark_resolved(&self, id: Uuid) -> RepositoryResult<()> {
+        let mut active_model: sign_up_compensation::ActiveModel = self.find_model(id).await?.into();
+        active_model.resolved_at = ActiveValue::Set(Some(chrono::Utc::now()));
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+
+    async fn record_failure(&self, id: Uuid, error: &str) -> RepositoryResult<()> {
+        let model = self.find_model(id).await?;
+        let attempts = model.attempts + 1;
+        let mut active_model: sign_up_compensation::ActiveModel = model.into();
+        active_model.attempts = ActiveValue::Set(attempts);
+        active_model.last_error = ActiveValue::Set(error.to_string());
+        active_model.updated_at = ActiveValue::Set(chrono::Utc::now());
+        active_model.update(self.connection.as_ref()).await?;
+        Ok(())
+    }
+}
@@ -1,14 +1,22 @@
-use anyhow::Result;
 use async_graphql::async_trait::async_trait;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    TransactionTrait, sea_query::OnConflict,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::domain::entities::user::{NewUser, User};
+use crate::domain::entities::identity_link::{IdentityLink, NewIdentityLink};
+use crate::domain::entities::user::{NewUser, User, UserChanges};
+use crate::domain::errors::{RepositoryError, RepositoryResult};
 use crate::domain::repositories::user_repository::UserRepository;
+use crate::infrastructure::database::models::identity_link::ActiveModel as IdentityLinkActiveModel;
 use crate::infrastructure::database::models::user::{
     ActiveModel as UserActiveModel, Entity as UserEntity,
 };
+use crate::infrastructure::database::models::{
+    api_token, identity_link, session, user_role_assignment,
+};
 
 pub struct UserRepositoryImpl {
     connection: Arc<DatabaseConnection>,
@@ -22,14 +30,37 @@ impl UserRepositoryImpl {
 
 #[async_trait]
 impl UserRepository for UserRepositoryImpl {
-    async fn create(&self, new_user: NewUser) -> Result<User> {
+    async fn create(&self, new_user: NewUser) -> RepositoryResult<User> {
         let active_model = UserActiveModel::from(new_user);
         let model = active_model.insert(self.connection.as_ref()).await?;
 
         Ok(User::from(model))
     }
 
-    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
+    async fn create_with_identity_link(
+        &self,
+        new_user: NewUser,
+        provider: String,
+        sub: String,
+    ) -> RepositoryResult<(User, IdentityLink)> {
+        let transaction = self.connection.begin().await?;
+        let user_model = UserActiveModel::from(new_user).insert(&transaction).await?;
+        let identity_link_model = IdentityLinkActiveModel::from(NewIdentityLink {
+            provider,
+            sub,
+            user_id: user_model.id,
+        })
+        .insert(&transaction)
+        .await?;
+        transaction.commit().await?;
+
+        Ok((
+            User::from(user_model),
+            IdentityLink::from(identity_link_model),
+        ))
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>> {
         let model = UserEntity::find_by_id(id)
             .one(self.connection.as_ref())
             .await?;
@@ -37,23 +68,220 @@ impl UserRepository for UserRepositoryImpl {
         Ok(model.map(User::from))
     }
 
-    async fn find_all(&self) -> Result<Vec<User>> {
+    async fn find_all(&self) -> RepositoryResult<Vec<User>> {
         let models = UserEntity::find().all(self.connection.as_ref()).await?;
 
         Ok(models.into_iter().map(User::from).collect())
     }
 
-    async fn update(&self, user: User) -> Result<User> {
-        let active_model = UserActiveModel::from(user);
+    async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User> {
+        let mut active_model = UserActiveModel::from(changes);
+        active_model.id = ActiveValue::Unchanged(id);
         let model = active_model.update(self.connection.as_ref()).await?;
 
         Ok(User::from(model))
     }
 
-    async fn delete(&self, id: Uuid) -> Result<()> {
+    async fn delete(&self, id: Uuid) -> RepositoryResult<()> {
         UserEntity::delete_by_id(id)
             .exec(self.connection.as_ref())
             .await?;
         Ok(())
     }
+
+    async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User> {
+        let transaction = self.connection.begin().await?;
+        UserEntity::find_by_id(target_id)
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("user".to_string()))?;
+        // ロールはtargetのものを維持する（sourceの管理者権限を引き継いで昇格させない）
+        UserEntity::find_by_id(source_id)
+            .one(&transaction)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("user".to_string()))?;
+
+        identity_link::Entity::update_many()
+            .col_expr(identity_link::Column::UserId, target_id.into())
+            .filter(identity_link::Column::UserId.eq(source_id))
+            .exec(&transaction)
+            .await?;
+        session::Entity::update_many()
+            .col_expr(session::Column::UserId, target_id.into())
+            .filter(session::Column::UserId.eq(source_id))
+            .exec(&transaction)
+            .await?;
+        api_token::Entity::update_many()
+            .col_expr(api_token::Column::UserId, target_id.into())
+            .filter(api_token::Column::UserId.eq(source_id))
+            .exec(&transaction)
+            .await?;
+
+        // ロールは主キーが(user_id, role_id)のため、target側にないものだけを追加する
+        let assignments = user_role_assignment::Entity::find()
+            .filter(user_role_assignment::Column::UserId.eq(source_id))
+            .all(&transaction)
+            .await?;
+        for assignment in assignments {
+            user_role_assignment::Entity::insert(user_role_assignment::ActiveModel {
+                user_id: ActiveValue::Set(target_id),
+                role_id: ActiveValue::Set(assignment.role_id),
+                created_at: ActiveValue::Set(assignment.created_at),
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    user_role_assignment::Column::UserId,
+                    user_role_assignment::Column::RoleId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&transaction)
+            .await?;
+        }
+
+        // sourceに残ったロールの割り当てはON DELETE CASCADEで削除される
+        UserEntity::delete_by_id(source_id)
+            .exec(&transaction)
+            .await?;
+        transaction.commit().await?;
+
+        // 移したIdentityLinkとロールを反映したtargetを返す
+        let target = UserEntity::find_by_id(target_id)
+            .one(self.connection.as_ref())
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("user".to_string()))?;
+        let identity_links = identity_link::Entity::find()
+            .filter(identity_link::Column::UserId.eq(target_id))
+            .all(self.connection.as_ref())
+            .await?;
+        let mut merged_user = User::from(target);
+        merged_user.identity_links = identity_links.into_iter().map(IdentityLink::from).collect();
+        Ok(merged_user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::enums::user_role::UserRole;
+    use crate::infrastructure::database::models::user;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Transaction};
+
+    fn user_model(id: Uuid, role: UserRole) -> user::Model {
+        let now = chrono::Utc::now();
+        user::Model {
+            id,
+            name: "user".to_string(),
+            role: role.to_string(),
+            mfa_enabled: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn identity_link_model(user_id: Uuid, provider: &str) -> identity_link::Model {
+        let now = chrono::Utc::now();
+        identity_link::Model {
+            id: Uuid::new_v4(),
+            provider: provider.to_string(),
+            sub: format!("{}-sub-123", provider),
+            user_id,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    // sourceとtargetをまとめ、まとめた結果と発行されたSQLを返す
+    async fn merge(source_role: UserRole, target_role: UserRole) -> (User, Vec<Transaction>) {
+        let (source_id, target_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let connection = Arc::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([
+                    vec![user_model(target_id, target_role)],
+                    vec![user_model(source_id, source_role)],
+                ])
+                .append_query_results([Vec::<user_role_assignment::Model>::new()])
+                .append_query_results([vec![user_model(target_id, target_role)]])
+                .append_query_results([vec![
+                    identity_link_model(target_id, "cognito"),
+                    identity_link_model(target_id, "google"),
+                ]])
+                .append_exec_results(vec![
+                    MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    };
+                    4
+                ])
+                .into_connection(),
+        );
+        let repository = UserRepositoryImpl::new(connection.clone());
+
+        let merged_user = repository.merge(source_id, target_id).await.unwrap();
+
+        drop(repository);
+        let log = Arc::try_unwrap(connection).unwrap().into_transaction_log();
+        (merged_user, log)
+    }
+
+    fn updates_role(log: &[Transaction]) -> bool {
+        log.iter()
+            .flat_map(|transaction| transaction.statements())
+            .any(|statement| statement.sql.starts_with(r#"UPDATE "users" SET "role""#))
+    }
+
+    #[tokio::test]
+    async fn test_merge_returns_target_with_moved_identity_links() {
+        let (merged_user, log) = merge(UserRole::User, UserRole::User).await;
+
+        assert_eq!(merged_user.role, UserRole::User);
+        assert_eq!(merged_user.identity_links.len(), 2);
+        assert!(!updates_role(&log));
+    }
+
+    #[tokio::test]
+    async fn test_merge_does_not_promote_target_to_admin_role_of_source() {
+        // 管理者のsourceをまとめても、targetは管理者に昇格しない
+        let (merged_user, log) = merge(UserRole::Admin, UserRole::User).await;
+
+        assert_eq!(merged_user.role, UserRole::User);
+        assert!(!updates_role(&log));
+    }
+
+    #[tokio::test]
+    async fn test_merge_keeps_admin_role_of_target() {
+        let (merged_user, log) = merge(UserRole::User, UserRole::Admin).await;
+
+        assert_eq!(merged_user.role, UserRole::Admin);
+        assert!(!updates_role(&log));
+    }
+
+    #[tokio::test]
+    async fn test_update_sets_only_changed_columns() {
+        let id = Uuid::new_v4();
+        let connection = Arc::new(
+            MockDatabase::new(DatabaseBackend::Postgres)
+                .append_query_results([vec![user_model(id, UserRole::Admin)]])
+                .into_connection(),
+        );
+        let repository = UserRepositoryImpl::new(connection.clone());
+
+        let changes = UserChanges {
+            name: Some("renamed".to_string()),
+            ..Default::default()
+        };
+        repository.update(id, changes).await.unwrap();
+
+        // 読み込んだ時点のロールやMFA設定で上書きしない
+        drop(repository);
+        let log = Arc::try_unwrap(connection).unwrap().into_transaction_log();
+        let sql = &log[0].statements()[0].sql;
+        assert!(
+            sql.starts_with(r#"UPDATE "users" SET "name" = $1 WHERE"#),
+            "{}",
+            sql
+        );
+        assert!(!sql.contains(r#""role" ="#) && !sql.contains(r#""mfa_enabled" ="#));
+    }
 }
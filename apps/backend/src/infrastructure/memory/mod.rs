@@ -0,0 +1,7 @@
+// このモジュールは、データベースを使わずにメモリ上で動くリポジトリ実装を提供します。
+// テストや、単一プロセスで動かす開発環境向けです。
+
+pub mod revoked_token_repository_impl;
+
+// エクスポート
+pub use revoked_token_repository_impl::InMemoryRevokedTokenRepository;
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::domain::entities::revoked_token::RevokedToken;
+use crate::domain::errors::RepositoryResult;
+use crate::domain::repositories::revoked_token_repository::RevokedTokenRepository;
+
+// プロセス内でだけ共有されるため、複数インスタンスで動かす場合はPostgres実装を使う
+// ロックを持ったままパニックしても以降のリクエストが失敗しないよう、ポイズニングの無いtokioのMutexを使う
+#[derive(Default)]
+pub struct InMemoryRevokedTokenRepository {
+    tokens: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl InMemoryRevokedTokenRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RevokedTokenRepository for InMemoryRevokedTokenRepository {
+    async fn add(&self, token: RevokedToken) -> RepositoryResult<()> {
+        self.tokens.lock().await.insert(token.jti, token.expires_at);
+        Ok(())
+    }
+
+    async fn contains_any(&self, jtis: &[String]) -> RepositoryResult<bool> {
+        let now = Utc::now();
+        let tokens = self.tokens.lock().await;
+        Ok(jtis
+            .iter()
+            .any(|jti| tokens.get(jti).is_some_and(|expires_at| *expires_at > now)))
+    }
+
+    async fn purge_expired(&self) -> RepositoryResult<u64> {
+        let now = Utc::now();
+        let mut tokens = self.tokens.lock().await;
+        let before = tokens.len();
+        tokens.retain(|_, expires_at| *expires_at > now);
+        Ok((before - tokens.len()) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn revoked_token(jti: &str, expires_in: Duration) -> RevokedToken {
+        RevokedToken {
+            jti: jti.to_string(),
+            expires_at: Utc::now() + expires_in,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_contains_any_ignores_expired_tokens() {
+        let repository = InMemoryRevokedTokenRepository::new();
+        repository
+            .add(revoked_token("active-jti", Duration::hours(1)))
+            .await
+            .unwrap();
+        repository
+            .add(revoked_token("expired-jti", -Duration::hours(1)))
+            .await
+            .unwrap();
+
+        assert!(
+            repository
+                .contains_any(&["other-jti".to_string(), "active-jti".to_string()])
+                .await
+                .unwrap()
+        );
+        assert!(
+            !repository
+                .contains_any(&["expired-jti".to_string()])
+                .await
+                .unwrap()
+        );
+        assert!(!repository.contains_any(&[]).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_removes_only_expired_tokens() {
+        let repository = InMemoryRevokedTokenRepository::new();
+        repository
+            .add(revoked_token("active-jti", Duration::hours(1)))
+            .await
+            .unwrap();
+        repository
+            .add(revoked_token("expired-jti", -Duration::hours(1)))
+            .await
+            .unwrap();
+
+        assert_eq!(repository.purge_expired().await.unwrap(), 1);
+        assert_eq!(repository.purge_expired().await.unwrap(), 0);
+        assert!(
+            repository
+                .contains_any(&["active-jti".to_string()])
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -1,6 +1,7 @@
 pub mod authentication;
 pub mod config;
 pub mod database;
+pub mod memory;
 
 // このモジュールは、外部システムとの連携を提供します。
 // データベース、外部API、ファイルシステムなどのインフラストラクチャを実装します。
@@ -1,18 +1,31 @@
-use backend::{application, infrastructure, presentation};
+use backend::{application, domain, infrastructure, presentation};
 
-use axum::Router;
+use application::services::TokenDenylist;
 use axum::middleware::from_fn_with_state;
 use axum::routing::get;
+use axum::{Extension, Router};
+use domain::services::authorization_code_service::AuthorizationCodeService;
 use dotenvy::dotenv;
-use infrastructure::config::app_config::AppConfig;
+use infrastructure::authentication::HostedUiClient;
+use infrastructure::config::app_config::{AppConfig, TokenDenylistStore};
+use infrastructure::memory::InMemoryRevokedTokenRepository;
+use presentation::http::handlers::authorization_handler::{
+    AuthorizationState, authorize, callback,
+};
 use presentation::http::handlers::graphql_handler::{graphql_handler, graphql_playground};
-use presentation::http::middlewares::authentication::authenticate_user;
+use presentation::http::middlewares::authentication::{AuthenticationState, authenticate_user};
 use presentation::{graphql::schema::build_schema, http::handlers::health::health_check};
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tower::ServiceBuilder;
-use tracing::{Level, info};
+use tracing::{Level, error, info};
 use tracing_subscriber::FmtSubscriber;
 
+const SIGN_UP_COMPENSATION_RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const TOKEN_DENYLIST_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SIGN_IN_CODE_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 環境変数のロード
@@ -31,41 +44,134 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Application configuration loaded");
 
     // データベース接続の確立
-    let connection =
-        infrastructure::database::connection::establish_connection(&config.database_url).await?;
+    let connection = Arc::new(
+        infrastructure::database::connection::establish_connection(&config.database_url).await?,
+    );
     info!("Database connection established");
 
-    let sdk_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-    info!("AWS SDK configuration loaded");
-
     // リポジトリの初期化
-    let repositories =
+    let mut repositories =
         infrastructure::database::repositories::init_repositories(connection.clone());
+    if config.token_denylist_store == TokenDenylistStore::Memory {
+        repositories.revoked_token_repository = Arc::new(InMemoryRevokedTokenRepository::new());
+    }
     info!("Repositories initialized");
 
-    let authentication_service =
-        Arc::new(infrastructure::authentication::cognito_service::CognitoService::new(&sdk_config));
+    // サインアウト済みのアクセストークンを期限まで拒否する
+    let token_denylist = Arc::new(TokenDenylist::new(
+        repositories.revoked_token_repository.clone(),
+        chrono::Duration::seconds(config.access_token_lifetime),
+    ));
+
+    // 認証サービスの初期化
+    let authentication_services =
+        infrastructure::authentication::init_authentication_services(&config, connection.clone())
+            .await?;
+    info!(
+        "Authentication services initialized: {}",
+        authentication_services.provider_names().join(", ")
+    );
 
     // アプリケーションサービスの初期化
-    let services = application::services::init_services(Arc::new(repositories.clone())).await;
+    let services = application::services::init_services(
+        Arc::new(repositories.clone()),
+        token_denylist.clone(),
+    )
+    .await;
     info!("Application services initialized");
 
+    // Hosted UIが設定されていれば、認可コードフローでのサインインを提供する
+    let authorization_code_service = config.hosted_ui.clone().map(|hosted_ui| {
+        Arc::new(HostedUiClient::new(hosted_ui)) as Arc<dyn AuthorizationCodeService>
+    });
+
     let use_cases = application::usecases::init_use_cases(
         Arc::new(repositories.clone()),
-        authentication_service,
+        authentication_services,
+        authorization_code_service.clone(),
+        config.group_mapping.clone(),
+        token_denylist.clone(),
     );
 
+    // 未解決のサインアップ補償を定期的に再試行する
+    let retry_sign_up_compensations = use_cases.retry_sign_up_compensations.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SIGN_UP_COMPENSATION_RETRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            match retry_sign_up_compensations.execute().await {
+                Ok(0) => {}
+                Ok(resolved) => info!("Resolved {} sign up compensations", resolved),
+                Err(e) => error!("Failed to retry sign up compensations: {}", e),
+            }
+        }
+    });
+
+    // 期限切れになった失効トークンの記録を定期的に削除する
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TOKEN_DENYLIST_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match token_denylist.purge_expired().await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} expired revoked tokens", purged),
+                Err(e) => error!("Failed to purge revoked tokens: {}", e),
+            }
+        }
+    });
+
+    // レート制限の期間を過ぎたワンタイムコードの要求を定期的に削除する
+    let request_sign_in_code = use_cases.request_sign_in_code.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SIGN_IN_CODE_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match request_sign_in_code.purge_expired().await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} sign in code requests", purged),
+                Err(e) => error!("Failed to purge sign in code requests: {}", e),
+            }
+        }
+    });
+
     // GraphQLスキーマの作成
     let schema = build_schema(&use_cases, &services);
     info!("GraphQL schema created");
 
     // HTTPルーターの作成
     // let router = build_routes(Arc::new(services), schema);
-    let router = Router::new()
+    let mut router = Router::new()
         .route("/health", get(health_check))
-        .route("/graphql", get(graphql_playground).post(graphql_handler))
+        .route("/graphql", get(graphql_playground).post(graphql_handler));
+    if let (Some(hosted_ui), Some(authorization_code_service), Some(sign_in)) = (
+        &config.hosted_ui,
+        authorization_code_service,
+        use_cases.sign_in_with_authorization_code.clone(),
+    ) {
+        router = router.merge(
+            Router::new()
+                .route("/auth/authorize", get(authorize))
+                .route("/auth/callback", get(callback))
+                .with_state(AuthorizationState {
+                    authorization_code_service,
+                    sign_in_with_authorization_code: sign_in,
+                    cookies: config.cookies.clone(),
+                    post_login_redirect: hosted_ui.post_login_redirect.clone(),
+                    secure_cookie: hosted_ui.secure_cookie,
+                    trusted_proxies: config.trusted_proxies.clone(),
+                }),
+        );
+        info!("Hosted UI sign in enabled at /auth/authorize");
+    }
+    let router = router
+        .layer(Extension(config.trusted_proxies.clone()))
         .layer(ServiceBuilder::new().layer(from_fn_with_state(
-            use_cases.authenticate_user,
+            AuthenticationState {
+                authenticate_user: use_cases.authenticate_user,
+                authenticate_api_token: use_cases.authenticate_api_token,
+                refresh_tokens: use_cases.refresh_tokens,
+                cookies: config.cookies.clone(),
+            },
             authenticate_user,
         )))
         .with_state(schema)
@@ -76,6 +182,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = tokio::net::TcpListener::bind(addr).await?;
     info!("Server listening on {}", listener.local_addr()?);
 
-    axum::serve(listener, router).await?;
+    // セッション一覧に接続元のIPアドレスを記録するため、接続情報をリクエストに含める
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
@@ -1,12 +1,72 @@
+use std::collections::HashSet;
+use uuid::Uuid;
+
 use crate::application::dtos::user_dto::UserDTO;
+use crate::domain::value_objects::authentication::Claims;
+use crate::presentation::graphql::errors::{authentication_failed, authentication_required};
+
+// アクセストークンで認証できなかった理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticationFailure {
+    InvalidToken,
+    ExpiredToken,
+    // トークンは有効だが、対応するユーザーが存在しない
+    UnknownUser,
+}
+
+impl AuthenticationFailure {
+    // extensions.reasonに入れる値
+    pub fn reason(&self) -> &'static str {
+        match self {
+            AuthenticationFailure::InvalidToken => "invalid_token",
+            AuthenticationFailure::ExpiredToken => "expired_token",
+            AuthenticationFailure::UnknownUser => "unknown_user",
+        }
+    }
 
-#[derive(Debug, Clone)]
+    pub fn message(&self) -> &'static str {
+        match self {
+            AuthenticationFailure::InvalidToken => "Invalid access token",
+            AuthenticationFailure::ExpiredToken => "Access token has expired",
+            AuthenticationFailure::UnknownUser => "No user is linked to this access token",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct UserContext {
     pub user: Option<UserDTO>,
+    pub access_token: Option<String>,
+    // Cookieモードで送られたリフレッシュトークン（現在のセッションのサインアウトに使う）
+    pub refresh_token: Option<String>,
+    // 割り当てられたロールから解決した実効権限
+    pub permissions: HashSet<String>,
+    // アクセストークンのクレーム（グループ・スコープ・有効期限など）
+    pub claims: Option<Claims>,
+    // トークンが送られたが認証できなかった場合の理由（トークンが無ければNone）
+    pub authentication_failure: Option<AuthenticationFailure>,
+    // Cookieで認証されたが、CSRFトークンがCookieと一致しなかった
+    pub csrf_failed: bool,
+    // パーソナルアクセストークンで認証された場合のトークンID
+    pub api_token_id: Option<Uuid>,
 }
 
-impl Default for UserContext {
-    fn default() -> Self {
-        Self { user: None }
+impl UserContext {
+    // 認証済みのユーザー。未認証の場合は、トークンを拒否した理由をエラーに含める
+    pub fn authenticated_user(&self) -> async_graphql::Result<&UserDTO> {
+        self.user
+            .as_ref()
+            .ok_or_else(|| match self.authentication_failure {
+                Some(failure) => authentication_failed(failure),
+                None => authentication_required(),
+            })
+    }
+
+    // 管理者はすべての権限を持つ
+    pub fn has_permission(&self, permission: &str) -> bool {
+        match &self.user {
+            Some(user) => user.role.is_admin() || self.permissions.contains(permission),
+            None => false,
+        }
     }
 }
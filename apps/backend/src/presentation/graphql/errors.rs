@@ -0,0 +1,215 @@
+use async_graphql::{Error, ErrorExtensions};
+
+use crate::application::errors::ApplicationError;
+use crate::domain::errors::AuthenticationError;
+use crate::presentation::graphql::context::AuthenticationFailure;
+
+// クライアントに返すエラーコード（extensions.code）
+pub const UNAUTHENTICATED: &str = "UNAUTHENTICATED";
+pub const FORBIDDEN: &str = "FORBIDDEN";
+pub const NOT_FOUND: &str = "NOT_FOUND";
+pub const CONFLICT: &str = "CONFLICT";
+pub const VALIDATION_FAILED: &str = "VALIDATION_FAILED";
+pub const INTERNAL: &str = "INTERNAL";
+
+// 認証プロバイダーのエラーを区別するためのエラーコード
+pub const NOT_AUTHORIZED: &str = "NOT_AUTHORIZED";
+pub const USER_NOT_FOUND: &str = "USER_NOT_FOUND";
+pub const USERNAME_EXISTS: &str = "USERNAME_EXISTS";
+pub const USER_NOT_CONFIRMED: &str = "USER_NOT_CONFIRMED";
+pub const PASSWORD_RESET_REQUIRED: &str = "PASSWORD_RESET_REQUIRED";
+pub const INVALID_PASSWORD: &str = "INVALID_PASSWORD";
+pub const CODE_MISMATCH: &str = "CODE_MISMATCH";
+pub const EXPIRED_CODE: &str = "EXPIRED_CODE";
+pub const TOO_MANY_REQUESTS: &str = "TOO_MANY_REQUESTS";
+
+const INTERNAL_ERROR_MESSAGE: &str = "Internal server error";
+
+fn error_code(error: &ApplicationError) -> &'static str {
+    match error {
+        ApplicationError::Unauthenticated(_) => UNAUTHENTICATED,
+        ApplicationError::Forbidden(_) => FORBIDDEN,
+        ApplicationError::NotFound(_) => NOT_FOUND,
+        ApplicationError::Conflict(_) => CONFLICT,
+        ApplicationError::ValidationError(_) => VALIDATION_FAILED,
+        ApplicationError::Authentication(error) => authentication_error_code(error),
+        ApplicationError::DatabaseError(_) | ApplicationError::InternalError(_) => INTERNAL,
+    }
+}
+
+fn authentication_error_code(error: &AuthenticationError) -> &'static str {
+    match error {
+        AuthenticationError::InvalidToken(_) | AuthenticationError::ExpiredToken(_) => {
+            UNAUTHENTICATED
+        }
+        AuthenticationError::NotAuthorized(_) => NOT_AUTHORIZED,
+        AuthenticationError::UserNotFound(_) => USER_NOT_FOUND,
+        AuthenticationError::UsernameExists(_) => USERNAME_EXISTS,
+        AuthenticationError::UserNotConfirmed(_) => USER_NOT_CONFIRMED,
+        AuthenticationError::PasswordResetRequired(_) => PASSWORD_RESET_REQUIRED,
+        AuthenticationError::InvalidPassword { .. } => INVALID_PASSWORD,
+        AuthenticationError::CodeMismatch { .. } => CODE_MISMATCH,
+        AuthenticationError::ExpiredCode { .. } => EXPIRED_CODE,
+        AuthenticationError::TooManyRequests(_) => TOO_MANY_REQUESTS,
+        AuthenticationError::InvalidParameter(_) | AuthenticationError::Unsupported(_) => {
+            VALIDATION_FAILED
+        }
+        AuthenticationError::Provider(_) => INTERNAL,
+    }
+}
+
+// 認証サービスの引数名（snake_case）をGraphQLの入力フィールド名（camelCase）に変換する
+fn to_field_name(parameter: &str) -> String {
+    let mut field = String::with_capacity(parameter.len());
+    let mut upper = false;
+    for c in parameter.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            field.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            field.push(c);
+        }
+    }
+    field
+}
+
+// 内部エラーの詳細はログにだけ残し、クライアントには固定のメッセージを返す
+impl ErrorExtensions for ApplicationError {
+    fn extend(&self) -> Error {
+        let code = error_code(self);
+        let message = match self {
+            _ if code == INTERNAL => {
+                tracing::error!("{}", self);
+                INTERNAL_ERROR_MESSAGE.to_string()
+            }
+            ApplicationError::Unauthenticated(message)
+            | ApplicationError::Forbidden(message)
+            | ApplicationError::NotFound(message)
+            | ApplicationError::Conflict(message)
+            | ApplicationError::ValidationError(message)
+            | ApplicationError::DatabaseError(message)
+            | ApplicationError::InternalError(message) => message.clone(),
+            ApplicationError::Authentication(error) => error.message().to_string(),
+        };
+        let field = match self {
+            ApplicationError::Authentication(error) => error.parameter().map(to_field_name),
+            _ => None,
+        };
+        Error::new(message).extend_with(|_, extensions| {
+            extensions.set("code", code);
+            if let Some(field) = field {
+                extensions.set("field", field);
+            }
+        })
+    }
+}
+
+// 認証が必要なフィールドに未認証でアクセスされた場合のエラー
+pub fn authentication_required() -> Error {
+    ApplicationError::Unauthenticated("Authentication required".to_string()).extend()
+}
+
+// 送られたアクセストークンで認証できなかった場合のエラー（理由をextensions.reasonに入れる）
+pub fn authentication_failed(failure: AuthenticationFailure) -> Error {
+    ApplicationError::Unauthenticated(failure.message().to_string())
+        .extend()
+        .extend_with(|_, extensions| extensions.set("reason", failure.reason()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::Value;
+
+    fn code_of(error: &Error) -> Option<Value> {
+        error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code"))
+            .cloned()
+    }
+
+    #[test]
+    fn test_extend_sets_code_and_keeps_client_message() {
+        let error = ApplicationError::NotFound("User not found".to_string()).extend();
+
+        assert_eq!(error.message, "User not found");
+        assert_eq!(code_of(&error), Some(Value::from(NOT_FOUND)));
+    }
+
+    #[test]
+    fn test_extend_hides_internal_details() {
+        let error =
+            ApplicationError::DatabaseError("connection refused: 10.0.0.1:5432".to_string())
+                .extend();
+
+        assert_eq!(error.message, INTERNAL_ERROR_MESSAGE);
+        assert_eq!(code_of(&error), Some(Value::from(INTERNAL)));
+    }
+
+    #[test]
+    fn test_extend_sets_authentication_code_and_field() {
+        let error = ApplicationError::Authentication(AuthenticationError::CodeMismatch {
+            parameter: "confirmation_code",
+            message: "Invalid verification code provided, please try again.".to_string(),
+        })
+        .extend();
+
+        assert_eq!(
+            error.message,
+            "Invalid verification code provided, please try again."
+        );
+        assert_eq!(code_of(&error), Some(Value::from(CODE_MISMATCH)));
+        assert_eq!(
+            error
+                .extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("field"))
+                .cloned(),
+            Some(Value::from("confirmationCode"))
+        );
+    }
+
+    #[test]
+    fn test_extend_hides_authentication_provider_details() {
+        let error = ApplicationError::Authentication(AuthenticationError::Provider(
+            "dispatch failure".to_string(),
+        ))
+        .extend();
+
+        assert_eq!(error.message, INTERNAL_ERROR_MESSAGE);
+        assert_eq!(code_of(&error), Some(Value::from(INTERNAL)));
+    }
+
+    #[test]
+    fn test_to_field_name() {
+        assert_eq!(to_field_name("email"), "email");
+        assert_eq!(to_field_name("new_password"), "newPassword");
+    }
+
+    #[test]
+    fn test_authentication_required_is_unauthenticated() {
+        let error = authentication_required();
+
+        assert_eq!(error.message, "Authentication required");
+        assert_eq!(code_of(&error), Some(Value::from(UNAUTHENTICATED)));
+    }
+
+    #[test]
+    fn test_authentication_failed_sets_reason() {
+        let error = authentication_failed(AuthenticationFailure::ExpiredToken);
+
+        assert_eq!(error.message, "Access token has expired");
+        assert_eq!(code_of(&error), Some(Value::from(UNAUTHENTICATED)));
+        assert_eq!(
+            error
+                .extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("reason"))
+                .cloned(),
+            Some(Value::from("expired_token"))
+        );
+    }
+}
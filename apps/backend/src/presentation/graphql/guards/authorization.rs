@@ -1,19 +1,18 @@
-use async_graphql::{Context, ErrorExtensions, Guard, Result as GraphQLResult};
+use async_graphql::{Context, ErrorExtensions, Guard, Result as GraphQLResult, ResultExt};
+use std::sync::Arc;
+use uuid::Uuid;
 
+use crate::application::errors::ApplicationError;
+use crate::application::policies::{Action, Policy};
+use crate::domain::enums::user_role::UserRole;
 use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::errors::authentication_required;
 
 // Role-based authorization guard
 pub struct RoleGuard {
     pub required_role: UserRole,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum UserRole {
-    User,
-    Admin,
-    SuperAdmin,
-}
-
 impl RoleGuard {
     pub fn new(required_role: UserRole) -> Self {
         Self { required_role }
@@ -23,34 +22,23 @@ impl RoleGuard {
         Self::new(UserRole::Admin)
     }
 
-    pub fn super_admin() -> Self {
-        Self::new(UserRole::SuperAdmin)
-    }
-
     pub fn user() -> Self {
         Self::new(UserRole::User)
     }
 }
 
-#[async_graphql::async_trait]
 impl Guard for RoleGuard {
     async fn check(&self, ctx: &Context<'_>) -> GraphQLResult<()> {
         let user_context = ctx
             .data::<UserContext>()
-            .map_err(|_| "Authentication required".extend())?;
+            .map_err(|_| authentication_required())?;
 
-        if let Some(user) = &user_context.user {
-            // For now, we'll implement basic role checking
-            // In a real application, you'd store roles in the database
-            let user_role = determine_user_role(&user.id).await;
-
-            if has_sufficient_role(&user_role, &self.required_role) {
-                Ok(())
-            } else {
-                Err("Insufficient permissions".extend())
-            }
+        let user = user_context.authenticated_user()?;
+        // ロールはリクエストごとに認証ミドルウェアがデータベースから読み込んでいる
+        if has_sufficient_role(&user.role, &self.required_role) {
+            Ok(())
         } else {
-            Err("Authentication required".extend())
+            Err(ApplicationError::Forbidden("Insufficient permissions".to_string()).extend())
         }
     }
 }
@@ -58,66 +46,143 @@ impl Guard for RoleGuard {
 // Authentication guard (simpler - just checks if user is logged in)
 pub struct AuthenticationGuard;
 
-#[async_graphql::async_trait]
 impl Guard for AuthenticationGuard {
     async fn check(&self, ctx: &Context<'_>) -> GraphQLResult<()> {
         let user_context = ctx
             .data::<UserContext>()
-            .map_err(|_| "Authentication required".extend())?;
+            .map_err(|_| authentication_required())?;
 
-        if user_context.user.is_some() {
+        user_context.authenticated_user()?;
+        Ok(())
+    }
+}
+
+// Session guard - サインインで発行されたアクセストークンを要求する
+// パーソナルアクセストークンにはサインアウトする対象のセッションやプロバイダーが無いため拒否する
+pub struct SessionGuard;
+
+impl Guard for SessionGuard {
+    async fn check(&self, ctx: &Context<'_>) -> GraphQLResult<()> {
+        let user_context = ctx
+            .data::<UserContext>()
+            .map_err(|_| authentication_required())?;
+
+        user_context.authenticated_user()?;
+        if user_context.api_token_id.is_some() {
+            Err(
+                ApplicationError::Forbidden("Not available for personal access tokens".to_string())
+                    .extend(),
+            )
+        } else {
             Ok(())
+        }
+    }
+}
+
+// CSRF guard - Cookieで認証されたリクエストのミューテーションに、ダブルサブミットのCSRFトークンを要求する
+// Authorizationヘッダーで認証されたリクエストと匿名のリクエストは対象外
+pub struct CsrfGuard;
+
+impl Guard for CsrfGuard {
+    async fn check(&self, ctx: &Context<'_>) -> GraphQLResult<()> {
+        if ctx
+            .data_opt::<UserContext>()
+            .is_some_and(|user_context| user_context.csrf_failed)
+        {
+            Err(
+                ApplicationError::Forbidden("Invalid CSRF token".to_string())
+                    .extend()
+                    .extend_with(|_, extensions| extensions.set("reason", "csrf_token_mismatch")),
+            )
         } else {
-            Err("Authentication required".extend())
+            Ok(())
         }
     }
 }
 
-// Helper functions
-async fn determine_user_role(user_id: &uuid::Uuid) -> UserRole {
-    // TODO: Implement actual role lookup from database
-    // For now, return User role as default
-    UserRole::User
+// Permission-based authorization guard
+// 認証ミドルウェアが解決した実効権限（UserContext::permissions）を参照する
+pub struct PermissionGuard {
+    pub permission: String,
 }
 
+impl PermissionGuard {
+    pub fn new(permission: &str) -> Self {
+        Self {
+            permission: permission.to_string(),
+        }
+    }
+}
+
+impl Guard for PermissionGuard {
+    async fn check(&self, ctx: &Context<'_>) -> GraphQLResult<()> {
+        let user_context = ctx
+            .data::<UserContext>()
+            .map_err(|_| authentication_required())?;
+
+        user_context.authenticated_user()?;
+        if user_context.has_permission(&self.permission) {
+            Ok(())
+        } else {
+            Err(ApplicationError::Forbidden("Insufficient permissions".to_string()).extend())
+        }
+    }
+}
+
+// Helper functions
 fn has_sufficient_role(user_role: &UserRole, required_role: &UserRole) -> bool {
     use UserRole::*;
 
-    match (user_role, required_role) {
-        (SuperAdmin, _) => true,
-        (Admin, Admin) | (Admin, User) => true,
-        (User, User) => true,
-        _ => false,
-    }
+    matches!((user_role, required_role), (Admin, _) | (User, User))
 }
 
-// Resource ownership guard - checks if user owns the resource
-pub struct ResourceOwnershipGuard {
-    pub resource_user_id_field: String,
+// Resource ownership guard - リソースをポリシー経由で読み込み、操作が許可されているかを判定する
+// リソースが存在しない場合の扱いはリゾルバーに任せる
+pub struct ResourceOwnershipGuard<P: Policy> {
+    pub policy: Arc<P>,
+    pub action: Action,
+    pub resource_id: Uuid,
 }
 
-impl ResourceOwnershipGuard {
-    pub fn new(resource_user_id_field: &str) -> Self {
+impl<P: Policy> ResourceOwnershipGuard<P> {
+    pub fn new(policy: Arc<P>, action: Action, resource_id: Uuid) -> Self {
         Self {
-            resource_user_id_field: resource_user_id_field.to_string(),
+            policy,
+            action,
+            resource_id,
         }
     }
 }
 
-#[async_graphql::async_trait]
-impl Guard for ResourceOwnershipGuard {
+impl<P: Policy> Guard for ResourceOwnershipGuard<P> {
     async fn check(&self, ctx: &Context<'_>) -> GraphQLResult<()> {
         let user_context = ctx
             .data::<UserContext>()
-            .map_err(|_| "Authentication required".extend())?;
+            .map_err(|_| authentication_required())?;
+        let user = user_context.authenticated_user()?;
 
-        if let Some(current_user) = &user_context.user {
-            // TODO: Implement resource ownership checking
-            // This would involve looking up the resource and comparing user IDs
-            // For now, we'll just ensure the user is authenticated
-            Ok(())
-        } else {
-            Err("Authentication required".extend())
+        match self.policy.find(self.resource_id).await.extend()? {
+            Some(resource) if !self.policy.authorize(user, self.action, &resource) => {
+                Err(ApplicationError::Forbidden("Insufficient permissions".to_string()).extend())
+            }
+            _ => Ok(()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_satisfies_every_role() {
+        assert!(has_sufficient_role(&UserRole::Admin, &UserRole::Admin));
+        assert!(has_sufficient_role(&UserRole::Admin, &UserRole::User));
+    }
+
+    #[test]
+    fn test_user_does_not_satisfy_admin() {
+        assert!(has_sufficient_role(&UserRole::User, &UserRole::User));
+        assert!(!has_sufficient_role(&UserRole::User, &UserRole::Admin));
+    }
+}
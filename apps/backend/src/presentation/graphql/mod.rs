@@ -1,9 +1,12 @@
 pub mod context;
+pub mod errors;
+pub mod guards;
 pub mod mutations;
 pub mod resolvers;
 pub mod scalars;
 pub mod schema;
 pub mod types;
+pub mod validators;
 
 // このモジュールは、GraphQL APIを提供します。
 // async-graphqlフレームワークを使用したGraphQLインターフェースを実装します。
@@ -0,0 +1,67 @@
+use async_graphql::{Context, ErrorExtensions, Object, Result, ResultExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::api_token_dto::CreateApiTokenDto;
+use crate::application::errors::ApplicationError;
+use crate::application::services::ApiTokenService;
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::SessionGuard;
+use crate::presentation::graphql::types::api_token_type::{CreateApiTokenInput, CreatedApiToken};
+
+pub struct ApiTokenMutation {
+    api_token_service: Arc<ApiTokenService>,
+}
+
+impl ApiTokenMutation {
+    pub fn new(api_token_service: Arc<ApiTokenService>) -> Self {
+        Self { api_token_service }
+    }
+}
+
+#[Object]
+impl ApiTokenMutation {
+    // 平文のトークンはこの応答でだけ返す
+    // トークンから別のトークンを作って、有効期限や失効を回避できないようにする
+    #[graphql(guard = "SessionGuard")]
+    async fn create_api_token(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateApiTokenInput,
+    ) -> Result<CreatedApiToken> {
+        let user_context = ctx.data::<UserContext>()?;
+        let user_id = user_context.authenticated_user()?.id;
+        // 自分が持っていない権限をトークンに与えることはできない
+        if let Some(scope) = input
+            .scopes
+            .iter()
+            .find(|scope| !user_context.has_permission(scope))
+        {
+            return Err(ApplicationError::Forbidden(format!(
+                "Permission '{}' is not granted",
+                scope
+            ))
+            .extend());
+        }
+
+        let dto = CreateApiTokenDto::from(input);
+        let created = self
+            .api_token_service
+            .create_token(user_id, dto)
+            .await
+            .extend()?;
+
+        Ok(CreatedApiToken::from(created))
+    }
+
+    // 失効したトークンは、以降のリクエストで401になる
+    // 漏洩したトークンで他のトークンを失効させられないよう、サインインしたセッションに限る
+    #[graphql(guard = "SessionGuard")]
+    async fn revoke_api_token(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+        self.api_token_service
+            .revoke_token(user_id, id)
+            .await
+            .extend()
+    }
+}
@@ -1,104 +1,442 @@
 // use anyhow::Result;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, ErrorExtensions, Object, Result, ResultExt};
+use axum::http::header::SET_COOKIE;
 use std::sync::Arc;
 
 use crate::application::dtos::authentication_dto::{
-    SignInInputDTO, SignOutInputDTO, SignUpInputDTO,
+    AssociateSoftwareTokenInputDTO, AuthenticationTokensDTO, ChangePasswordInputDTO, ClientInfoDTO,
+    ConfirmForgotPasswordInputDTO, ConfirmSignUpInputDTO, ForgotPasswordInputDTO,
+    GlobalSignOutInputDTO, RefreshTokensInputDTO, RequestSignInCodeInputDTO,
+    ResendConfirmationCodeInputDTO, RespondToAuthChallengeInputDTO, SetMfaPreferenceInputDTO,
+    SignInInputDTO, SignInOutputDTO, SignOutInputDTO, SignUpInputDTO, VerifySignInCodeInputDTO,
+    VerifySoftwareTokenInputDTO,
 };
+use crate::application::errors::{ApplicationError, ApplicationResult};
+use crate::application::usecases::UseCases;
+use crate::application::usecases::associate_software_token::AssociateSoftwareToken;
+use crate::application::usecases::change_password::ChangePassword;
+use crate::application::usecases::confirm_forgot_password::ConfirmForgotPassword;
+use crate::application::usecases::confirm_sign_up::ConfirmSignUp;
+use crate::application::usecases::forgot_password::ForgotPassword;
+use crate::application::usecases::global_sign_out::GlobalSignOut;
+use crate::application::usecases::refresh_tokens::RefreshTokens;
+use crate::application::usecases::request_sign_in_code::RequestSignInCode;
+use crate::application::usecases::resend_confirmation_code::ResendConfirmationCode;
+use crate::application::usecases::respond_to_auth_challenge::RespondToAuthChallenge;
+use crate::application::usecases::set_mfa_preference::SetMfaPreference;
 use crate::application::usecases::sign_in::SignIn;
 use crate::application::usecases::sign_out::SignOut;
 use crate::application::usecases::sign_up::SignUp;
+use crate::application::usecases::verify_sign_in_code::VerifySignInCode;
+use crate::application::usecases::verify_software_token::VerifySoftwareToken;
+use crate::infrastructure::config::app_config::CookieConfig;
 use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::errors::authentication_required;
+use crate::presentation::graphql::guards::SessionGuard;
 use crate::presentation::graphql::types::authentication_type::{
-    SignInInput, SignOutResponse, SignUpInput, TokenSet,
+    ChangePasswordInput, ChangePasswordResponse, CodeDeliveryDetails, ConfirmForgotPasswordInput,
+    ConfirmForgotPasswordResponse, ConfirmSignUpInput, ConfirmSignUpResponse, ForgotPasswordInput,
+    ForgotPasswordResponse, RequestSignInCodeInput, ResendConfirmationCodeInput,
+    RespondToAuthChallengeInput, SignInCodeChallenge, SignInInput, SignInResult, SignOutResponse,
+    SignUpInput, SignUpResponse, SoftwareTokenAssociation, TokenSet, VerifySignInCodeInput,
+    VerifySoftwareTokenInput, VerifySoftwareTokenResponse,
 };
-use crate::presentation::graphql::types::identity_link_type::IdentityLink;
+use crate::presentation::graphql::types::user_type::User;
+use crate::presentation::http::cookies::{clear_cookies, csrf_cookie, token_cookies};
 
 pub struct AuthenticationMutation {
     sign_up: Arc<SignUp>,
+    confirm_sign_up: Arc<ConfirmSignUp>,
+    resend_confirmation_code: Arc<ResendConfirmationCode>,
     sign_in: Arc<SignIn>,
+    respond_to_auth_challenge: Arc<RespondToAuthChallenge>,
+    request_sign_in_code: Arc<RequestSignInCode>,
+    verify_sign_in_code: Arc<VerifySignInCode>,
+    refresh_tokens: Arc<RefreshTokens>,
+    forgot_password: Arc<ForgotPassword>,
+    confirm_forgot_password: Arc<ConfirmForgotPassword>,
+    change_password: Arc<ChangePassword>,
+    associate_software_token: Arc<AssociateSoftwareToken>,
+    verify_software_token: Arc<VerifySoftwareToken>,
+    set_mfa_preference: Arc<SetMfaPreference>,
     sign_out: Arc<SignOut>,
+    global_sign_out: Arc<GlobalSignOut>,
 }
 
 impl AuthenticationMutation {
-    pub fn new(sign_up: Arc<SignUp>, sign_in: Arc<SignIn>, sign_out: Arc<SignOut>) -> Self {
+    pub fn new(use_cases: &UseCases) -> Self {
         Self {
-            sign_up,
-            sign_in,
-            sign_out,
+            sign_up: Arc::clone(&use_cases.sign_up),
+            confirm_sign_up: Arc::clone(&use_cases.confirm_sign_up),
+            resend_confirmation_code: Arc::clone(&use_cases.resend_confirmation_code),
+            sign_in: Arc::clone(&use_cases.sign_in),
+            respond_to_auth_challenge: Arc::clone(&use_cases.respond_to_auth_challenge),
+            request_sign_in_code: Arc::clone(&use_cases.request_sign_in_code),
+            verify_sign_in_code: Arc::clone(&use_cases.verify_sign_in_code),
+            refresh_tokens: Arc::clone(&use_cases.refresh_tokens),
+            forgot_password: Arc::clone(&use_cases.forgot_password),
+            confirm_forgot_password: Arc::clone(&use_cases.confirm_forgot_password),
+            change_password: Arc::clone(&use_cases.change_password),
+            associate_software_token: Arc::clone(&use_cases.associate_software_token),
+            verify_software_token: Arc::clone(&use_cases.verify_software_token),
+            set_mfa_preference: Arc::clone(&use_cases.set_mfa_preference),
+            sign_out: Arc::clone(&use_cases.sign_out),
+            global_sign_out: Arc::clone(&use_cases.global_sign_out),
         }
     }
 }
 
+// ミドルウェアが検証済みのリクエストに保持したアクセストークン
+fn current_access_token(ctx: &Context<'_>) -> Result<String> {
+    ctx.data::<UserContext>()?
+        .access_token
+        .clone()
+        .ok_or_else(authentication_required)
+}
+
+// Cookieモードではトークンと新しいCSRFトークンをCookieに設定し、
+// スクリプトから読めないようアクセストークンとリフレッシュトークンをレスポンス本文から取り除く
+fn issue_tokens(ctx: &Context<'_>, tokens: AuthenticationTokensDTO) -> TokenSet {
+    let Some(cookies) = ctx.data_opt::<CookieConfig>() else {
+        return TokenSet::from(tokens);
+    };
+    for cookie in token_cookies(cookies, &tokens) {
+        ctx.append_http_header(SET_COOKIE, cookie);
+    }
+    ctx.append_http_header(SET_COOKIE, csrf_cookie(cookies));
+    TokenSet {
+        access_token: String::new(),
+        refresh_token: String::new(),
+        ..TokenSet::from(tokens)
+    }
+}
+
+// ハンドラーが記録した接続元の情報に、クライアントが指定した端末名を加える
+fn client_info(ctx: &Context<'_>, device: Option<String>) -> ClientInfoDTO {
+    ClientInfoDTO {
+        device,
+        ..ctx.data_opt::<ClientInfoDTO>().cloned().unwrap_or_default()
+    }
+}
+
+// Cookieモードでは、サインアウトの成否にかかわらずCookieを削除する
+fn expire_cookies(ctx: &Context<'_>) {
+    if let Some(cookies) = ctx.data_opt::<CookieConfig>() {
+        for cookie in clear_cookies(cookies) {
+            ctx.append_http_header(SET_COOKIE, cookie);
+        }
+    }
+}
+
+fn sign_out_response(result: ApplicationResult<()>) -> SignOutResponse {
+    match result {
+        Ok(_) => SignOutResponse {
+            success: true,
+            message: "Successfully signed out".to_string(),
+        },
+        Err(e) => {
+            // 失敗の詳細はクライアントに返さない
+            tracing::error!("Sign out failed: {}", e);
+            SignOutResponse {
+                success: false,
+                message: "Sign out failed".to_string(),
+            }
+        }
+    }
+}
+
+fn issue_sign_in_result(ctx: &Context<'_>, output: SignInOutputDTO) -> SignInResult {
+    match output {
+        SignInOutputDTO::Authenticated(tokens) => SignInResult::TokenSet(issue_tokens(ctx, tokens)),
+        challenge => SignInResult::from(challenge),
+    }
+}
+
 #[Object]
 impl AuthenticationMutation {
-    async fn sign_up(&self, _ctx: &Context<'_>, input: SignUpInput) -> Result<IdentityLink> {
+    async fn sign_up(&self, _ctx: &Context<'_>, input: SignUpInput) -> Result<SignUpResponse> {
         let input_dto = SignUpInputDTO::from(input);
-        let identity_link_dto = self.sign_up.execute(input_dto).await?;
+        let output_dto = self.sign_up.execute(input_dto).await.extend()?;
+
+        Ok(SignUpResponse::from(output_dto))
+    }
+
+    async fn confirm_sign_up(
+        &self,
+        _ctx: &Context<'_>,
+        input: ConfirmSignUpInput,
+    ) -> Result<ConfirmSignUpResponse> {
+        let input_dto = ConfirmSignUpInputDTO::from(input);
+        self.confirm_sign_up.execute(input_dto).await.extend()?;
 
-        Ok(IdentityLink::from(identity_link_dto))
+        Ok(ConfirmSignUpResponse {
+            success: true,
+            message: "Successfully confirmed sign up".to_string(),
+        })
     }
 
-    async fn sign_in(&self, _ctx: &Context<'_>, input: SignInInput) -> Result<TokenSet> {
+    async fn resend_confirmation_code(
+        &self,
+        _ctx: &Context<'_>,
+        input: ResendConfirmationCodeInput,
+    ) -> Result<CodeDeliveryDetails> {
+        let input_dto = ResendConfirmationCodeInputDTO::from(input);
+        let output_dto = self
+            .resend_confirmation_code
+            .execute(input_dto)
+            .await
+            .extend()?;
+
+        Ok(CodeDeliveryDetails::from(output_dto))
+    }
+
+    async fn sign_in(&self, ctx: &Context<'_>, input: SignInInput) -> Result<SignInResult> {
+        let client = client_info(ctx, input.device_name.clone());
         let input_dto = SignInInputDTO::from(input);
-        let output_dto = self.sign_in.execute(input_dto).await?;
-
-        Ok(TokenSet::from(output_dto))
-    }
-
-    async fn sign_out(&self, ctx: &Context<'_>) -> Result<SignOutResponse> {
-        let user_context = ctx.data::<UserContext>().unwrap();
-        match &user_context.user {
-            Some(user) => {
-                let input_dto = SignOutInputDTO {
-                    username: user.identity_links.first().unwrap().clone().sub,
-                };
-                match self.sign_out.execute(input_dto).await {
-                    Ok(_) => Ok(SignOutResponse {
-                        success: true,
-                        message: "Successfully signed out".to_string(),
-                    }),
-                    Err(e) => Ok(SignOutResponse {
-                        success: false,
-                        message: format!("Sign out failed: {}", e),
-                    }),
-                }
-            }
-            None => Ok(SignOutResponse {
+        let output_dto = self.sign_in.execute(input_dto, client).await.extend()?;
+
+        Ok(issue_sign_in_result(ctx, output_dto))
+    }
+
+    async fn respond_to_auth_challenge(
+        &self,
+        ctx: &Context<'_>,
+        input: RespondToAuthChallengeInput,
+    ) -> Result<SignInResult> {
+        let client = client_info(ctx, input.device_name.clone());
+        let input_dto = RespondToAuthChallengeInputDTO::from(input);
+        let output_dto = self
+            .respond_to_auth_challenge
+            .execute(input_dto, client)
+            .await
+            .extend()?;
+
+        Ok(issue_sign_in_result(ctx, output_dto))
+    }
+
+    // パスワードの代わりに、メールで送るワンタイムコードでサインインする
+    async fn request_sign_in_code(
+        &self,
+        _ctx: &Context<'_>,
+        input: RequestSignInCodeInput,
+    ) -> Result<SignInCodeChallenge> {
+        let input_dto = RequestSignInCodeInputDTO::from(input);
+        let output_dto = self
+            .request_sign_in_code
+            .execute(input_dto)
+            .await
+            .extend()?;
+
+        Ok(SignInCodeChallenge::from(output_dto))
+    }
+
+    async fn verify_sign_in_code(
+        &self,
+        ctx: &Context<'_>,
+        input: VerifySignInCodeInput,
+    ) -> Result<TokenSet> {
+        let client = client_info(ctx, input.device_name.clone());
+        let input_dto = VerifySignInCodeInputDTO::from(input);
+        let output_dto = self
+            .verify_sign_in_code
+            .execute(input_dto, client)
+            .await
+            .extend()?;
+
+        Ok(issue_tokens(ctx, output_dto))
+    }
+
+    async fn refresh_tokens(
+        &self,
+        ctx: &Context<'_>,
+        refresh_token: Option<String>,
+    ) -> Result<TokenSet> {
+        // CookieモードではスクリプトからHttpOnlyのCookieを読めないため、Cookieのリフレッシュトークンを使う
+        let refresh_token = refresh_token
+            .or_else(|| {
+                ctx.data_opt::<UserContext>()
+                    .and_then(|user_context| user_context.refresh_token.clone())
+            })
+            .ok_or_else(|| {
+                ApplicationError::ValidationError("Refresh token is required".to_string())
+            })
+            .extend()?;
+        let input_dto = RefreshTokensInputDTO { refresh_token };
+        let output_dto = self.refresh_tokens.execute(input_dto).await.extend()?;
+
+        Ok(issue_tokens(ctx, output_dto))
+    }
+
+    async fn forgot_password(
+        &self,
+        _ctx: &Context<'_>,
+        input: ForgotPasswordInput,
+    ) -> Result<ForgotPasswordResponse> {
+        let input_dto = ForgotPasswordInputDTO::from(input);
+        self.forgot_password.execute(input_dto).await.extend()?;
+
+        Ok(ForgotPasswordResponse {
+            success: true,
+            message: "If an account exists for this email, a reset code has been sent".to_string(),
+        })
+    }
+
+    async fn confirm_forgot_password(
+        &self,
+        _ctx: &Context<'_>,
+        input: ConfirmForgotPasswordInput,
+    ) -> Result<ConfirmForgotPasswordResponse> {
+        let input_dto = ConfirmForgotPasswordInputDTO::from(input);
+        self.confirm_forgot_password
+            .execute(input_dto)
+            .await
+            .extend()?;
+
+        Ok(ConfirmForgotPasswordResponse {
+            success: true,
+            message: "Password has been reset".to_string(),
+        })
+    }
+
+    #[graphql(guard = "SessionGuard")]
+    async fn change_password(
+        &self,
+        ctx: &Context<'_>,
+        input: ChangePasswordInput,
+    ) -> Result<ChangePasswordResponse> {
+        let access_token = current_access_token(ctx)?;
+        let input_dto = ChangePasswordInputDTO {
+            access_token,
+            old_password: input.old_password,
+            new_password: input.new_password,
+        };
+        self.change_password.execute(input_dto).await.extend()?;
+
+        Ok(ChangePasswordResponse {
+            success: true,
+            message: "Password has been changed".to_string(),
+        })
+    }
+
+    #[graphql(guard = "SessionGuard")]
+    async fn associate_software_token(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<SoftwareTokenAssociation> {
+        let input_dto = AssociateSoftwareTokenInputDTO {
+            access_token: current_access_token(ctx)?,
+        };
+        let output_dto = self
+            .associate_software_token
+            .execute(input_dto)
+            .await
+            .extend()?;
+
+        Ok(SoftwareTokenAssociation::from(output_dto))
+    }
+
+    #[graphql(guard = "SessionGuard")]
+    async fn verify_software_token(
+        &self,
+        ctx: &Context<'_>,
+        input: VerifySoftwareTokenInput,
+    ) -> Result<VerifySoftwareTokenResponse> {
+        let input_dto = VerifySoftwareTokenInputDTO {
+            access_token: current_access_token(ctx)?,
+            user_code: input.user_code,
+            friendly_device_name: input.friendly_device_name,
+        };
+        self.verify_software_token
+            .execute(input_dto)
+            .await
+            .extend()?;
+
+        Ok(VerifySoftwareTokenResponse {
+            success: true,
+            message: "Software token has been verified".to_string(),
+        })
+    }
+
+    #[graphql(guard = "SessionGuard")]
+    async fn set_mfa_preference(&self, ctx: &Context<'_>, enabled: bool) -> Result<User> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+        let input_dto = SetMfaPreferenceInputDTO {
+            access_token: current_access_token(ctx)?,
+            user_id,
+            enabled,
+        };
+        let output_dto = self.set_mfa_preference.execute(input_dto).await.extend()?;
+
+        Ok(User::from(output_dto))
+    }
+
+    // 現在の端末のセッションだけをサインアウトする
+    // リフレッシュトークンは引数で渡すか、Cookieモードでは自動的にCookieから読み取る
+    async fn sign_out(
+        &self,
+        ctx: &Context<'_>,
+        refresh_token: Option<String>,
+    ) -> Result<SignOutResponse> {
+        expire_cookies(ctx);
+        let user_context = ctx.data::<UserContext>()?;
+        let Some(user) = &user_context.user else {
+            return Ok(SignOutResponse {
                 success: false,
                 message: "No user context available".to_string(),
-            }),
+            });
+        };
+        // パーソナルアクセストークンにはサインアウトするセッションが無い
+        if user_context.api_token_id.is_some() {
+            return Err(ApplicationError::Forbidden(
+                "Not available for personal access tokens".to_string(),
+            )
+            .extend());
         }
-        // match ctx.data::<UserContext>() {
-        //     Some(user_context) => {
-        //         if let Some(username) = &user_context.user {
-        //             self.perform_sign_out(SignOutInput {
-        //                 username: username.clone(),
-        //             })
-        //             .await
-        //         } else {
-        //             Ok(SignOutResponse {
-        //                 success: false,
-        //                 message: "User not authenticated".to_string(),
-        //             })
-        //         }
-        //     }
-        //     None => Ok(SignOutResponse {
-        //         success: false,
-        //         message: "No user context available".to_string(),
-        //     }),
-        // }
-        // let input_dto = SignOutDTO::from(input);
-
-        // match self.sign_out.execute(input_dto).await {
-        //     Ok(_) => Ok(SignOutResponse {
-        //         success: true,
-        //         message: "Successfully signed out".to_string(),
-        //     }),
-        //     Err(e) => Ok(SignOutResponse {
-        //         success: false,
-        //         message: format!("Sign out failed: {}", e),
-        //     }),
-        // }
+        let input_dto = SignOutInputDTO {
+            user_id: user.id,
+            provider: user_context
+                .claims
+                .as_ref()
+                .map(|claims| claims.provider.clone())
+                .unwrap_or_default(),
+            jti: user_context
+                .claims
+                .as_ref()
+                .and_then(|claims| claims.jti.clone()),
+            origin_jti: user_context
+                .claims
+                .as_ref()
+                .and_then(|claims| claims.origin_jti.clone()),
+            expires_at: user_context
+                .claims
+                .as_ref()
+                .and_then(|claims| claims.expires_at),
+            refresh_token: refresh_token.or_else(|| user_context.refresh_token.clone()),
+        };
+
+        Ok(sign_out_response(self.sign_out.execute(input_dto).await))
+    }
+
+    // すべての端末のセッションをサインアウトする
+    #[graphql(guard = "SessionGuard")]
+    async fn global_sign_out(&self, ctx: &Context<'_>) -> Result<SignOutResponse> {
+        expire_cookies(ctx);
+        let user_context = ctx.data::<UserContext>()?;
+        let user = user_context.authenticated_user()?;
+        let Some(claims) = &user_context.claims else {
+            return Err(authentication_required());
+        };
+        let input_dto = GlobalSignOutInputDTO {
+            user_id: user.id,
+            provider: claims.provider.clone(),
+            username: claims.sub.clone(),
+            jti: claims.jti.clone(),
+            origin_jti: claims.origin_jti.clone(),
+            expires_at: claims.expires_at,
+        };
+
+        Ok(sign_out_response(
+            self.global_sign_out.execute(input_dto).await,
+        ))
     }
 }
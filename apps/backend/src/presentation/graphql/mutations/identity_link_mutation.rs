@@ -0,0 +1,65 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::identity_link_dto::LinkIdentityInputDTO;
+use crate::application::services::IdentityLinkService;
+use crate::application::usecases::link_identity::LinkIdentity;
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::SessionGuard;
+use crate::presentation::graphql::types::identity_link_type::IdentityLink;
+
+pub struct IdentityLinkMutation {
+    link_identity: Arc<LinkIdentity>,
+    identity_link_service: Arc<IdentityLinkService>,
+}
+
+impl IdentityLinkMutation {
+    pub fn new(
+        link_identity: Arc<LinkIdentity>,
+        identity_link_service: Arc<IdentityLinkService>,
+    ) -> Self {
+        Self {
+            link_identity,
+            identity_link_service,
+        }
+    }
+}
+
+#[Object]
+impl IdentityLinkMutation {
+    // tokenは紐付けるプロバイダーが発行したトークン。検証できたアカウントを自分に紐付ける
+    // サインイン手段の追加は、本人がサインインしている場合に限る
+    #[graphql(guard = "SessionGuard")]
+    async fn link_identity(
+        &self,
+        ctx: &Context<'_>,
+        provider: String,
+        token: String,
+    ) -> Result<IdentityLink> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+
+        let identity_link = self
+            .link_identity
+            .execute(LinkIdentityInputDTO {
+                user_id,
+                provider,
+                token,
+            })
+            .await
+            .extend()?;
+
+        Ok(IdentityLink::from(identity_link))
+    }
+
+    // 最後の1つは解除できない
+    #[graphql(guard = "SessionGuard")]
+    async fn unlink_identity(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+
+        self.identity_link_service
+            .unlink_identity(user_id, id)
+            .await
+            .extend()
+    }
+}
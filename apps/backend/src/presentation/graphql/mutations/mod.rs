@@ -1,2 +1,6 @@
+pub mod api_token_mutation;
 pub mod authentication_mutation;
+pub mod identity_link_mutation;
+pub mod role_mutation;
+pub mod session_mutation;
 pub mod user_mutation;
@@ -0,0 +1,75 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::dtos::role_dto::CreateRoleDto;
+use crate::application::services::RoleService;
+use crate::domain::value_objects::permission::ROLES_MANAGE;
+use crate::presentation::graphql::guards::PermissionGuard;
+use crate::presentation::graphql::types::role_type::{CreateRoleInput, Role};
+
+pub struct RoleMutation {
+    role_service: Arc<RoleService>,
+}
+
+impl RoleMutation {
+    pub fn new(role_service: Arc<RoleService>) -> Self {
+        Self { role_service }
+    }
+}
+
+// ロールの管理とユーザーへの割り当て - roles:manage権限必須
+// 割り当ての変更は、対象ユーザーの次のリクエストから反映される
+#[Object]
+impl RoleMutation {
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn create_role(&self, _ctx: &Context<'_>, input: CreateRoleInput) -> Result<Role> {
+        let dto = CreateRoleDto::from(input);
+        let role = self.role_service.create_role(dto).await.extend()?;
+
+        Ok(Role::from(role))
+    }
+
+    // ロールの権限を指定された権限で置き換える
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn set_role_permissions(
+        &self,
+        _ctx: &Context<'_>,
+        id: Uuid,
+        permissions: Vec<String>,
+    ) -> Result<Role> {
+        let role = self
+            .role_service
+            .set_role_permissions(id, permissions)
+            .await
+            .extend()?;
+
+        Ok(Role::from(role))
+    }
+
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn delete_role(&self, _ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+        self.role_service.delete_role(id).await.extend()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn assign_role(&self, _ctx: &Context<'_>, user_id: Uuid, role_id: Uuid) -> Result<bool> {
+        self.role_service
+            .assign_role(user_id, role_id)
+            .await
+            .extend()
+    }
+
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn unassign_role(
+        &self,
+        _ctx: &Context<'_>,
+        user_id: Uuid,
+        role_id: Uuid,
+    ) -> Result<bool> {
+        self.role_service
+            .unassign_role(user_id, role_id)
+            .await
+            .extend()
+    }
+}
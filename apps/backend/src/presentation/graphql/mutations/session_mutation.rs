@@ -0,0 +1,31 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::services::SessionService;
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::SessionGuard;
+
+pub struct SessionMutation {
+    session_service: Arc<SessionService>,
+}
+
+impl SessionMutation {
+    pub fn new(session_service: Arc<SessionService>) -> Self {
+        Self { session_service }
+    }
+}
+
+#[Object]
+impl SessionMutation {
+    // 自分のセッションを端末ごとに失効させる
+    // 失効したセッションのリフレッシュトークンでは、以降トークンを更新できない
+    #[graphql(guard = "SessionGuard")]
+    async fn revoke_session(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+        self.session_service
+            .revoke_session(user_id, id)
+            .await
+            .extend()
+    }
+}
@@ -1,35 +1,49 @@
 // use anyhow::Result;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, Object, Result, ResultExt};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::application::dtos::user_dto::{CreateUserDto, UpdateUserDto};
+use crate::application::policies::{Action, UserPolicy};
 use crate::application::services::UserService;
+use crate::domain::enums::user_role::UserRole;
+use crate::domain::value_objects::permission::{USERS_CREATE, USERS_DELETE, USERS_UPDATE};
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::{
+    PermissionGuard, ResourceOwnershipGuard, RoleGuard, SessionGuard,
+};
 use crate::presentation::graphql::types::user_type::{CreateUserInput, UpdateUserInput, User}; // This path should now be correct
 
 pub struct UserMutation {
     user_service: Arc<UserService>,
+    user_policy: Arc<UserPolicy>,
 }
 
 impl UserMutation {
-    pub fn new(user_service: Arc<UserService>) -> Self {
-        Self { user_service }
+    pub fn new(user_service: Arc<UserService>, user_policy: Arc<UserPolicy>) -> Self {
+        Self {
+            user_service,
+            user_policy,
+        }
     }
 }
 
 #[Object]
 impl UserMutation {
-    async fn create_user(
-        &self,
-        _ctx: &Context<'_>,
-        input: CreateUserInput,
-    ) -> Result<User> {
+    // ユーザー作成 - users:create権限必須（利用者自身の登録はsignUpを使う）
+    #[graphql(guard = "PermissionGuard::new(USERS_CREATE)")]
+    async fn create_user(&self, _ctx: &Context<'_>, input: CreateUserInput) -> Result<User> {
         let dto = CreateUserDto::from(input);
-        let user = self.user_service.create_user(dto).await?;
+        let user = self.user_service.create_user(dto).await.extend()?;
 
         Ok(User::from(user))
     }
 
+    // ユーザー更新 - 本人、またはusers:update権限が必要
+    // パーソナルアクセストークンは、users:updateのスコープが無ければ本人でも更新できない
+    #[graphql(
+        guard = "PermissionGuard::new(USERS_UPDATE).or(ResourceOwnershipGuard::new(self.user_policy.clone(), Action::Update, id).and(SessionGuard))"
+    )]
     async fn update_user(
         &self,
         _ctx: &Context<'_>,
@@ -37,12 +51,45 @@ impl UserMutation {
         input: UpdateUserInput,
     ) -> Result<Option<User>> {
         let dto = UpdateUserDto::from(input);
-        let user = self.user_service.update_user(id, dto).await?;
+        let user = self.user_service.update_user(id, dto).await.extend()?;
 
         Ok(user.map(User::from))
     }
 
+    // ユーザー削除 - users:delete権限必須
+    #[graphql(guard = "PermissionGuard::new(USERS_DELETE)")]
     async fn delete_user(&self, _ctx: &Context<'_>, id: Uuid) -> Result<bool> {
-        Ok(self.user_service.delete_user(id).await?)
+        self.user_service.delete_user(id).await.extend()
+    }
+
+    // ユーザーのロールを変更 - 管理者ロール必須
+    #[graphql(guard = "RoleGuard::admin()")]
+    async fn set_user_role(&self, ctx: &Context<'_>, id: Uuid, role: UserRole) -> Result<User> {
+        let actor = ctx.data::<UserContext>()?.authenticated_user()?;
+        let user = self
+            .user_service
+            .set_user_role(actor.id, id, role)
+            .await
+            .extend()?;
+
+        Ok(User::from(user))
+    }
+
+    // sourceのアカウントをtargetに統合し、sourceを削除する - 管理者ロール必須
+    #[graphql(guard = "RoleGuard::admin()")]
+    async fn merge_users(
+        &self,
+        ctx: &Context<'_>,
+        source_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<User> {
+        let actor = ctx.data::<UserContext>()?.authenticated_user()?;
+        let user = self
+            .user_service
+            .merge_users(actor.id, source_id, target_id)
+            .await
+            .extend()?;
+
+        Ok(User::from(user))
     }
 }
@@ -0,0 +1,33 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+
+use crate::application::services::ApiTokenService;
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::AuthenticationGuard;
+use crate::presentation::graphql::types::api_token_type::ApiToken;
+
+pub struct ApiTokenResolver {
+    api_token_service: Arc<ApiTokenService>,
+}
+
+impl ApiTokenResolver {
+    pub fn new(api_token_service: Arc<ApiTokenService>) -> Self {
+        Self { api_token_service }
+    }
+}
+
+#[Object]
+impl ApiTokenResolver {
+    // 自分のパーソナルアクセストークンの一覧（失効したトークンは含まない）
+    #[graphql(guard = "AuthenticationGuard")]
+    async fn my_api_tokens(&self, ctx: &Context<'_>) -> Result<Vec<ApiToken>> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+        let tokens = self
+            .api_token_service
+            .get_user_tokens(user_id)
+            .await
+            .extend()?;
+
+        Ok(tokens.into_iter().map(ApiToken::from).collect())
+    }
+}
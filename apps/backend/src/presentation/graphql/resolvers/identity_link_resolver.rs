@@ -0,0 +1,35 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+
+use crate::application::services::IdentityLinkService;
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::AuthenticationGuard;
+use crate::presentation::graphql::types::identity_link_type::IdentityLink;
+
+pub struct IdentityLinkResolver {
+    identity_link_service: Arc<IdentityLinkService>,
+}
+
+impl IdentityLinkResolver {
+    pub fn new(identity_link_service: Arc<IdentityLinkService>) -> Self {
+        Self {
+            identity_link_service,
+        }
+    }
+}
+
+#[Object]
+impl IdentityLinkResolver {
+    // 自分に紐付いているすべてのプロバイダーのアカウント
+    #[graphql(guard = "AuthenticationGuard")]
+    async fn my_identities(&self, ctx: &Context<'_>) -> Result<Vec<IdentityLink>> {
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+        let identity_links = self
+            .identity_link_service
+            .get_user_identities(user_id)
+            .await
+            .extend()?;
+
+        Ok(identity_links.into_iter().map(IdentityLink::from).collect())
+    }
+}
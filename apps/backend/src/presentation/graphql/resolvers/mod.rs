@@ -2,5 +2,9 @@
 // GraphQLクエリやミューテーションを処理します。
 
 // サンプルリゾルバーモジュールをここに追加します
+pub mod api_token_resolver;
+pub mod identity_link_resolver;
+pub mod role_resolver;
+pub mod session_resolver;
 pub mod user_resolver;
 // pub mod auth_resolver;
@@ -0,0 +1,41 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::services::RoleService;
+use crate::domain::value_objects::permission::ROLES_MANAGE;
+use crate::presentation::graphql::guards::PermissionGuard;
+use crate::presentation::graphql::types::role_type::{Permission, Role};
+
+pub struct RoleResolver {
+    role_service: Arc<RoleService>,
+}
+
+impl RoleResolver {
+    pub fn new(role_service: Arc<RoleService>) -> Self {
+        Self { role_service }
+    }
+}
+
+// ロールと権限の参照 - roles:manage権限必須
+#[Object]
+impl RoleResolver {
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn roles(&self, _ctx: &Context<'_>) -> Result<Vec<Role>> {
+        let roles = self.role_service.get_all_roles().await.extend()?;
+        Ok(roles.into_iter().map(Role::from).collect())
+    }
+
+    // 付与できる権限の一覧
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn permissions(&self, _ctx: &Context<'_>) -> Result<Vec<Permission>> {
+        let permissions = self.role_service.get_all_permissions().await.extend()?;
+        Ok(permissions.into_iter().map(Permission::from).collect())
+    }
+
+    #[graphql(guard = "PermissionGuard::new(ROLES_MANAGE)")]
+    async fn user_roles(&self, _ctx: &Context<'_>, user_id: Uuid) -> Result<Vec<Role>> {
+        let roles = self.role_service.get_user_roles(user_id).await.extend()?;
+        Ok(roles.into_iter().map(Role::from).collect())
+    }
+}
@@ -0,0 +1,38 @@
+use async_graphql::{Context, Object, Result, ResultExt};
+use std::sync::Arc;
+
+use crate::application::services::SessionService;
+use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::AuthenticationGuard;
+use crate::presentation::graphql::types::session_type::Session;
+
+pub struct SessionResolver {
+    session_service: Arc<SessionService>,
+}
+
+impl SessionResolver {
+    pub fn new(session_service: Arc<SessionService>) -> Self {
+        Self { session_service }
+    }
+}
+
+#[Object]
+impl SessionResolver {
+    // サインイン中の端末の一覧（失効したセッションは含まない）
+    #[graphql(guard = "AuthenticationGuard")]
+    async fn my_sessions(&self, ctx: &Context<'_>) -> Result<Vec<Session>> {
+        let user_context = ctx.data::<UserContext>()?;
+        let user_id = user_context.authenticated_user()?.id;
+        let current_origin_jti = user_context
+            .claims
+            .as_ref()
+            .and_then(|claims| claims.origin_jti.as_deref());
+        let sessions = self
+            .session_service
+            .get_active_sessions(user_id, current_origin_jti)
+            .await
+            .extend()?;
+
+        Ok(sessions.into_iter().map(Session::from).collect())
+    }
+}
@@ -1,10 +1,15 @@
-use async_graphql::{Context, Object, Result, SimpleObject};
+use async_graphql::{Context, Object, Result, ResultExt, SimpleObject};
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::application::policies::{Action, Policy, UserPolicy};
 use crate::application::services::UserService;
+use crate::domain::value_objects::permission::USERS_READ;
 use crate::presentation::graphql::context::UserContext;
+use crate::presentation::graphql::guards::{
+    AuthenticationGuard, PermissionGuard, ResourceOwnershipGuard,
+};
 use crate::presentation::graphql::types::user_type::User;
 
 #[derive(SimpleObject)]
@@ -17,43 +22,56 @@ pub struct UserStatistics {
 
 pub struct UserResolver {
     service: Arc<UserService>,
+    user_policy: Arc<UserPolicy>,
 }
 
 impl UserResolver {
-    pub fn new(service: Arc<UserService>) -> Self {
-        Self { service }
+    pub fn new(service: Arc<UserService>, user_policy: Arc<UserPolicy>) -> Self {
+        Self {
+            service,
+            user_policy,
+        }
     }
 }
 
 #[Object]
 impl UserResolver {
-    // 個別ユーザー取得 - 認証必須
+    // 個別ユーザー取得 - 本人、またはusers:read権限が必要
+    #[graphql(
+        guard = "PermissionGuard::new(USERS_READ).or(ResourceOwnershipGuard::new(self.user_policy.clone(), Action::Read, id))"
+    )]
     async fn user(&self, _ctx: &Context<'_>, id: Uuid) -> Result<Option<User>> {
-        let user = self.service.get_user(id).await?; // Access the inner Uuid via id.0
+        let user = self.service.get_user(id).await.extend()?; // Access the inner Uuid via id.0
         Ok(user.map(User::from))
     }
 
-    // 全ユーザー取得 - 管理者ロール必須
-    async fn users(&self, _ctx: &Context<'_>) -> Result<Vec<User>> {
-        let users = self.service.get_all_users().await?;
+    // 全ユーザー取得 - 参照を許可されたユーザーだけを返す（users:read権限があれば全件）
+    #[graphql(guard = "AuthenticationGuard")]
+    async fn users(&self, ctx: &Context<'_>) -> Result<Vec<User>> {
+        let user_context = ctx.data::<UserContext>()?;
+        let actor = user_context.authenticated_user()?;
+        let mut users = self.service.get_all_users().await.extend()?;
+        if !user_context.has_permission(USERS_READ) {
+            users = self
+                .user_policy
+                .filter_authorized(actor, Action::Read, users);
+        }
+
         Ok(users.into_iter().map(User::from).collect())
     }
 
     // 現在認証されているユーザーの情報を取得
     async fn me(&self, ctx: &Context<'_>) -> Result<Option<User>> {
-        let user_context = ctx.data::<UserContext>()?;
-        if let Some(user) = user_context.user.clone() {
-            let user = self.service.get_user(user.id).await?;
-            Ok(user.map(User::from))
-        } else {
-            return Err("User not found".into());
-        }
+        let user_id = ctx.data::<UserContext>()?.authenticated_user()?.id;
+        let user = self.service.get_user(user_id).await.extend()?;
+        Ok(user.map(User::from))
     }
 
-    // User statistics for admin dashboard
+    // User statistics for admin dashboard - users:read権限必須
+    #[graphql(guard = "PermissionGuard::new(USERS_READ)")]
     async fn user_statistics(&self, _ctx: &Context<'_>) -> Result<UserStatistics> {
         // In a real application, you'd calculate these from the database
-        let all_users = self.service.get_all_users().await?;
+        let all_users = self.service.get_all_users().await.extend()?;
 
         Ok(UserStatistics {
             total_users: all_users.len() as i32,
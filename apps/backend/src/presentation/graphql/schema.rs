@@ -1,5 +1,14 @@
 use crate::application::{services::Services, usecases::UseCases};
+use crate::presentation::graphql::guards::CsrfGuard;
+use crate::presentation::graphql::mutations::api_token_mutation::ApiTokenMutation;
+use crate::presentation::graphql::mutations::identity_link_mutation::IdentityLinkMutation;
+use crate::presentation::graphql::mutations::role_mutation::RoleMutation;
+use crate::presentation::graphql::mutations::session_mutation::SessionMutation;
 use crate::presentation::graphql::mutations::user_mutation::UserMutation;
+use crate::presentation::graphql::resolvers::api_token_resolver::ApiTokenResolver;
+use crate::presentation::graphql::resolvers::identity_link_resolver::IdentityLinkResolver;
+use crate::presentation::graphql::resolvers::role_resolver::RoleResolver;
+use crate::presentation::graphql::resolvers::session_resolver::SessionResolver;
 use crate::presentation::graphql::resolvers::user_resolver::UserResolver;
 use async_graphql::{EmptySubscription, Schema, SchemaBuilder};
 use std::sync::Arc;
@@ -8,7 +17,11 @@ use super::mutations::authentication_mutation::AuthenticationMutation;
 
 // クエリルート定義
 pub struct QueryRoot {
-    user_resolver: UserResolver,
+    pub user_resolver: UserResolver,
+    pub role_resolver: RoleResolver,
+    pub session_resolver: SessionResolver,
+    pub api_token_resolver: ApiTokenResolver,
+    pub identity_link_resolver: IdentityLinkResolver,
     // 他のクエリをここに追加
 }
 
@@ -18,24 +31,67 @@ impl QueryRoot {
     async fn users(&self) -> &UserResolver {
         &self.user_resolver
     }
+    // ロールと権限の参照
+    async fn roles(&self) -> &RoleResolver {
+        &self.role_resolver
+    }
+    // サインイン中の端末の参照
+    async fn sessions(&self) -> &SessionResolver {
+        &self.session_resolver
+    }
+    // パーソナルアクセストークンの参照
+    async fn api_tokens(&self) -> &ApiTokenResolver {
+        &self.api_token_resolver
+    }
+    // ユーザーに紐付いたプロバイダーのアカウントの参照
+    async fn identities(&self) -> &IdentityLinkResolver {
+        &self.identity_link_resolver
+    }
 }
 
 // ミューテーションルート定義
 pub struct MutationRoot {
-    authentication_mutation: AuthenticationMutation,
-    user_mutation: UserMutation,
+    pub authentication_mutation: AuthenticationMutation,
+    pub user_mutation: UserMutation,
+    pub role_mutation: RoleMutation,
+    pub session_mutation: SessionMutation,
+    pub api_token_mutation: ApiTokenMutation,
+    pub identity_link_mutation: IdentityLinkMutation,
     // 他のミューテーションをここに追加
 }
 
 #[async_graphql::Object]
 impl MutationRoot {
+    // すべてのミューテーションは、Cookieで認証されている場合にCSRFトークンを要求する
     // ユーザーミューテーションへのアクセスを提供
+    #[graphql(guard = "CsrfGuard")]
     async fn users(&self) -> &UserMutation {
         &self.user_mutation
     }
+    #[graphql(guard = "CsrfGuard")]
     async fn authentication_mutation(&self) -> &AuthenticationMutation {
         &self.authentication_mutation
     }
+    // ロールの管理
+    #[graphql(guard = "CsrfGuard")]
+    async fn roles(&self) -> &RoleMutation {
+        &self.role_mutation
+    }
+    // 端末ごとのサインアウト
+    #[graphql(guard = "CsrfGuard")]
+    async fn sessions(&self) -> &SessionMutation {
+        &self.session_mutation
+    }
+    // パーソナルアクセストークンの発行と失効
+    #[graphql(guard = "CsrfGuard")]
+    async fn api_tokens(&self) -> &ApiTokenMutation {
+        &self.api_token_mutation
+    }
+    // プロバイダーのアカウントの紐付けと解除
+    #[graphql(guard = "CsrfGuard")]
+    async fn identities(&self) -> &IdentityLinkMutation {
+        &self.identity_link_mutation
+    }
 }
 
 // スキーマ型エイリアス
@@ -59,19 +115,43 @@ pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
 // }
 
 pub fn build_schema(use_cases: &UseCases, services: &Services) -> AppSchema {
-    let user_resolver = UserResolver::new(Arc::clone(&services.user_service));
-    let user_mutation = UserMutation::new(Arc::clone(&services.user_service));
-    let authentication_mutation = AuthenticationMutation::new(
-        Arc::clone(&use_cases.sign_up),
-        Arc::clone(&use_cases.sign_in),
-        Arc::clone(&use_cases.sign_out),
+    let user_resolver = UserResolver::new(
+        Arc::clone(&services.user_service),
+        Arc::clone(&services.user_policy),
+    );
+    let user_mutation = UserMutation::new(
+        Arc::clone(&services.user_service),
+        Arc::clone(&services.user_policy),
+    );
+    let authentication_mutation = AuthenticationMutation::new(use_cases);
+    let role_resolver = RoleResolver::new(Arc::clone(&services.role_service));
+    let role_mutation = RoleMutation::new(Arc::clone(&services.role_service));
+    let session_resolver = SessionResolver::new(Arc::clone(&services.session_service));
+    let session_mutation = SessionMutation::new(Arc::clone(&services.session_service));
+    let api_token_resolver = ApiTokenResolver::new(Arc::clone(&services.api_token_service));
+    let api_token_mutation = ApiTokenMutation::new(Arc::clone(&services.api_token_service));
+    let identity_link_resolver =
+        IdentityLinkResolver::new(Arc::clone(&services.identity_link_service));
+    let identity_link_mutation = IdentityLinkMutation::new(
+        Arc::clone(&use_cases.link_identity),
+        Arc::clone(&services.identity_link_service),
     );
 
     Schema::build(
-        QueryRoot { user_resolver },
+        QueryRoot {
+            user_resolver,
+            role_resolver,
+            session_resolver,
+            api_token_resolver,
+            identity_link_resolver,
+        },
         MutationRoot {
             user_mutation,
             authentication_mutation,
+            role_mutation,
+            session_mutation,
+            api_token_mutation,
+            identity_link_mutation,
         },
         EmptySubscription,
     )
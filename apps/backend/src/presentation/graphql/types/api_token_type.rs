@@ -0,0 +1,57 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::api_token_dto::{ApiTokenDTO, CreatedApiTokenDTO};
+
+#[derive(SimpleObject)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    // 一覧でトークンを見分けるための先頭部分（"morrow_pat_1a2b3c4d"）
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ApiTokenDTO> for ApiToken {
+    fn from(token: ApiTokenDTO) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            scopes: token.scopes,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct CreatedApiToken {
+    pub api_token: ApiToken,
+    // 平文のトークン。サーバーにはハッシュしか残らないため、この応答でしか取得できない
+    pub token: String,
+}
+
+impl From<CreatedApiTokenDTO> for CreatedApiToken {
+    fn from(created: CreatedApiTokenDTO) -> Self {
+        Self {
+            api_token: ApiToken::from(created.api_token),
+            token: created.token,
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct CreateApiTokenInput {
+    #[graphql(validator(min_length = 1, max_length = 100))]
+    pub name: String,
+    // "users:read"のような権限名。自分が持っている権限だけを指定できる
+    pub scopes: Vec<String>,
+    // 省略した場合は無期限
+    pub expires_at: Option<DateTime<Utc>>,
+}
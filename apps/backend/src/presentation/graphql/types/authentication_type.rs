@@ -1,6 +1,13 @@
-use async_graphql::{InputObject, SimpleObject};
+use async_graphql::{InputObject, SimpleObject, Union};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
-use crate::application::dtos::authentication_dto::SignInOutputDTO;
+use crate::application::dtos::authentication_dto::{
+    AssociateSoftwareTokenOutputDTO, AuthChallengeDTO, AuthenticationTokensDTO,
+    CodeDeliveryDetailsDTO, SignInCodeChallengeDTO, SignInOutputDTO, SignUpOutputDTO,
+};
+use crate::presentation::graphql::types::identity_link_type::IdentityLink;
+use crate::presentation::graphql::validators::PasswordValidator;
 
 #[derive(InputObject, Clone)]
 pub struct SignUpInput {
@@ -8,16 +15,179 @@ pub struct SignUpInput {
     pub name: String,
     #[graphql(validator(email))]
     pub email: String,
-    #[graphql(validator(min_length = 8))]
+    #[graphql(validator(custom = "PasswordValidator"))]
     pub password: String,
 }
 
+#[derive(SimpleObject)]
+pub struct SignUpResponse {
+    pub identity_link: IdentityLink,
+    pub user_confirmed: bool,
+    pub session: Option<String>,
+}
+
+impl From<SignUpOutputDTO> for SignUpResponse {
+    fn from(output: SignUpOutputDTO) -> Self {
+        Self {
+            identity_link: IdentityLink::from(output.identity_link),
+            user_confirmed: output.user_confirmed,
+            session: output.session,
+        }
+    }
+}
+
+#[derive(InputObject, Clone)]
+pub struct ConfirmSignUpInput {
+    #[graphql(validator(email))]
+    pub email: String,
+    #[graphql(validator(min_length = 1))]
+    pub confirmation_code: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ConfirmSignUpResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(InputObject, Clone)]
+pub struct ResendConfirmationCodeInput {
+    #[graphql(validator(email))]
+    pub email: String,
+}
+
+#[derive(SimpleObject)]
+pub struct CodeDeliveryDetails {
+    pub destination: Option<String>,
+    pub delivery_medium: Option<String>,
+    pub attribute_name: Option<String>,
+}
+
+impl From<CodeDeliveryDetailsDTO> for CodeDeliveryDetails {
+    fn from(dto: CodeDeliveryDetailsDTO) -> Self {
+        Self {
+            destination: dto.destination,
+            delivery_medium: dto.delivery_medium,
+            attribute_name: dto.attribute_name,
+        }
+    }
+}
+
 #[derive(InputObject, Clone)]
 pub struct SignInInput {
     #[graphql(validator(email))]
     pub email: String,
     #[graphql(validator(min_length = 8))]
     pub password: String,
+    // セッション一覧に表示する端末名（例: "Work laptop"）
+    #[graphql(validator(max_length = 100))]
+    pub device_name: Option<String>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct RequestSignInCodeInput {
+    #[graphql(validator(email))]
+    pub email: String,
+}
+
+// メールで送ったワンタイムコードの入力待ちの状態
+#[derive(SimpleObject)]
+pub struct SignInCodeChallenge {
+    // verifySignInCodeに渡すセッション
+    pub session: Uuid,
+    // この日時を過ぎたら、requestSignInCodeからやり直す
+    pub expires_at: DateTime<Utc>,
+    pub code_delivery_details: CodeDeliveryDetails,
+}
+
+impl From<SignInCodeChallengeDTO> for SignInCodeChallenge {
+    fn from(dto: SignInCodeChallengeDTO) -> Self {
+        Self {
+            session: dto.session,
+            expires_at: dto.expires_at,
+            code_delivery_details: CodeDeliveryDetails::from(dto.code_delivery_details),
+        }
+    }
+}
+
+#[derive(InputObject, Clone)]
+pub struct VerifySignInCodeInput {
+    pub session: Uuid,
+    #[graphql(validator(min_length = 1, max_length = 20))]
+    pub code: String,
+    #[graphql(validator(max_length = 100))]
+    pub device_name: Option<String>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct ForgotPasswordInput {
+    #[graphql(validator(email))]
+    pub email: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ForgotPasswordResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(InputObject, Clone)]
+pub struct ConfirmForgotPasswordInput {
+    #[graphql(validator(email))]
+    pub email: String,
+    #[graphql(validator(min_length = 1))]
+    pub confirmation_code: String,
+    #[graphql(validator(custom = "PasswordValidator"))]
+    pub new_password: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ConfirmForgotPasswordResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(InputObject, Clone)]
+pub struct ChangePasswordInput {
+    #[graphql(validator(min_length = 1))]
+    pub old_password: String,
+    #[graphql(validator(custom = "PasswordValidator"))]
+    pub new_password: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ChangePasswordResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+// 認証アプリに登録するTOTPシークレット
+#[derive(SimpleObject)]
+pub struct SoftwareTokenAssociation {
+    pub secret_code: String,
+    pub session: Option<String>,
+}
+
+impl From<AssociateSoftwareTokenOutputDTO> for SoftwareTokenAssociation {
+    fn from(dto: AssociateSoftwareTokenOutputDTO) -> Self {
+        Self {
+            secret_code: dto.secret_code,
+            session: dto.session,
+        }
+    }
+}
+
+#[derive(InputObject, Clone)]
+pub struct VerifySoftwareTokenInput {
+    #[graphql(validator(regex = r"^\d{6}$"))]
+    pub user_code: String,
+    pub friendly_device_name: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct VerifySoftwareTokenResponse {
+    pub success: bool,
+    pub message: String,
 }
 
 #[derive(SimpleObject)]
@@ -28,14 +198,53 @@ pub struct TokenSet {
     pub expires_in: i32,
 }
 
+#[derive(SimpleObject)]
+pub struct ChallengeParameter {
+    pub name: String,
+    pub value: String,
+}
+
+// サインインを完了するためにrespondToAuthChallengeで応答が必要なチャレンジ
+#[derive(SimpleObject)]
+pub struct AuthChallenge {
+    pub name: String,
+    pub session: Option<String>,
+    pub parameters: Vec<ChallengeParameter>,
+}
+
+#[derive(Union)]
+pub enum SignInResult {
+    TokenSet(TokenSet),
+    AuthChallenge(AuthChallenge),
+}
+
+#[derive(InputObject, Clone)]
+pub struct ChallengeResponseInput {
+    #[graphql(validator(min_length = 1))]
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(InputObject, Clone)]
+pub struct RespondToAuthChallengeInput {
+    #[graphql(validator(email))]
+    pub email: String,
+    #[graphql(validator(min_length = 1))]
+    pub challenge_name: String,
+    pub session: Option<String>,
+    pub responses: Vec<ChallengeResponseInput>,
+    #[graphql(validator(max_length = 100))]
+    pub device_name: Option<String>,
+}
+
 #[derive(SimpleObject)]
 pub struct SignOutResponse {
     pub success: bool,
     pub message: String,
 }
 
-impl From<SignInOutputDTO> for TokenSet {
-    fn from(input: SignInOutputDTO) -> Self {
+impl From<AuthenticationTokensDTO> for TokenSet {
+    fn from(input: AuthenticationTokensDTO) -> Self {
         Self {
             id_token: input.id_token,
             access_token: input.access_token,
@@ -44,3 +253,32 @@ impl From<SignInOutputDTO> for TokenSet {
         }
     }
 }
+
+impl From<AuthChallengeDTO> for AuthChallenge {
+    fn from(dto: AuthChallengeDTO) -> Self {
+        let mut parameters: Vec<ChallengeParameter> = dto
+            .parameters
+            .into_iter()
+            .map(|(name, value)| ChallengeParameter { name, value })
+            .collect();
+        parameters.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            name: dto.name,
+            session: dto.session,
+            parameters,
+        }
+    }
+}
+
+impl From<SignInOutputDTO> for SignInResult {
+    fn from(dto: SignInOutputDTO) -> Self {
+        match dto {
+            SignInOutputDTO::Authenticated(tokens) => {
+                SignInResult::TokenSet(TokenSet::from(tokens))
+            }
+            SignInOutputDTO::Challenge(challenge) => {
+                SignInResult::AuthChallenge(AuthChallenge::from(challenge))
+            }
+        }
+    }
+}
@@ -1,3 +1,6 @@
+pub mod api_token_type;
 pub mod authentication_type;
 pub mod identity_link_type;
+pub mod role_type;
+pub mod session_type;
 pub mod user_type;
@@ -0,0 +1,54 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::role_dto::{PermissionDTO, RoleDTO};
+
+#[derive(SimpleObject)]
+pub struct Role {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<RoleDTO> for Role {
+    fn from(role: RoleDTO) -> Self {
+        Self {
+            id: role.id,
+            name: role.name,
+            description: role.description,
+            permissions: role.permissions,
+            created_at: role.created_at,
+            updated_at: role.updated_at,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Permission {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<PermissionDTO> for Permission {
+    fn from(permission: PermissionDTO) -> Self {
+        Self {
+            id: permission.id,
+            name: permission.name,
+            description: permission.description,
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct CreateRoleInput {
+    #[graphql(validator(min_length = 1))]
+    pub name: String,
+    pub description: Option<String>,
+    // "users:delete"のような権限名
+    pub permissions: Vec<String>,
+}
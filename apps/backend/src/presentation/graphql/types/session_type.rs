@@ -0,0 +1,31 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dtos::session_dto::SessionDTO;
+
+#[derive(SimpleObject)]
+pub struct Session {
+    pub id: Uuid,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    // このリクエストを送った端末のセッション
+    pub current: bool,
+    pub last_used_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SessionDTO> for Session {
+    fn from(session: SessionDTO) -> Self {
+        Self {
+            id: session.id,
+            device: session.device,
+            ip_address: session.ip_address,
+            user_agent: session.user_agent,
+            current: session.current,
+            last_used_at: session.last_used_at,
+            created_at: session.created_at,
+        }
+    }
+}
@@ -1,14 +1,20 @@
-use async_graphql::{InputObject, SimpleObject};
+use async_graphql::{ComplexObject, Context, InputObject, Result, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::application::dtos::user_dto::UserDTO;
+use crate::domain::enums::user_role::UserRole;
+use crate::domain::value_objects::authentication::Claims;
+use crate::presentation::graphql::context::UserContext;
 use crate::presentation::graphql::types::identity_link_type::IdentityLink;
 
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct User {
     pub id: Uuid,
     pub name: String,
+    pub role: UserRole,
+    pub mfa_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub identity_links: Vec<IdentityLink>,
@@ -19,13 +25,42 @@ impl From<UserDTO> for User {
         Self {
             id: user.id,
             name: user.name,
+            role: user.role,
+            mfa_enabled: user.mfa_enabled,
             created_at: user.created_at,
             updated_at: user.updated_at,
-            identity_links: user.identity_links.into_iter().map(IdentityLink::from).collect(),
+            identity_links: user
+                .identity_links
+                .into_iter()
+                .map(IdentityLink::from)
+                .collect(),
         }
     }
 }
 
+// グループとスコープはアクセストークン由来のため、呼び出し元自身（me）の場合だけ返す
+#[ComplexObject]
+impl User {
+    async fn groups(&self, ctx: &Context<'_>) -> Result<Option<Vec<String>>> {
+        Ok(self.caller_claims(ctx)?.map(|claims| claims.groups.clone()))
+    }
+
+    async fn scopes(&self, ctx: &Context<'_>) -> Result<Option<Vec<String>>> {
+        Ok(self.caller_claims(ctx)?.map(|claims| claims.scopes.clone()))
+    }
+}
+
+impl User {
+    fn caller_claims<'a>(&self, ctx: &Context<'a>) -> Result<Option<&'a Claims>> {
+        let user_context = ctx.data::<UserContext>()?;
+        let is_caller = user_context
+            .user
+            .as_ref()
+            .is_some_and(|user| user.id == self.id);
+        Ok(user_context.claims.as_ref().filter(|_| is_caller))
+    }
+}
+
 #[derive(InputObject, Clone)]
 pub struct CreateUserInput {
     #[graphql(validator(min_length = 1))]
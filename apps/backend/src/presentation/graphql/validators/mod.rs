@@ -0,0 +1,5 @@
+// このモジュールは、GraphQL入力値のカスタムバリデーターを定義します。
+
+pub mod password_validator;
+
+pub use password_validator::PasswordValidator;
@@ -0,0 +1,19 @@
+use async_graphql::{CustomValidator, InputValueError};
+
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+
+// サインアップ・パスワード再設定・パスワード変更で共通のパスワードルール
+pub struct PasswordValidator;
+
+impl CustomValidator<String> for PasswordValidator {
+    fn check(&self, value: &String) -> Result<(), InputValueError<String>> {
+        if value.chars().count() >= PASSWORD_MIN_LENGTH {
+            Ok(())
+        } else {
+            Err(InputValueError::custom(format!(
+                "the password must be at least {} characters long",
+                PASSWORD_MIN_LENGTH
+            )))
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use axum::http::{HeaderMap, header};
+use uuid::Uuid;
+
+use crate::application::dtos::authentication_dto::AuthenticationTokensDTO;
+use crate::domain::value_objects::authorization_request::AuthorizationRequest;
+use crate::infrastructure::config::app_config::CookieConfig;
+
+pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+// JavaScriptから読み取り、同じ値をCSRF_TOKEN_HEADERで送り返してもらう（ダブルサブミット）
+pub const CSRF_TOKEN_COOKIE: &str = "csrf_token";
+pub const CSRF_TOKEN_HEADER: &str = "x-csrf-token";
+// 認可コードフローの開始時に生成した値。コールバックにだけ送られるよう、パスを限定する
+pub const AUTHORIZATION_REQUEST_COOKIE: &str = "authorization_request";
+const AUTHORIZATION_REQUEST_PATH: &str = "/auth/callback";
+// Hosted UIでのサインインにかけられる時間
+const AUTHORIZATION_REQUEST_MAX_AGE: i64 = 10 * 60;
+
+pub fn read_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value.to_string())
+}
+
+// アクセストークンとリフレッシュトークンのCookie（どちらもHttpOnly）
+pub fn token_cookies(config: &CookieConfig, tokens: &AuthenticationTokensDTO) -> Vec<String> {
+    vec![
+        build_cookie(
+            config,
+            ACCESS_TOKEN_COOKIE,
+            &tokens.access_token,
+            i64::from(tokens.expires_in),
+            true,
+        ),
+        build_cookie(
+            config,
+            REFRESH_TOKEN_COOKIE,
+            &tokens.refresh_token,
+            config.refresh_token_max_age,
+            true,
+        ),
+    ]
+}
+
+// 新しいCSRFトークンのCookie
+pub fn csrf_cookie(config: &CookieConfig) -> String {
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    build_cookie(
+        config,
+        CSRF_TOKEN_COOKIE,
+        &token,
+        config.refresh_token_max_age,
+        false,
+    )
+}
+
+pub fn clear_cookies(config: &CookieConfig) -> Vec<String> {
+    [
+        (ACCESS_TOKEN_COOKIE, true),
+        (REFRESH_TOKEN_COOKIE, true),
+        (CSRF_TOKEN_COOKIE, false),
+    ]
+    .into_iter()
+    .map(|(name, http_only)| build_cookie(config, name, "", 0, http_only))
+    .collect()
+}
+
+// CookieのCSRFトークンとヘッダーの値が一致するか
+pub fn csrf_token_matches(headers: &HeaderMap) -> bool {
+    let header = headers
+        .get(CSRF_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok());
+    match (read_cookie(headers, CSRF_TOKEN_COOKIE), header) {
+        (Some(cookie), Some(header)) => constant_time_eq(cookie.as_bytes(), header.as_bytes()),
+        _ => false,
+    }
+}
+
+// 認可サーバーからのリダイレクト（トップレベルのGET）でも送られるよう、SameSite=Laxにする
+// state・nonce・code_verifierはbase64urlのため、"."で区切って1つのCookieにまとめる
+pub fn authorization_request_cookie(request: &AuthorizationRequest, secure: bool) -> String {
+    let value = format!(
+        "{}.{}.{}",
+        request.state, request.nonce, request.code_verifier
+    );
+    build_authorization_request_cookie(&value, AUTHORIZATION_REQUEST_MAX_AGE, secure)
+}
+
+pub fn clear_authorization_request_cookie(secure: bool) -> String {
+    build_authorization_request_cookie("", 0, secure)
+}
+
+// Cookieに保存した認可リクエストのうち、コールバックのstateと一致するものを返す
+pub fn read_authorization_request(
+    headers: &HeaderMap,
+    state: &str,
+) -> Option<AuthorizationRequest> {
+    let value = read_cookie(headers, AUTHORIZATION_REQUEST_COOKIE)?;
+    let mut parts = value.splitn(3, '.');
+    let request = AuthorizationRequest {
+        state: parts.next()?.to_string(),
+        nonce: parts.next()?.to_string(),
+        code_verifier: parts.next()?.to_string(),
+    };
+    constant_time_eq(request.state.as_bytes(), state.as_bytes()).then_some(request)
+}
+
+fn build_authorization_request_cookie(value: &str, max_age: i64, secure: bool) -> String {
+    let mut cookie = format!(
+        "{}={}; Path={}; Max-Age={}; SameSite=Lax; HttpOnly",
+        AUTHORIZATION_REQUEST_COOKIE, value, AUTHORIZATION_REQUEST_PATH, max_age
+    );
+    if secure {
+        cookie.push_str("; Secure");
+    }
+    cookie
+}
+
+fn build_cookie(
+    config: &CookieConfig,
+    name: &str,
+    value: &str,
+    max_age: i64,
+    http_only: bool,
+) -> String {
+    let mut cookie = format!(
+        "{}={}; Path=/; Max-Age={}; SameSite={}",
+        name, value, max_age, config.same_site
+    );
+    if let Some(domain) = &config.domain {
+        cookie.push_str(&format!("; Domain={}", domain));
+    }
+    if config.secure {
+        cookie.push_str("; Secure");
+    }
+    if http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    cookie
+}
+
+// 比較にかかる時間からトークンを推測されないようにする
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::app_config::SameSite;
+    use axum::http::HeaderValue;
+
+    fn config() -> CookieConfig {
+        CookieConfig {
+            domain: None,
+            secure: true,
+            same_site: SameSite::Lax,
+            refresh_token_max_age: 3600,
+        }
+    }
+
+    fn headers(cookie: &str, csrf_header: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_str(cookie).unwrap());
+        if let Some(csrf_header) = csrf_header {
+            headers.insert(
+                CSRF_TOKEN_HEADER,
+                HeaderValue::from_str(csrf_header).unwrap(),
+            );
+        }
+        headers
+    }
+
+    #[test]
+    fn test_read_cookie() {
+        let headers = headers("theme=dark; access_token=abc.def; csrf_token=", None);
+
+        assert_eq!(
+            read_cookie(&headers, ACCESS_TOKEN_COOKIE).as_deref(),
+            Some("abc.def")
+        );
+        assert_eq!(read_cookie(&headers, CSRF_TOKEN_COOKIE), None);
+        assert_eq!(read_cookie(&headers, REFRESH_TOKEN_COOKIE), None);
+    }
+
+    #[test]
+    fn test_token_cookies_are_http_only() {
+        let tokens = AuthenticationTokensDTO {
+            id_token: "id".to_string(),
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_in: 300,
+        };
+
+        let cookies = token_cookies(&config(), &tokens);
+
+        assert_eq!(
+            cookies[0],
+            "access_token=access; Path=/; Max-Age=300; SameSite=Lax; Secure; HttpOnly"
+        );
+        assert_eq!(
+            cookies[1],
+            "refresh_token=refresh; Path=/; Max-Age=3600; SameSite=Lax; Secure; HttpOnly"
+        );
+    }
+
+    #[test]
+    fn test_csrf_cookie_is_readable_by_scripts() {
+        let cookie = csrf_cookie(&config());
+
+        assert!(cookie.starts_with("csrf_token="));
+        assert!(!cookie.contains("HttpOnly"));
+    }
+
+    #[test]
+    fn test_clear_cookies_expire_immediately() {
+        let cookies = clear_cookies(&config());
+
+        assert_eq!(cookies.len(), 3);
+        assert!(cookies.iter().all(|cookie| cookie.contains("Max-Age=0")));
+    }
+
+    #[test]
+    fn test_authorization_request_cookie_round_trip() {
+        let request = AuthorizationRequest::generate();
+        let cookie = authorization_request_cookie(&request, true);
+        assert!(cookie.contains("Path=/auth/callback"));
+        assert!(cookie.contains("HttpOnly"));
+        let value = cookie.split(';').next().unwrap();
+
+        assert_eq!(
+            read_authorization_request(&headers(value, None), &request.state),
+            Some(request.clone())
+        );
+        // コールバックのstateが一致しなければ、保存した値は使わない
+        assert_eq!(
+            read_authorization_request(&headers(value, None), "forged-state"),
+            None
+        );
+        assert_eq!(
+            read_authorization_request(&headers("theme=dark", None), &request.state),
+            None
+        );
+    }
+
+    #[test]
+    fn test_csrf_token_matches() {
+        assert!(csrf_token_matches(&headers("csrf_token=abc", Some("abc"))));
+        assert!(!csrf_token_matches(&headers("csrf_token=abc", Some("abd"))));
+        assert!(!csrf_token_matches(&headers("csrf_token=abc", None)));
+        assert!(!csrf_token_matches(&headers(
+            "access_token=abc",
+            Some("abc")
+        )));
+    }
+}
@@ -0,0 +1,188 @@
+use axum::{
+    Json,
+    extract::{ConnectInfo, Extension, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::application::dtos::authentication_dto::{
+    AuthenticationTokensDTO, AuthorizationCodeInputDTO,
+};
+use crate::application::errors::ApplicationError;
+use crate::application::usecases::sign_in_with_authorization_code::SignInWithAuthorizationCode;
+use crate::domain::services::authorization_code_service::AuthorizationCodeService;
+use crate::domain::value_objects::authorization_request::AuthorizationRequest;
+use crate::infrastructure::config::app_config::{CookieConfig, TrustedProxies};
+use crate::presentation::http::cookies::{
+    authorization_request_cookie, clear_authorization_request_cookie, csrf_cookie,
+    read_authorization_request, token_cookies,
+};
+use crate::presentation::http::handlers::graphql_handler::client_info;
+
+// /auth/authorizeと/auth/callbackが使う状態
+#[derive(Clone)]
+pub struct AuthorizationState {
+    pub authorization_code_service: Arc<dyn AuthorizationCodeService>,
+    pub sign_in_with_authorization_code: Arc<SignInWithAuthorizationCode>,
+    // Cookieモードではトークンを本文で返さず、Cookieに設定してpost_login_redirectへ戻す
+    pub cookies: Option<CookieConfig>,
+    pub post_login_redirect: String,
+    pub secure_cookie: bool,
+    // セッションに記録する接続元のIPアドレスを決めるのに使う
+    pub trusted_proxies: TrustedProxies,
+}
+
+#[derive(Deserialize)]
+pub struct AuthorizeParams {
+    // Cognitoに登録した外部プロバイダー名（例: Google, SignInWithApple）
+    identity_provider: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+// Hosted UIでのサインインを開始する
+pub async fn authorize(
+    State(state): State<AuthorizationState>,
+    Query(params): Query<AuthorizeParams>,
+) -> Response {
+    let request = AuthorizationRequest::generate();
+    let url = state
+        .authorization_code_service
+        .authorization_url(&request, params.identity_provider.as_deref());
+    (
+        [(
+            header::SET_COOKIE,
+            authorization_request_cookie(&request, state.secure_cookie),
+        )],
+        Redirect::to(&url),
+    )
+        .into_response()
+}
+
+// Hosted UIからのリダイレクトを受け取り、サインインを完了する
+pub async fn callback(
+    State(state): State<AuthorizationState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    Query(params): Query<CallbackParams>,
+) -> Response {
+    let remote_addr = connect_info.map(|Extension(ConnectInfo(addr))| addr);
+    let mut response = complete_sign_in(&state, &headers, remote_addr, params).await;
+    // 成否にかかわらず、認可リクエストは一度だけ使う
+    append_cookie(
+        &mut response,
+        clear_authorization_request_cookie(state.secure_cookie),
+    );
+    response
+}
+
+async fn complete_sign_in(
+    state: &AuthorizationState,
+    headers: &HeaderMap,
+    remote_addr: Option<SocketAddr>,
+    params: CallbackParams,
+) -> Response {
+    // ユーザーがサインインを取り消した場合など、認可サーバーがエラーを返した
+    if let Some(error) = params.error {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            &error,
+            params.error_description.as_deref(),
+        );
+    }
+    let (Some(code), Some(callback_state)) = (params.code, params.state) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_request",
+            Some("code and state are required"),
+        );
+    };
+    // このブラウザで開始したサインインへの応答でなければ受け付けない
+    let Some(request) = read_authorization_request(headers, &callback_state) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_state",
+            Some("state does not match the authorization request"),
+        );
+    };
+
+    let result = state
+        .sign_in_with_authorization_code
+        .execute(
+            AuthorizationCodeInputDTO {
+                code,
+                code_verifier: request.code_verifier,
+                nonce: request.nonce,
+            },
+            client_info(headers, remote_addr, &state.trusted_proxies),
+        )
+        .await;
+    match result {
+        Ok(tokens) => issue_tokens(state, tokens),
+        Err(ApplicationError::Authentication(error)) => error_response(
+            StatusCode::UNAUTHORIZED,
+            "access_denied",
+            Some(&error.to_string()),
+        ),
+        Err(ApplicationError::Unauthenticated(message)) => {
+            error_response(StatusCode::UNAUTHORIZED, "access_denied", Some(&message))
+        }
+        Err(error) => {
+            tracing::error!("Failed to complete authorization code sign in: {}", error);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "server_error", None)
+        }
+    }
+}
+
+// Cookieモードではトークンと新しいCSRFトークンをCookieに設定してアプリケーションへ戻し、
+// それ以外ではトークンを本文で返す
+fn issue_tokens(state: &AuthorizationState, tokens: AuthenticationTokensDTO) -> Response {
+    let Some(cookies) = &state.cookies else {
+        return (
+            [(header::CACHE_CONTROL, "no-store")],
+            Json(serde_json::json!({
+                "idToken": tokens.id_token,
+                "accessToken": tokens.access_token,
+                "refreshToken": tokens.refresh_token,
+                "expiresIn": tokens.expires_in,
+            })),
+        )
+            .into_response();
+    };
+    let mut response = Redirect::to(&state.post_login_redirect).into_response();
+    for cookie in token_cookies(cookies, &tokens) {
+        append_cookie(&mut response, cookie);
+    }
+    append_cookie(&mut response, csrf_cookie(cookies));
+    response
+}
+
+fn append_cookie(response: &mut Response, cookie: String) {
+    match HeaderValue::from_str(&cookie) {
+        Ok(value) => {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+        Err(e) => tracing::error!("Invalid Set-Cookie header: {}", e),
+    }
+}
+
+// RFC 6749 5.2と同じ形式のエラー
+fn error_response(status: StatusCode, error: &str, description: Option<&str>) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "error": error,
+            "error_description": description,
+        })),
+    )
+        .into_response()
+}
@@ -1,10 +1,14 @@
 use async_graphql::http::GraphiQLSource;
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
-    extract::{Extension, State},
+    extract::{ConnectInfo, Extension, State},
+    http::{HeaderMap, header},
     response::{Html, IntoResponse},
 };
+use std::net::{IpAddr, SocketAddr};
 
+use crate::application::dtos::authentication_dto::ClientInfoDTO;
+use crate::infrastructure::config::app_config::{CookieConfig, TrustedProxies};
 use crate::presentation::graphql::{AppSchema, context::UserContext};
 
 // GraphQL Playground
@@ -16,10 +20,143 @@ pub async fn graphql_playground() -> impl IntoResponse {
 pub async fn graphql_handler(
     State(schema): State<AppSchema>,
     Extension(user): Extension<UserContext>,
+    cookies: Option<Extension<CookieConfig>>,
+    trusted_proxies: Option<Extension<TrustedProxies>>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
     // GraphQLリクエストを実行
     let mut request = req.0;
     request = request.data(user);
+    // サインイン時にセッションとして記録する接続元の情報
+    let remote_addr = connect_info.map(|Extension(ConnectInfo(addr))| addr);
+    let trusted_proxies = trusted_proxies
+        .map(|Extension(trusted_proxies)| trusted_proxies)
+        .unwrap_or_default();
+    request = request.data(client_info(&headers, remote_addr, &trusted_proxies));
+    // Cookieモードでは、サインインなどのリゾルバーがレスポンスにCookieを設定する
+    if let Some(Extension(cookies)) = cookies {
+        request = request.data(cookies);
+    }
     schema.execute(request).await.into()
 }
+
+// 接続元のIPアドレスとUser-Agent
+// X-Forwarded-Forは信頼するプロキシから届いた場合だけ使い、右から順に信頼するプロキシを除いた最初のアドレスを接続元とする
+// 左側の値はクライアントが自由に書けるため、先頭の値をそのまま使ってはいけない
+pub(crate) fn client_info(
+    headers: &HeaderMap,
+    remote_addr: Option<SocketAddr>,
+    trusted_proxies: &TrustedProxies,
+) -> ClientInfoDTO {
+    ClientInfoDTO {
+        device: None,
+        ip_address: remote_addr
+            .map(|addr| client_ip(headers, addr.ip(), trusted_proxies).to_string()),
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string()),
+    }
+}
+
+fn client_ip(headers: &HeaderMap, remote_ip: IpAddr, trusted_proxies: &TrustedProxies) -> IpAddr {
+    if !trusted_proxies.contains(&remote_ip) {
+        return remote_ip;
+    }
+    let hops = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    let mut client_ip = remote_ip;
+    for hop in hops.into_iter().rev() {
+        // 解釈できない値より左は信頼できないため、最後に確認できたアドレスを使う
+        let Ok(ip) = hop.parse::<IpAddr>() else {
+            break;
+        };
+        client_ip = ip;
+        if !trusted_proxies.contains(&ip) {
+            break;
+        }
+    }
+    client_ip
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(forwarded_for: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static(forwarded_for));
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("curl/8.5.0"));
+        headers
+    }
+
+    fn ip_address(headers: &HeaderMap, remote_addr: &str, trusted_proxies: &str) -> Option<String> {
+        client_info(
+            headers,
+            Some(remote_addr.parse().unwrap()),
+            &trusted_proxies.parse().unwrap(),
+        )
+        .ip_address
+    }
+
+    #[test]
+    fn test_client_info_uses_right_most_untrusted_forwarded_for() {
+        // クライアントが偽装した198.51.100.1は、信頼するプロキシの外側にあるため使わない
+        let headers = headers("198.51.100.1, 203.0.113.7, 10.0.0.1");
+
+        let client = client_info(
+            &headers,
+            Some("10.0.0.2:443".parse().unwrap()),
+            &"10.0.0.0/8".parse().unwrap(),
+        );
+
+        assert_eq!(client.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(client.user_agent.as_deref(), Some("curl/8.5.0"));
+    }
+
+    #[test]
+    fn test_client_info_ignores_forwarded_for_without_trusted_proxies() {
+        let headers = headers("203.0.113.7");
+
+        assert_eq!(
+            ip_address(&headers, "10.0.0.2:443", "").as_deref(),
+            Some("10.0.0.2")
+        );
+        // 信頼するプロキシ以外から直接届いたX-Forwarded-Forも使わない
+        assert_eq!(
+            ip_address(&headers, "192.0.2.5:443", "10.0.0.0/8").as_deref(),
+            Some("192.0.2.5")
+        );
+    }
+
+    #[test]
+    fn test_client_info_stops_at_unparsable_forwarded_for() {
+        let headers = headers("203.0.113.7, unknown, 10.0.0.1");
+
+        assert_eq!(
+            ip_address(&headers, "10.0.0.2:443", "10.0.0.0/8").as_deref(),
+            Some("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_client_info_falls_back_to_remote_addr() {
+        let client = client_info(
+            &HeaderMap::new(),
+            Some("10.0.0.2:443".parse().unwrap()),
+            &TrustedProxies::default(),
+        );
+
+        assert_eq!(client.ip_address.as_deref(), Some("10.0.0.2"));
+        assert_eq!(client.user_agent, None);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod authorization_handler;
 pub mod graphql_handler;
 pub mod health;
 // このモジュールは、HTTPリクエストハンドラーを定義します。
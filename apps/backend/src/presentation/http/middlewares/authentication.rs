@@ -1,39 +1,191 @@
+use crate::application::dtos::authentication_dto::{
+    AuthenticationTokensDTO, RefreshTokensInputDTO,
+};
+use crate::application::errors::ApplicationError;
+use crate::application::usecases::authenticate_api_token::AuthenticateApiToken;
 use crate::application::usecases::authenticate_user::AuthenticateUser;
-use crate::presentation::graphql::context::UserContext;
+use crate::application::usecases::refresh_tokens::RefreshTokens;
+use crate::domain::errors::AuthenticationError;
+use crate::domain::value_objects::api_token_secret::API_TOKEN_PREFIX;
+use crate::infrastructure::config::app_config::CookieConfig;
+use crate::presentation::graphql::context::{AuthenticationFailure, UserContext};
+use crate::presentation::graphql::errors::authentication_failed;
+use crate::presentation::http::cookies::{
+    ACCESS_TOKEN_COOKIE, CSRF_TOKEN_COOKIE, REFRESH_TOKEN_COOKIE, clear_cookies, csrf_cookie,
+    csrf_token_matches, read_cookie, token_cookies,
+};
+use async_graphql::ErrorExtensions;
 use axum::{
+    Json,
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use std::sync::Arc;
 
+// トークンを受け付けなかった理由
+enum Rejection {
+    // 不正・期限切れのトークン（401で拒否する）
+    Token(AuthenticationFailure),
+    // 認証プロバイダーやDBの障害で、トークンの正否を判断できなかった
+    Unavailable(ApplicationError),
+}
+
+// 認証ミドルウェアの状態（cookiesがSomeの場合はCookieモード）
+#[derive(Clone)]
+pub struct AuthenticationState {
+    pub authenticate_user: Arc<AuthenticateUser>,
+    pub authenticate_api_token: Arc<AuthenticateApiToken>,
+    pub refresh_tokens: Arc<RefreshTokens>,
+    pub cookies: Option<CookieConfig>,
+}
+
 pub async fn authenticate_user(
-    State(authenticate_user): State<Arc<AuthenticateUser>>,
+    State(state): State<AuthenticationState>,
     headers: HeaderMap,
     mut request: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    let token = extract_token_from_headers(&headers);
-    match token {
-        Some(token) => {
-            let user = authenticate_user.execute(&token).await;
-            match user {
-                Ok(user) => {
-                    request
-                        .extensions_mut()
-                        .insert(UserContext { user: Some(user) });
-                    Ok(next.run(request).await)
-                }
-                Err(_) => {
-                    request.extensions_mut().insert(UserContext::default());
-                    Ok(next.run(request).await)
-                }
+) -> Response {
+    // Cookieモードでは、リゾルバーがCookieを設定できるよう設定をリクエストに渡す
+    if let Some(cookies) = &state.cookies {
+        request.extensions_mut().insert(cookies.clone());
+    }
+
+    // Authorizationヘッダーが優先され、CSRFトークンは不要
+    if let Some(token) = extract_token_from_headers(&headers) {
+        return match authenticate(&state, token).await {
+            Ok(user_context) => {
+                request.extensions_mut().insert(user_context);
+                next.run(request).await
             }
-        }
+            // 不正・期限切れのトークンはRFC 6750に従って401で拒否し、クライアントに更新を促す
+            Err(Rejection::Token(failure)) => invalid_token_response(failure),
+            Err(Rejection::Unavailable(error)) => unavailable_response(&error),
+        };
+    }
+
+    match &state.cookies {
+        Some(cookies) => authenticate_with_cookies(&state, cookies, &headers, request, next).await,
+        // トークンが無ければ匿名のリクエストとして扱う
         None => {
             request.extensions_mut().insert(UserContext::default());
-            Ok(next.run(request).await)
+            next.run(request).await
+        }
+    }
+}
+
+// アクセストークンのCookieが期限切れ（ブラウザが破棄済み）なら、リフレッシュトークンで透過的に更新する
+async fn authenticate_with_cookies(
+    state: &AuthenticationState,
+    cookies: &CookieConfig,
+    headers: &HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let access_token = read_cookie(headers, ACCESS_TOKEN_COOKIE);
+    let refresh_token = read_cookie(headers, REFRESH_TOKEN_COOKIE);
+    if access_token.is_none() && refresh_token.is_none() {
+        request.extensions_mut().insert(UserContext::default());
+        return next.run(request).await;
+    }
+
+    let result = match access_token {
+        Some(access_token) => authenticate(state, access_token).await,
+        None => Err(Rejection::Token(AuthenticationFailure::ExpiredToken)),
+    };
+    let mut set_cookies = Vec::new();
+    let mut user_context = match (result, refresh_token.clone()) {
+        (Ok(user_context), _) => user_context,
+        (Err(Rejection::Token(AuthenticationFailure::ExpiredToken)), Some(refresh_token)) => {
+            let tokens = match refresh(&state.refresh_tokens, refresh_token).await {
+                Some(tokens) => tokens,
+                None => return reject_cookies(cookies, AuthenticationFailure::ExpiredToken),
+            };
+            set_cookies = token_cookies(cookies, &tokens);
+            if read_cookie(headers, CSRF_TOKEN_COOKIE).is_none() {
+                set_cookies.push(csrf_cookie(cookies));
+            }
+            match authenticate(state, tokens.access_token).await {
+                Ok(user_context) => user_context,
+                Err(Rejection::Token(failure)) => return reject_cookies(cookies, failure),
+                Err(Rejection::Unavailable(error)) => return unavailable_response(&error),
+            }
+        }
+        (Err(Rejection::Token(failure)), _) => return reject_cookies(cookies, failure),
+        // 障害時にCookieを削除すると全員がサインアウトされるため、Cookieは残す
+        (Err(Rejection::Unavailable(error)), _) => return unavailable_response(&error),
+    };
+    // Cookieはブラウザが自動で送るため、ミューテーションにはダブルサブミットのCSRFトークンを要求する
+    user_context.csrf_failed = !csrf_token_matches(headers);
+    user_context.refresh_token = refresh_token;
+    request.extensions_mut().insert(user_context);
+
+    let mut response = next.run(request).await;
+    append_cookies(&mut response, set_cookies);
+    response
+}
+
+// 認証できればUserContextを返す。不正・期限切れのトークンと、認証できなかった障害をErrにする
+// プレフィックスの付いたトークンは、パーソナルアクセストークンとして認証する
+async fn authenticate(
+    state: &AuthenticationState,
+    token: String,
+) -> Result<UserContext, Rejection> {
+    let is_api_token = token.starts_with(API_TOKEN_PREFIX);
+    let result = if is_api_token {
+        state.authenticate_api_token.execute(&token).await
+    } else {
+        state.authenticate_user.execute(&token).await
+    };
+    match result {
+        Ok(authenticated) => Ok(UserContext {
+            user: Some(authenticated.user),
+            // パーソナルアクセストークンは認証プロバイダーのアクセストークンとして使えないため保持しない
+            access_token: (!is_api_token).then_some(token),
+            permissions: authenticated.permissions,
+            claims: Some(authenticated.claims),
+            api_token_id: authenticated.api_token_id,
+            ..Default::default()
+        }),
+        Err(error) => match authentication_failure(&error) {
+            Some(
+                failure @ (AuthenticationFailure::InvalidToken
+                | AuthenticationFailure::ExpiredToken),
+            ) => Err(Rejection::Token(failure)),
+            // 認証を必要としない操作（signUpなど）は続けられるよう、理由だけを保持する
+            Some(failure) => Ok(UserContext {
+                authentication_failure: Some(failure),
+                ..Default::default()
+            }),
+            // 匿名として続けると、障害の間は認証済みのユーザーも未認証として扱われてしまう
+            None => Err(Rejection::Unavailable(error)),
+        },
+    }
+}
+
+async fn refresh(
+    refresh_tokens: &RefreshTokens,
+    refresh_token: String,
+) -> Option<AuthenticationTokensDTO> {
+    refresh_tokens
+        .execute(RefreshTokensInputDTO { refresh_token })
+        .await
+        .inspect_err(|e| tracing::debug!("Failed to refresh tokens from cookie: {}", e))
+        .ok()
+}
+
+// 使えなくなったCookieは削除して、クライアントに再サインインを促す
+fn reject_cookies(cookies: &CookieConfig, failure: AuthenticationFailure) -> Response {
+    let mut response = invalid_token_response(failure);
+    append_cookies(&mut response, clear_cookies(cookies));
+    response
+}
+
+fn append_cookies(response: &mut Response, cookies: Vec<String>) {
+    for cookie in cookies {
+        if let Ok(cookie) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, cookie);
         }
     }
 }
@@ -42,11 +194,124 @@ fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
     headers
         .get("Authorization")
         .and_then(|value| value.to_str().ok())
-        .and_then(|value| {
-            if value.starts_with("Bearer ") {
-                Some(value[7..].to_string())
-            } else {
-                None
-            }
-        })
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.to_string())
+}
+
+fn authentication_failure(error: &ApplicationError) -> Option<AuthenticationFailure> {
+    match error {
+        ApplicationError::Authentication(AuthenticationError::InvalidToken(_)) => {
+            Some(AuthenticationFailure::InvalidToken)
+        }
+        ApplicationError::Authentication(AuthenticationError::ExpiredToken(_)) => {
+            Some(AuthenticationFailure::ExpiredToken)
+        }
+        ApplicationError::Authentication(AuthenticationError::UserNotFound(_)) => {
+            Some(AuthenticationFailure::UnknownUser)
+        }
+        _ => None,
+    }
+}
+
+// GraphQLクライアントが通常のエラーとして扱えるよう、本文はGraphQLのレスポンス形式にする
+fn invalid_token_response(failure: AuthenticationFailure) -> Response {
+    let error = authentication_failed(failure);
+    let body = serde_json::json!({
+        "errors": [{ "message": error.message, "extensions": error.extensions }],
+    });
+    let challenge = format!(
+        "Bearer error=\"invalid_token\", error_description=\"{}\"",
+        failure.message()
+    );
+    let mut response = (StatusCode::UNAUTHORIZED, Json(body)).into_response();
+    if let Ok(challenge) = HeaderValue::from_str(&challenge) {
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, challenge);
+    }
+    response
+}
+
+// トークンを検証できなかった場合のエラー（詳細はログにだけ残す）
+// 認証プロバイダーの障害は503、それ以外は500にする
+fn unavailable_response(error: &ApplicationError) -> Response {
+    let status = match error {
+        ApplicationError::Authentication(AuthenticationError::Provider(_)) => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let error =
+        ApplicationError::InternalError(format!("Failed to authenticate access token: {}", error))
+            .extend();
+    let body = serde_json::json!({
+        "errors": [{ "message": error.message, "extensions": error.extensions }],
+    });
+    (status, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authentication_failure_classifies_token_errors() {
+        let failure = |error| authentication_failure(&ApplicationError::Authentication(error));
+
+        assert_eq!(
+            failure(AuthenticationError::InvalidToken("bad".to_string())),
+            Some(AuthenticationFailure::InvalidToken)
+        );
+        assert_eq!(
+            failure(AuthenticationError::ExpiredToken("expired".to_string())),
+            Some(AuthenticationFailure::ExpiredToken)
+        );
+        assert_eq!(
+            failure(AuthenticationError::UserNotFound("unknown".to_string())),
+            Some(AuthenticationFailure::UnknownUser)
+        );
+        assert_eq!(
+            authentication_failure(&ApplicationError::DatabaseError("down".to_string())),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_response_follows_rfc_6750() {
+        let response = invalid_token_response(AuthenticationFailure::ExpiredToken);
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[header::WWW_AUTHENTICATE],
+            "Bearer error=\"invalid_token\", error_description=\"Access token has expired\""
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["errors"][0]["extensions"]["code"], "UNAUTHENTICATED");
+        assert_eq!(body["errors"][0]["extensions"]["reason"], "expired_token");
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_response_hides_the_cause() {
+        let response = unavailable_response(&ApplicationError::Authentication(
+            AuthenticationError::Provider("JWKS endpoint is down".to_string()),
+        ));
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(header::WWW_AUTHENTICATE).is_none());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["errors"][0]["extensions"]["code"], "INTERNAL");
+        assert_eq!(body["errors"][0]["message"], "Internal server error");
+
+        let response = unavailable_response(&ApplicationError::DatabaseError(
+            "connection refused".to_string(),
+        ));
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
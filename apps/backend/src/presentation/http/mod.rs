@@ -1,3 +1,4 @@
+pub mod cookies;
 pub mod handlers;
 pub mod middlewares;
 
@@ -2,8 +2,13 @@
 mod tests {
     use backend::{
         application::{services, usecases},
+        domain::{
+            services::authentication_service_registry::AuthenticationServiceRegistry,
+            value_objects::group_mapping::GroupMapping,
+        },
         infrastructure::{
-            authentication::cognito_service::CognitoService,
+            authentication::{cognito_service::CognitoService, unverified_issuer},
+            config::app_config::CognitoConfig,
             database::{connection, repositories},
         },
         presentation::graphql::schema::build_schema,
@@ -32,16 +37,40 @@ mod tests {
                 println!("Database connection established successfully");
 
                 // Initialize repositories
-                let repositories = repositories::init_repositories(conn);
+                let repositories = repositories::init_repositories(Arc::new(conn));
 
                 // Create a mock AWS config for testing
                 let sdk_config =
                     aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
-                let auth_service = Arc::new(CognitoService::new(&sdk_config));
+                let cognito_config = CognitoConfig {
+                    region: "ap-northeast-1".to_string(),
+                    user_pool_id: "ap-northeast-1_test".to_string(),
+                    client_id: "test-client-id".to_string(),
+                    jwks_url: "file:///dev/null".to_string(),
+                    jwks_cache_ttl: 3600,
+                };
+                let cognito_issuer = cognito_config.issuer();
+                let auth_service = Arc::new(CognitoService::new(&sdk_config, cognito_config));
 
                 // Initialize services and use cases
-                let services = services::init_services(Arc::new(repositories.clone())).await;
-                let use_cases = usecases::init_use_cases(Arc::new(repositories), auth_service);
+                let token_denylist = Arc::new(services::TokenDenylist::new(
+                    repositories.revoked_token_repository.clone(),
+                    chrono::Duration::hours(1),
+                ));
+                let services =
+                    services::init_services(Arc::new(repositories.clone()), token_denylist.clone())
+                        .await;
+                let use_cases = usecases::init_use_cases(
+                    Arc::new(repositories),
+                    Arc::new(AuthenticationServiceRegistry::new(
+                        cognito_issuer,
+                        auth_service,
+                        unverified_issuer,
+                    )),
+                    None,
+                    GroupMapping::default(),
+                    token_denylist,
+                );
 
                 // Try to build the GraphQL schema
                 let _schema = build_schema(&use_cases, &services);
@@ -1,26 +1,70 @@
 #[cfg(test)]
 mod tests {
-    use async_graphql::{Request, Schema, EmptySubscription};
+    use async_graphql::{EmptySubscription, Request, Schema, ServerError, Value, Variables};
+    use async_trait::async_trait;
     use backend::{
         application::{
-            usecases::{sign_up::SignUp, sign_in::SignIn, sign_out::SignOut},
-            services::UserService,
+            dtos::{authentication_dto::ClientInfoDTO, user_dto::UserDTO},
+            policies::UserPolicy,
+            services::{
+                ApiTokenService, IdentityLinkService, Repositories, RoleService, SessionService,
+                TokenDenylist, UserService,
+            },
+            usecases::init_use_cases,
         },
+        domain::services::authentication_service_registry::AuthenticationServiceRegistry,
         domain::{
-            entities::{identity_link::{IdentityLink, NewIdentityLink}, user::{User, NewUser}},
-            repositories::{identity_link_repository::IdentityLinkRepository, user_repository::UserRepository},
+            entities::{
+                api_token::{ApiToken, NewApiToken},
+                identity_link::{IdentityLink, NewIdentityLink},
+                permission::Permission,
+                role::{NewRole, Role},
+                session::{NewSession, Session},
+                sign_in_code_request::{NewSignInCodeRequest, SignInCodeRequest},
+                sign_up_compensation::{NewSignUpCompensation, SignUpCompensation},
+                user::{NewUser, User, UserChanges},
+            },
+            enums::user_role::UserRole,
+            errors::{
+                AuthenticationError, AuthenticationResult, RepositoryError, RepositoryResult,
+            },
+            repositories::{
+                api_token_repository::ApiTokenRepository,
+                identity_link_repository::IdentityLinkRepository,
+                permission_repository::PermissionRepository, session_repository::SessionRepository,
+                sign_in_code_request_repository::SignInCodeRequestRepository,
+                sign_up_compensation_repository::SignUpCompensationRepository,
+                user_repository::UserRepository,
+            },
             services::authentication_service::AuthenticationService,
-            value_objects::authentication::{SignUpOutput, SignInOutput, Claims},
+            value_objects::authentication::{
+                AuthChallenge, AuthenticationTokens, Claims, CodeDeliveryDetails,
+                SignInCodeChallenge, SignInCodeVerification, SignInOutput, SignUpOutput,
+                SoftwareTokenAssociation,
+            },
+            value_objects::group_mapping::GroupMapping,
         },
+        infrastructure::authentication::unverified_issuer,
+        infrastructure::config::app_config::{CookieConfig, SameSite},
+        infrastructure::memory::InMemoryRevokedTokenRepository,
         presentation::graphql::{
-            schema::{QueryRoot, MutationRoot},
-            mutations::{authentication_mutation::AuthenticationMutation, user_mutation::UserMutation},
-            resolvers::user_resolver::UserResolver,
+            context::UserContext,
+            mutations::{
+                api_token_mutation::ApiTokenMutation,
+                authentication_mutation::AuthenticationMutation,
+                identity_link_mutation::IdentityLinkMutation, role_mutation::RoleMutation,
+                session_mutation::SessionMutation, user_mutation::UserMutation,
+            },
+            resolvers::{
+                api_token_resolver::ApiTokenResolver, identity_link_resolver::IdentityLinkResolver,
+                role_resolver::RoleResolver, session_resolver::SessionResolver,
+                user_resolver::UserResolver,
+            },
+            schema::{MutationRoot, QueryRoot},
         },
     };
-    use anyhow::Result;
-    use async_trait::async_trait;
     use mockall::mock;
+    use std::collections::HashMap;
     use std::sync::Arc;
     use uuid::Uuid;
 
@@ -31,10 +75,24 @@ mod tests {
         #[async_trait]
         impl AuthenticationService for AuthService {
             fn provider_name(&self) -> String;
-            async fn sign_up(&self, email: &str, password: &str) -> Result<SignUpOutput>;
-            async fn sign_in(&self, email: &str, password: &str) -> Result<SignInOutput>;
-            async fn sign_out(&self, access_token: &str) -> Result<()>;
-            async fn verify_token(&self, access_token: &str) -> Result<Claims>;
+            async fn sign_up(&self, email: &str, password: &str) -> AuthenticationResult<SignUpOutput>;
+            async fn confirm_sign_up(&self, email: &str, confirmation_code: &str) -> AuthenticationResult<()>;
+            async fn resend_confirmation_code(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+            async fn sign_in(&self, email: &str, password: &str) -> AuthenticationResult<SignInOutput>;
+            async fn respond_to_auth_challenge(&self, email: &str, challenge_name: &str, session: Option<String>, responses: &HashMap<String, String>) -> AuthenticationResult<SignInOutput>;
+            async fn request_sign_in_code(&self, email: &str) -> AuthenticationResult<SignInCodeChallenge>;
+            async fn verify_sign_in_code(&self, email: &str, session: Option<String>, code: &str) -> AuthenticationResult<SignInCodeVerification>;
+            async fn refresh_tokens(&self, refresh_token: &str) -> AuthenticationResult<AuthenticationTokens>;
+            async fn forgot_password(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+            async fn confirm_forgot_password(&self, email: &str, confirmation_code: &str, new_password: &str) -> AuthenticationResult<()>;
+            async fn change_password(&self, access_token: &str, old_password: &str, new_password: &str) -> AuthenticationResult<()>;
+            async fn associate_software_token(&self, access_token: &str) -> AuthenticationResult<SoftwareTokenAssociation>;
+            async fn verify_software_token(&self, access_token: &str, user_code: &str, friendly_device_name: Option<String>) -> AuthenticationResult<()>;
+            async fn set_mfa_preference(&self, access_token: &str, enabled: bool) -> AuthenticationResult<()>;
+            async fn sign_out(&self, access_token: &str) -> AuthenticationResult<()>;
+            async fn revoke_token(&self, refresh_token: &str) -> AuthenticationResult<()>;
+            async fn delete_user(&self, username: &str) -> AuthenticationResult<()>;
+            async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims>;
         }
     }
 
@@ -43,11 +101,13 @@ mod tests {
 
         #[async_trait]
         impl UserRepository for UserRepo {
-            async fn create(&self, user: NewUser) -> Result<User>;
-            async fn find_by_id(&self, id: Uuid) -> Result<Option<User>>;
-            async fn find_all(&self) -> Result<Vec<User>>;
-            async fn update(&self, user: User) -> Result<User>;
-            async fn delete(&self, id: Uuid) -> Result<()>;
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
         }
     }
 
@@ -56,24 +116,162 @@ mod tests {
 
         #[async_trait]
         impl IdentityLinkRepository for IdentityLinkRepo {
-            async fn create(&self, identity_link: NewIdentityLink) -> Result<IdentityLink>;
-            async fn find_by_sub(&self, sub: &str) -> Result<Option<IdentityLink>>;
-            async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<IdentityLink>>;
-            async fn delete(&self, id: Uuid) -> Result<()>;
+            async fn create(&self, identity_link: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+            async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+            async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+            async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        CompensationRepo {}
+
+        #[async_trait]
+        impl SignUpCompensationRepository for CompensationRepo {
+            async fn create(&self, compensation: NewSignUpCompensation) -> RepositoryResult<SignUpCompensation>;
+            async fn find_unresolved(&self) -> RepositoryResult<Vec<SignUpCompensation>>;
+            async fn mark_resolved(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn record_failure(&self, id: Uuid, error: &str) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        PermissionRepo {}
+
+        #[async_trait]
+        impl PermissionRepository for PermissionRepo {
+            async fn find_permissions_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<String>>;
+            async fn find_all_permissions(&self) -> RepositoryResult<Vec<Permission>>;
+            async fn find_all_roles(&self) -> RepositoryResult<Vec<Role>>;
+            async fn find_roles_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Role>>;
+            async fn create_role(&self, role: NewRole) -> RepositoryResult<Role>;
+            async fn set_role_permissions(&self, role_id: Uuid, permissions: Vec<String>) -> RepositoryResult<Role>;
+            async fn delete_role(&self, role_id: Uuid) -> RepositoryResult<()>;
+            async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+            async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        SessionRepo {}
+
+        #[async_trait]
+        impl SessionRepository for SessionRepo {
+            async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+            async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        SignInCodeRequestRepo {}
+
+        #[async_trait]
+        impl SignInCodeRequestRepository for SignInCodeRequestRepo {
+            async fn create(&self, request: NewSignInCodeRequest) -> RepositoryResult<SignInCodeRequest>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn count_by_email_since(&self, email: &str, since: chrono::DateTime<chrono::Utc>) -> RepositoryResult<u64>;
+            async fn start_attempt(&self, id: Uuid, max_attempts: i32, now: chrono::DateTime<chrono::Utc>) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn update_provider_session(&self, id: Uuid, provider_session: Option<String>) -> RepositoryResult<()>;
+            async fn consume(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn delete_created_before(&self, before: chrono::DateTime<chrono::Utc>) -> RepositoryResult<u64>;
+        }
+    }
+
+    mock! {
+        ApiTokenRepo {}
+
+        #[async_trait]
+        impl ApiTokenRepository for ApiTokenRepo {
+            async fn create(&self, token: NewApiToken) -> RepositoryResult<ApiToken>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<ApiToken>>;
+            async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<ApiToken>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ApiToken>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    // 他の端末から失効させたセッションのリフレッシュトークン
+    const REVOKED_REFRESH_TOKEN: &str = "revoked-refresh-token";
+    // セッションが記録されていないサインインのリフレッシュトークン
+    const UNRECORDED_REFRESH_TOKEN: &str = "unrecorded-refresh-token";
+
+    fn create_test_session(origin_jti: &str, revoked: bool) -> Session {
+        Session {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            origin_jti: origin_jti.to_string(),
+            device: None,
+            ip_address: None,
+            user_agent: None,
+            last_used_at: chrono::Utc::now(),
+            revoked_at: revoked.then(chrono::Utc::now),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn create_test_role(name: &str, permissions: Vec<String>) -> Role {
+        Role {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            permissions,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
         }
     }
 
     fn create_test_user() -> User {
         User {
             id: Uuid::new_v4(),
-            name: Some("Test User".to_string()),
+            name: "Test User".to_string(),
+            role: UserRole::User,
+            mfa_enabled: false,
             identity_links: vec![],
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
     }
 
+    // 更新内容を反映したユーザー
+    fn updated_user(id: Uuid, changes: UserChanges) -> User {
+        let mut user = User {
+            id,
+            ..create_test_user()
+        };
+        if let Some(name) = changes.name {
+            user.name = name;
+        }
+        if let Some(role) = changes.role {
+            user.role = role;
+        }
+        if let Some(mfa_enabled) = changes.mfa_enabled {
+            user.mfa_enabled = mfa_enabled;
+        }
+        user
+    }
+
+    fn create_test_token_denylist() -> Arc<TokenDenylist> {
+        Arc::new(TokenDenylist::new(
+            Arc::new(InMemoryRevokedTokenRepository::new()),
+            chrono::Duration::hours(1),
+        ))
+    }
+
     fn create_test_schema() -> Schema<QueryRoot, MutationRoot, EmptySubscription> {
+        create_test_schema_with_denylist(create_test_token_denylist())
+    }
+
+    // サインアウトで失効したトークンを確認できるよう、失効リストを外から渡す
+    fn create_test_schema_with_denylist(
+        token_denylist: Arc<TokenDenylist>,
+    ) -> Schema<QueryRoot, MutationRoot, EmptySubscription> {
         // Create mock services
         let mut mock_auth_service = MockAuthService::new();
         let mut mock_user_repo = MockUserRepo::new();
@@ -82,87 +280,361 @@ mod tests {
         // Setup default mock behaviors
         mock_auth_service
             .expect_sign_up()
-            .returning(|_, _| {
-                Ok(SignUpOutput {
+            .returning(|email, _| match email {
+                "existing@example.com" => Err(AuthenticationError::UsernameExists(
+                    "User already exists".to_string(),
+                )),
+                _ => Ok(SignUpOutput {
                     user_sub: "test-sub-123".to_string(),
                     user_confirmed: true,
                     session: None,
+                }),
+            });
+
+        mock_auth_service
+            .expect_confirm_sign_up()
+            .returning(|_, _| Ok(()));
+
+        mock_auth_service
+            .expect_resend_confirmation_code()
+            .returning(|_| {
+                Ok(CodeDeliveryDetails {
+                    destination: Some("t***@example.com".to_string()),
+                    delivery_medium: Some("EMAIL".to_string()),
+                    attribute_name: Some("email".to_string()),
                 })
             });
 
         mock_auth_service
             .expect_sign_in()
-            .returning(|_, _| {
-                Ok(SignInOutput {
+            .returning(|email, _| match email {
+                "unconfirmed@example.com" => Err(AuthenticationError::UserNotConfirmed(
+                    "User is not confirmed.".to_string(),
+                )),
+                "unavailable@example.com" => Err(AuthenticationError::Provider(
+                    "dispatch failure: cognito-idp.ap-northeast-1.amazonaws.com".to_string(),
+                )),
+                "mfa@example.com" => Ok(SignInOutput::Challenge(AuthChallenge {
+                    name: "SOFTWARE_TOKEN_MFA".to_string(),
+                    session: Some("test-challenge-session".to_string()),
+                    parameters: HashMap::from([(
+                        "USER_ID_FOR_SRP".to_string(),
+                        "test-sub-123".to_string(),
+                    )]),
+                })),
+                _ => Ok(SignInOutput::Authenticated(AuthenticationTokens {
                     id_token: "test-id-token".to_string(),
                     access_token: "test-access-token".to_string(),
                     refresh_token: "test-refresh-token".to_string(),
                     expires_in: 3600,
+                })),
+            });
+
+        mock_auth_service
+            .expect_respond_to_auth_challenge()
+            .returning(|_, _, session, responses| {
+                match (session.as_deref(), responses.get("SOFTWARE_TOKEN_MFA_CODE")) {
+                    (Some("test-challenge-session"), Some(code)) if code == "123456" => {
+                        Ok(SignInOutput::Authenticated(AuthenticationTokens {
+                            id_token: "mfa-id-token".to_string(),
+                            access_token: "mfa-access-token".to_string(),
+                            refresh_token: "mfa-refresh-token".to_string(),
+                            expires_in: 3600,
+                        }))
+                    }
+                    _ => Err(AuthenticationError::CodeMismatch {
+                        parameter: "responses",
+                        message: "Invalid code received for user".to_string(),
+                    }),
+                }
+            });
+
+        mock_auth_service
+            .expect_refresh_tokens()
+            .returning(|refresh_token| {
+                let access_token = match refresh_token {
+                    REVOKED_REFRESH_TOKEN => "revoked-session-access-token",
+                    UNRECORDED_REFRESH_TOKEN => "unrecorded-session-access-token",
+                    _ => "refreshed-access-token",
+                };
+                Ok(AuthenticationTokens {
+                    id_token: "refreshed-id-token".to_string(),
+                    access_token: access_token.to_string(),
+                    refresh_token: refresh_token.to_string(),
+                    expires_in: 3600,
+                })
+            });
+
+        mock_auth_service
+            .expect_forgot_password()
+            .returning(|email| match email {
+                "unknown@example.com" => Err(AuthenticationError::UserNotFound(
+                    "Username/client id combination not found.".to_string(),
+                )),
+                "throttled@example.com" => Err(AuthenticationError::TooManyRequests(
+                    "Attempt limit exceeded, please try after some time.".to_string(),
+                )),
+                _ => Ok(CodeDeliveryDetails {
+                    destination: Some("t***@example.com".to_string()),
+                    delivery_medium: Some("EMAIL".to_string()),
+                    attribute_name: Some("email".to_string()),
+                }),
+            });
+
+        mock_auth_service
+            .expect_confirm_forgot_password()
+            .returning(|email, _, _| match email {
+                "unknown@example.com" => Err(AuthenticationError::UserNotFound(
+                    "Username/client id combination not found.".to_string(),
+                )),
+                "expired@example.com" => Err(AuthenticationError::ExpiredCode {
+                    parameter: "confirmation_code",
+                    message: "Invalid code provided, please request a code again.".to_string(),
+                }),
+                "weak@example.com" => Err(AuthenticationError::InvalidPassword {
+                    parameter: "new_password",
+                    message: "Password does not conform to policy.".to_string(),
+                }),
+                _ => Err(AuthenticationError::CodeMismatch {
+                    parameter: "confirmation_code",
+                    message: "Invalid verification code provided, please try again.".to_string(),
+                }),
+            });
+
+        mock_auth_service.expect_change_password().returning(
+            |_, old_password, _| match old_password {
+                "wrongPassword123" => Err(AuthenticationError::NotAuthorized(
+                    "Incorrect username or password.".to_string(),
+                )),
+                _ => Ok(()),
+            },
+        );
+
+        mock_auth_service
+            .expect_associate_software_token()
+            .returning(|_| {
+                Ok(SoftwareTokenAssociation {
+                    secret_code: "TESTSECRETCODE".to_string(),
+                    session: None,
                 })
             });
 
+        mock_auth_service.expect_verify_software_token().returning(
+            |_, user_code, _| match user_code {
+                "123456" => Ok(()),
+                _ => Err(AuthenticationError::CodeMismatch {
+                    parameter: "user_code",
+                    message: "Software token verification failed".to_string(),
+                }),
+            },
+        );
+
+        mock_auth_service
+            .expect_set_mfa_preference()
+            .returning(|_, _| Ok(()));
+
+        mock_auth_service.expect_sign_out().returning(|_| Ok(()));
+
+        mock_auth_service
+            .expect_revoke_token()
+            .returning(|_| Ok(()));
+
         mock_auth_service
-            .expect_sign_out()
+            .expect_provider_name()
+            .returning(|| "cognito".to_string());
+
+        // origin_jtiはアクセストークンから決まるものとする
+        mock_auth_service
+            .expect_verify_token()
+            .returning(|access_token| {
+                Ok(Claims {
+                    sub: "test-sub-123".to_string(),
+                    origin_jti: Some(format!("origin-of-{}", access_token)),
+                    ..Default::default()
+                })
+            });
+
+        let mut mock_session_repo = MockSessionRepo::new();
+
+        mock_session_repo.expect_create().returning(|session| {
+            Ok(Session {
+                user_id: session.user_id,
+                device: session.device,
+                ip_address: session.ip_address,
+                user_agent: session.user_agent,
+                ..create_test_session(&session.origin_jti, false)
+            })
+        });
+
+        mock_session_repo
+            .expect_find_by_origin_jti()
+            .returning(|origin_jti| {
+                if origin_jti == "origin-of-unrecorded-session-access-token" {
+                    return Ok(None);
+                }
+                let revoked = origin_jti == "origin-of-revoked-session-access-token";
+                Ok(Some(create_test_session(origin_jti, revoked)))
+            });
+
+        mock_session_repo
+            .expect_find_active_by_user()
+            .returning(|_| Ok(vec![create_test_session("origin-of-other-device", false)]));
+
+        mock_session_repo.expect_touch().returning(|_| Ok(()));
+        mock_session_repo.expect_revoke().returning(|_| Ok(()));
+        mock_session_repo
+            .expect_revoke_all_by_user()
             .returning(|_| Ok(()));
 
         mock_user_repo
             .expect_create()
             .returning(|_| Ok(create_test_user()));
 
+        mock_user_repo
+            .expect_create_with_identity_link()
+            .returning(|_, provider, sub| {
+                let user = create_test_user();
+                let identity_link = IdentityLink {
+                    id: Uuid::new_v4(),
+                    user_id: user.id,
+                    provider,
+                    sub,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                };
+                Ok((user, identity_link))
+            });
+
+        mock_identity_link_repo.expect_create().returning(|_| {
+            Ok(IdentityLink {
+                id: Uuid::new_v4(),
+                user_id: Uuid::new_v4(),
+                provider: "cognito".to_string(),
+                sub: "test-sub-123".to_string(),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            })
+        });
+
         mock_identity_link_repo
-            .expect_create()
-            .returning(|_| {
+            .expect_find_by_sub()
+            .returning(|provider, sub| {
                 Ok(IdentityLink {
                     id: Uuid::new_v4(),
                     user_id: Uuid::new_v4(),
-                    provider: "cognito".to_string(),
-                    sub: "test-sub-123".to_string(),
+                    provider: provider.to_string(),
+                    sub: sub.to_string(),
                     created_at: chrono::Utc::now(),
                     updated_at: chrono::Utc::now(),
                 })
             });
 
+        mock_user_repo
+            .expect_find_by_id()
+            .returning(|_| Ok(Some(create_test_user())));
+
+        mock_user_repo
+            .expect_update()
+            .returning(|id, changes| Ok(updated_user(id, changes)));
+
         mock_user_repo
             .expect_find_all()
             .returning(|| Ok(vec![create_test_user()]));
 
+        let mut mock_permission_repo = MockPermissionRepo::new();
+
+        mock_permission_repo.expect_create_role().returning(|role| {
+            if role
+                .permissions
+                .iter()
+                .any(|permission| permission == "unknown:permission")
+            {
+                return Err(RepositoryError::NotFound("permission".to_string()));
+            }
+            Ok(create_test_role(&role.name, role.permissions))
+        });
+
+        mock_permission_repo.expect_find_all_roles().returning(|| {
+            Ok(vec![create_test_role(
+                "support",
+                vec!["users:read".to_string()],
+            )])
+        });
+
+        mock_permission_repo
+            .expect_assign_role()
+            .returning(|_, _| Ok(()));
+
         // Create use cases
         let auth_service = Arc::new(mock_auth_service);
         let user_repo = Arc::new(mock_user_repo);
         let identity_link_repo = Arc::new(mock_identity_link_repo);
+        let permission_repo = Arc::new(mock_permission_repo);
+        let session_repo = Arc::new(mock_session_repo);
+        let api_token_repo = Arc::new(MockApiTokenRepo::new());
 
-        let sign_up = Arc::new(SignUp::new(
-            auth_service.clone(),
-            user_repo.clone(),
-            identity_link_repo.clone(),
-        ));
-
-        let sign_in = Arc::new(SignIn::new(
-            auth_service.clone(),
-            identity_link_repo.clone(),
-            user_repo.clone(),
-        ));
-
-        let sign_out = Arc::new(SignOut::new(auth_service.clone()));
+        let use_cases = init_use_cases(
+            Arc::new(Repositories {
+                user_repository: user_repo.clone(),
+                identity_link_repository: identity_link_repo.clone(),
+                sign_up_compensation_repository: Arc::new(MockCompensationRepo::new()),
+                permission_repository: permission_repo.clone(),
+                session_repository: session_repo.clone(),
+                revoked_token_repository: Arc::new(InMemoryRevokedTokenRepository::new()),
+                api_token_repository: api_token_repo.clone(),
+                sign_in_code_request_repository: Arc::new(MockSignInCodeRequestRepo::new()),
+            }),
+            Arc::new(AuthenticationServiceRegistry::new(
+                "https://cognito.example.com",
+                auth_service,
+                unverified_issuer,
+            )),
+            None,
+            GroupMapping::default(),
+            token_denylist.clone(),
+        );
 
         // Create services
         let user_service = Arc::new(UserService::new(user_repo.clone()));
+        let user_policy = Arc::new(UserPolicy::new(user_repo.clone()));
+        let api_token_service = Arc::new(ApiTokenService::new(
+            api_token_repo,
+            permission_repo.clone(),
+        ));
+        let role_service = Arc::new(RoleService::new(permission_repo, user_repo.clone()));
+        let session_service = Arc::new(SessionService::new(session_repo, token_denylist));
+        let identity_link_service = Arc::new(IdentityLinkService::new(identity_link_repo));
 
         // Create GraphQL components
-        let user_resolver = UserResolver::new(user_service.clone());
-        let user_mutation = UserMutation::new(user_service.clone());
-        let authentication_mutation = AuthenticationMutation::new(
-            sign_up,
-            sign_in,
-            sign_out,
-        );
+        let user_resolver = UserResolver::new(user_service.clone(), user_policy.clone());
+        let user_mutation = UserMutation::new(user_service.clone(), user_policy);
+        let authentication_mutation = AuthenticationMutation::new(&use_cases);
+        let role_resolver = RoleResolver::new(role_service.clone());
+        let role_mutation = RoleMutation::new(role_service);
+        let session_resolver = SessionResolver::new(session_service.clone());
+        let session_mutation = SessionMutation::new(session_service);
+        let api_token_resolver = ApiTokenResolver::new(api_token_service.clone());
+        let api_token_mutation = ApiTokenMutation::new(api_token_service);
+        let identity_link_resolver = IdentityLinkResolver::new(identity_link_service.clone());
+        let identity_link_mutation =
+            IdentityLinkMutation::new(use_cases.link_identity.clone(), identity_link_service);
 
         // Build schema
         Schema::build(
-            QueryRoot { user_resolver },
+            QueryRoot {
+                user_resolver,
+                role_resolver,
+                session_resolver,
+                api_token_resolver,
+                identity_link_resolver,
+            },
             MutationRoot {
                 user_mutation,
                 authentication_mutation,
+                role_mutation,
+                session_mutation,
+                api_token_mutation,
+                identity_link_mutation,
             },
             EmptySubscription,
         )
@@ -175,12 +647,16 @@ mod tests {
 
         let query = r#"
             mutation SignUp($input: SignUpInput!) {
-                authentication_mutation {
+                authenticationMutation {
                     signUp(input: $input) {
-                        id
-                        userId
-                        provider
-                        sub
+                        identityLink {
+                            id
+                            userId
+                            provider
+                            sub
+                        }
+                        userConfirmed
+                        session
                     }
                 }
             }
@@ -194,12 +670,16 @@ mod tests {
             }
         });
 
-        let request = Request::new(query).variables(variables);
+        let request = Request::new(query).variables(Variables::from_json(variables));
         let response = schema.execute(request).await;
 
         println!("Response: {:?}", response);
-        assert!(response.errors.is_empty(), "GraphQL errors: {:?}", response.errors);
-        assert!(response.data.is_some());
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        assert_ne!(response.data, Value::Null);
     }
 
     #[tokio::test]
@@ -208,12 +688,14 @@ mod tests {
 
         let query = r#"
             mutation SignIn($input: SignInInput!) {
-                authentication_mutation {
+                authenticationMutation {
                     signIn(input: $input) {
-                        idToken
-                        accessToken
-                        refreshToken
-                        expiresIn
+                        ... on TokenSet {
+                            idToken
+                            accessToken
+                            refreshToken
+                            expiresIn
+                        }
                     }
                 }
             }
@@ -226,11 +708,1250 @@ mod tests {
             }
         });
 
-        let request = Request::new(query).variables(variables);
+        let request = Request::new(query).variables(Variables::from_json(variables));
         let response = schema.execute(request).await;
 
         println!("Response: {:?}", response);
-        assert!(response.errors.is_empty(), "GraphQL errors: {:?}", response.errors);
-        assert!(response.data.is_some());
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        assert_ne!(response.data, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_mutation_returns_challenge() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation SignIn($input: SignInInput!) {
+                authenticationMutation {
+                    signIn(input: $input) {
+                        __typename
+                        ... on AuthChallenge {
+                            name
+                            session
+                            parameters {
+                                name
+                                value
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "mfa@example.com",
+                "password": "password123"
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        let data = response.data.into_json().unwrap();
+        let challenge = &data["authenticationMutation"]["signIn"];
+        assert_eq!(challenge["__typename"], "AuthChallenge");
+        assert_eq!(challenge["name"], "SOFTWARE_TOKEN_MFA");
+        assert_eq!(challenge["session"], "test-challenge-session");
+        assert_eq!(challenge["parameters"][0]["name"], "USER_ID_FOR_SRP");
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_auth_challenge_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation RespondToAuthChallenge($input: RespondToAuthChallengeInput!) {
+                authenticationMutation {
+                    respondToAuthChallenge(input: $input) {
+                        __typename
+                        ... on TokenSet {
+                            accessToken
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "mfa@example.com",
+                "challengeName": "SOFTWARE_TOKEN_MFA",
+                "session": "test-challenge-session",
+                "responses": [
+                    { "name": "SOFTWARE_TOKEN_MFA_CODE", "value": "123456" }
+                ]
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        let data = response.data.into_json().unwrap();
+        let result = &data["authenticationMutation"]["respondToAuthChallenge"];
+        assert_eq!(result["__typename"], "TokenSet");
+        assert_eq!(result["accessToken"], "mfa-access-token");
+    }
+
+    #[tokio::test]
+    async fn test_respond_to_auth_challenge_mutation_rejects_wrong_code() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation RespondToAuthChallenge($input: RespondToAuthChallengeInput!) {
+                authenticationMutation {
+                    respondToAuthChallenge(input: $input) {
+                        __typename
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "mfa@example.com",
+                "challengeName": "SOFTWARE_TOKEN_MFA",
+                "session": "test-challenge-session",
+                "responses": [
+                    { "name": "SOFTWARE_TOKEN_MFA_CODE", "value": "000000" }
+                ]
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("CODE_MISMATCH"))
+        );
+        assert_eq!(
+            error_field(&response.errors[0]),
+            Some(Value::from("responses"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_mutation_reports_existing_email() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation SignUp($input: SignUpInput!) {
+                authenticationMutation {
+                    signUp(input: $input) {
+                        userConfirmed
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "name": "Existing User",
+                "email": "existing@example.com",
+                "password": "password123"
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "User already exists");
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("USERNAME_EXISTS"))
+        );
+        assert_eq!(error_field(&response.errors[0]), Some(Value::from("email")));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_mutation_reports_unconfirmed_user() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation SignIn($input: SignInInput!) {
+                authenticationMutation {
+                    signIn(input: $input) {
+                        __typename
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "unconfirmed@example.com",
+                "password": "password123"
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("USER_NOT_CONFIRMED"))
+        );
+        assert_eq!(error_field(&response.errors[0]), Some(Value::from("email")));
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_mutation_hides_internal_errors() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation SignIn($input: SignInInput!) {
+                authenticationMutation {
+                    signIn(input: $input) {
+                        __typename
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "unavailable@example.com",
+                "password": "password123"
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "Internal server error");
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("INTERNAL"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_sign_up_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation ConfirmSignUp($input: ConfirmSignUpInput!) {
+                authenticationMutation {
+                    confirmSignUp(input: $input) {
+                        success
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "test@example.com",
+                "confirmationCode": "123456"
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["confirmSignUp"]["success"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resend_confirmation_code_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation ResendConfirmationCode($input: ResendConfirmationCodeInput!) {
+                authenticationMutation {
+                    resendConfirmationCode(input: $input) {
+                        destination
+                        deliveryMedium
+                        attributeName
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "email": "test@example.com"
+            }
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["resendConfirmationCode"]["deliveryMedium"],
+            "EMAIL"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation RefreshTokens($refreshToken: String!) {
+                authenticationMutation {
+                    refreshTokens(refreshToken: $refreshToken) {
+                        idToken
+                        accessToken
+                        refreshToken
+                        expiresIn
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "refreshToken": "test-refresh-token"
+        });
+
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert!(
+            response.errors.is_empty(),
+            "GraphQL errors: {:?}",
+            response.errors
+        );
+        let data = response.data.into_json().unwrap();
+        let token_set = &data["authenticationMutation"]["refreshTokens"];
+        assert_eq!(token_set["accessToken"], "refreshed-access-token");
+        assert_eq!(token_set["refreshToken"], "test-refresh-token");
+    }
+
+    #[tokio::test]
+    async fn test_forgot_password_mutation_is_uniform() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation ForgotPassword($input: ForgotPasswordInput!) {
+                authenticationMutation {
+                    forgotPassword(input: $input) {
+                        success
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let mut responses = Vec::new();
+        for email in ["test@example.com", "unknown@example.com"] {
+            let variables = serde_json::json!({ "input": { "email": email } });
+            let request = Request::new(query).variables(Variables::from_json(variables));
+            let response = schema.execute(request).await;
+
+            assert!(
+                response.errors.is_empty(),
+                "GraphQL errors: {:?}",
+                response.errors
+            );
+            responses.push(response.data.into_json().unwrap());
+        }
+
+        assert_eq!(responses[0], responses[1]);
+        assert_eq!(
+            responses[0]["authenticationMutation"]["forgotPassword"]["success"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forgot_password_mutation_reports_rate_limit() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation ForgotPassword($input: ForgotPasswordInput!) {
+                authenticationMutation {
+                    forgotPassword(input: $input) {
+                        success
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({ "input": { "email": "throttled@example.com" } });
+        let request = Request::new(query).variables(Variables::from_json(variables));
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("TOO_MANY_REQUESTS"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_forgot_password_mutation_is_uniform() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation ConfirmForgotPassword($input: ConfirmForgotPasswordInput!) {
+                authenticationMutation {
+                    confirmForgotPassword(input: $input) {
+                        success
+                    }
+                }
+            }
+        "#;
+
+        let mut messages = Vec::new();
+        for email in [
+            "test@example.com",
+            "unknown@example.com",
+            "expired@example.com",
+            "weak@example.com",
+        ] {
+            let variables = serde_json::json!({
+                "input": {
+                    "email": email,
+                    "confirmationCode": "123456",
+                    "newPassword": "newPassword123"
+                }
+            });
+            let request = Request::new(query).variables(Variables::from_json(variables));
+            let response = schema.execute(request).await;
+
+            assert_eq!(response.errors.len(), 1);
+            assert_eq!(
+                error_code(&response.errors[0]),
+                Some(Value::from("CODE_MISMATCH"))
+            );
+            assert_eq!(
+                error_field(&response.errors[0]),
+                Some(Value::from("confirmationCode"))
+            );
+            messages.push(response.errors[0].message.clone());
+        }
+
+        assert!(messages.iter().all(|message| *message == messages[0]));
+    }
+
+    fn error_code(error: &ServerError) -> Option<Value> {
+        error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code"))
+            .cloned()
+    }
+
+    fn error_field(error: &ServerError) -> Option<Value> {
+        error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("field"))
+            .cloned()
+    }
+
+    fn authenticated_context() -> UserContext {
+        UserContext {
+            user: Some(UserDTO::from(create_test_user())),
+            access_token: Some("test-access-token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn admin_context() -> UserContext {
+        let mut user = UserDTO::from(create_test_user());
+        user.role = UserRole::Admin;
+        UserContext {
+            user: Some(user),
+            access_token: Some("test-access-token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    const CHANGE_PASSWORD_MUTATION: &str = r#"
+        mutation ChangePassword($input: ChangePasswordInput!) {
+            authenticationMutation {
+                changePassword(input: $input) {
+                    success
+                    message
+                }
+            }
+        }
+    "#;
+
+    #[tokio::test]
+    async fn test_change_password_mutation() {
+        let schema = create_test_schema();
+
+        let variables = serde_json::json!({
+            "input": {
+                "oldPassword": "password123",
+                "newPassword": "newPassword123"
+            }
+        });
+
+        let request = Request::new(CHANGE_PASSWORD_MUTATION)
+            .variables(Variables::from_json(variables))
+            .data(authenticated_context());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["changePassword"]["success"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_password_mutation_requires_authentication() {
+        let schema = create_test_schema();
+
+        let variables = serde_json::json!({
+            "input": {
+                "oldPassword": "password123",
+                "newPassword": "newPassword123"
+            }
+        });
+
+        let request = Request::new(CHANGE_PASSWORD_MUTATION)
+            .variables(Variables::from_json(variables))
+            .data(UserContext::default());
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "Authentication required");
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("UNAUTHENTICATED"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_password_mutation_rejects_wrong_old_password() {
+        let schema = create_test_schema();
+
+        let variables = serde_json::json!({
+            "input": {
+                "oldPassword": "wrongPassword123",
+                "newPassword": "newPassword123"
+            }
+        });
+
+        let request = Request::new(CHANGE_PASSWORD_MUTATION)
+            .variables(Variables::from_json(variables))
+            .data(authenticated_context());
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            response.errors[0].message,
+            "Incorrect username or password."
+        );
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("NOT_AUTHORIZED"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_password_mutation_validates_new_password() {
+        let schema = create_test_schema();
+
+        let variables = serde_json::json!({
+            "input": {
+                "oldPassword": "password123",
+                "newPassword": "short"
+            }
+        });
+
+        let request = Request::new(CHANGE_PASSWORD_MUTATION)
+            .variables(Variables::from_json(variables))
+            .data(authenticated_context());
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("at least 8 characters"));
+    }
+
+    #[tokio::test]
+    async fn test_associate_software_token_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation {
+                authenticationMutation {
+                    associateSoftwareToken {
+                        secretCode
+                    }
+                }
+            }
+        "#;
+
+        let request = Request::new(query).data(authenticated_context());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["associateSoftwareToken"]["secretCode"],
+            "TESTSECRETCODE"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_software_token_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation VerifySoftwareToken($input: VerifySoftwareTokenInput!) {
+                authenticationMutation {
+                    verifySoftwareToken(input: $input) {
+                        success
+                    }
+                }
+            }
+        "#;
+
+        let variables = serde_json::json!({
+            "input": {
+                "userCode": "123456",
+                "friendlyDeviceName": "Authenticator"
+            }
+        });
+
+        let request = Request::new(query)
+            .variables(Variables::from_json(variables))
+            .data(authenticated_context());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["verifySoftwareToken"]["success"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_mfa_preference_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation {
+                authenticationMutation {
+                    setMfaPreference(enabled: true) {
+                        mfaEnabled
+                    }
+                }
+            }
+        "#;
+
+        let request = Request::new(query).data(authenticated_context());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["setMfaPreference"]["mfaEnabled"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mfa_mutations_require_authentication() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation {
+                authenticationMutation {
+                    setMfaPreference(enabled: true) {
+                        mfaEnabled
+                    }
+                }
+            }
+        "#;
+
+        let request = Request::new(query).data(UserContext::default());
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "Authentication required");
+    }
+
+    const SET_USER_ROLE_MUTATION: &str = r#"
+        mutation SetUserRole($id: UUID!, $role: UserRole!) {
+            users {
+                setUserRole(id: $id, role: $role) {
+                    id
+                    role
+                }
+            }
+        }
+    "#;
+
+    fn set_user_role_request(id: Uuid, context: UserContext) -> Request {
+        let variables = serde_json::json!({
+            "id": id.to_string(),
+            "role": "ADMIN"
+        });
+
+        Request::new(SET_USER_ROLE_MUTATION)
+            .variables(Variables::from_json(variables))
+            .data(context)
+    }
+
+    #[tokio::test]
+    async fn test_set_user_role_mutation() {
+        let schema = create_test_schema();
+
+        let response = schema
+            .execute(set_user_role_request(Uuid::new_v4(), admin_context()))
+            .await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["users"]["setUserRole"]["role"], "ADMIN");
+    }
+
+    #[tokio::test]
+    async fn test_set_user_role_mutation_requires_admin() {
+        let schema = create_test_schema();
+
+        let response = schema
+            .execute(set_user_role_request(
+                Uuid::new_v4(),
+                authenticated_context(),
+            ))
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("FORBIDDEN"))
+        );
+
+        let response = schema
+            .execute(set_user_role_request(
+                Uuid::new_v4(),
+                UserContext::default(),
+            ))
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("UNAUTHENTICATED"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_user_role_mutation_rejects_own_role() {
+        let schema = create_test_schema();
+        let context = admin_context();
+        let own_id = context.user.as_ref().unwrap().id;
+
+        let response = schema.execute(set_user_role_request(own_id, context)).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("FORBIDDEN"))
+        );
+    }
+
+    const CREATE_ROLE_MUTATION: &str = r#"
+        mutation CreateRole($input: CreateRoleInput!) {
+            roles {
+                createRole(input: $input) {
+                    name
+                    permissions
+                }
+            }
+        }
+    "#;
+
+    fn create_role_request(permissions: Vec<&str>, context: UserContext) -> Request {
+        let variables = serde_json::json!({
+            "input": {
+                "name": "support",
+                "description": "Customer support staff",
+                "permissions": permissions
+            }
+        });
+
+        Request::new(CREATE_ROLE_MUTATION)
+            .variables(Variables::from_json(variables))
+            .data(context)
+    }
+
+    #[tokio::test]
+    async fn test_create_role_mutation() {
+        let schema = create_test_schema();
+
+        let response = schema
+            .execute(create_role_request(vec!["users:read"], admin_context()))
+            .await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["roles"]["createRole"]["name"], "support");
+        assert_eq!(
+            data["roles"]["createRole"]["permissions"],
+            serde_json::json!(["users:read"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_role_mutation_rejects_unknown_permission() {
+        let schema = create_test_schema();
+
+        let response = schema
+            .execute(create_role_request(
+                vec!["unknown:permission"],
+                admin_context(),
+            ))
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("NOT_FOUND"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_role_management_requires_roles_manage_permission() {
+        let schema = create_test_schema();
+
+        let response = schema
+            .execute(create_role_request(
+                vec!["users:read"],
+                authenticated_context(),
+            ))
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("FORBIDDEN"))
+        );
+
+        // 管理者でなくても、roles:manage権限があればロールを参照できる
+        let mut context = authenticated_context();
+        context.permissions.insert("roles:manage".to_string());
+        let request = Request::new("query { roles { roles { name permissions } } }").data(context);
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["roles"]["roles"][0]["name"], "support");
+    }
+
+    #[tokio::test]
+    async fn test_assign_role_mutation() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation AssignRole($userId: UUID!, $roleId: UUID!) {
+                roles {
+                    assignRole(userId: $userId, roleId: $roleId)
+                }
+            }
+        "#;
+        let variables = serde_json::json!({
+            "userId": Uuid::new_v4().to_string(),
+            "roleId": Uuid::new_v4().to_string()
+        });
+
+        let request = Request::new(query)
+            .variables(Variables::from_json(variables))
+            .data(admin_context());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["roles"]["assignRole"], true);
+    }
+
+    fn cookie_config() -> CookieConfig {
+        CookieConfig {
+            domain: None,
+            secure: true,
+            same_site: SameSite::Strict,
+            refresh_token_max_age: 3600,
+        }
+    }
+
+    fn set_cookies(response: &async_graphql::Response) -> Vec<String> {
+        response
+            .http_headers
+            .get_all("set-cookie")
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_mutation_sets_cookies_in_cookie_mode() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation SignIn($input: SignInInput!) {
+                authenticationMutation {
+                    signIn(input: $input) {
+                        ... on TokenSet {
+                            idToken
+                            accessToken
+                            refreshToken
+                        }
+                    }
+                }
+            }
+        "#;
+        let variables = serde_json::json!({
+            "input": {
+                "email": "test@example.com",
+                "password": "password123"
+            }
+        });
+
+        let request = Request::new(query)
+            .variables(Variables::from_json(variables))
+            .data(cookie_config());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let cookies = set_cookies(&response);
+        assert_eq!(cookies.len(), 3);
+        assert!(cookies[0].starts_with("access_token="));
+        assert!(cookies[0].contains("HttpOnly"));
+        assert!(cookies[1].starts_with("refresh_token="));
+        assert!(cookies[2].starts_with("csrf_token="));
+
+        // トークンはHttpOnly Cookieにだけ入れ、レスポンス本文からは取り除く
+        let data = response.data.into_json().unwrap();
+        let token_set = &data["authenticationMutation"]["signIn"];
+        assert_eq!(token_set["accessToken"], "");
+        assert_eq!(token_set["refreshToken"], "");
+        assert_ne!(token_set["idToken"], "");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_mutation_reads_refresh_token_cookie() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation {
+                authenticationMutation {
+                    refreshTokens {
+                        idToken
+                        accessToken
+                        refreshToken
+                    }
+                }
+            }
+        "#;
+        let context = UserContext {
+            refresh_token: Some("cookie-refresh-token".to_string()),
+            ..Default::default()
+        };
+
+        let request = Request::new(query).data(context).data(cookie_config());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let cookies = set_cookies(&response);
+        assert_eq!(cookies.len(), 3);
+        assert!(cookies[0].starts_with("access_token=refreshed-access-token"));
+        assert!(cookies[1].starts_with("refresh_token=cookie-refresh-token"));
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["refreshTokens"]["refreshToken"],
+            ""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_mutation_without_refresh_token() {
+        let schema = create_test_schema();
+
+        let request =
+            Request::new("mutation { authenticationMutation { refreshTokens { idToken } } }")
+                .data(UserContext::default());
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("VALIDATION_FAILED"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_mutation_clears_cookies_in_cookie_mode() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation {
+                authenticationMutation {
+                    signOut {
+                        success
+                    }
+                }
+            }
+        "#;
+
+        let request = Request::new(query)
+            .data(UserContext::default())
+            .data(cookie_config());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let cookies = set_cookies(&response);
+        assert_eq!(cookies.len(), 3);
+        assert!(cookies.iter().all(|cookie| cookie.contains("Max-Age=0")));
+    }
+
+    #[tokio::test]
+    async fn test_cookie_authenticated_mutation_requires_csrf_token() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation ChangePassword($input: ChangePasswordInput!) {
+                authenticationMutation {
+                    changePassword(input: $input) {
+                        success
+                    }
+                }
+            }
+        "#;
+        let variables = serde_json::json!({
+            "input": {
+                "oldPassword": "oldpassword123",
+                "newPassword": "newpassword123"
+            }
+        });
+        let mut context = authenticated_context();
+        context.csrf_failed = true;
+
+        let request = Request::new(query)
+            .variables(Variables::from_json(variables))
+            .data(context);
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1, "Errors: {:?}", response.errors);
+        let extensions = response.errors[0].extensions.as_ref().unwrap();
+        assert_eq!(extensions.get("code"), Some(&Value::from("FORBIDDEN")));
+        assert_eq!(
+            extensions.get("reason"),
+            Some(&Value::from("csrf_token_mismatch"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_mutation_records_session() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation SignIn($input: SignInInput!) {
+                authenticationMutation {
+                    signIn(input: $input) {
+                        ... on TokenSet {
+                            accessToken
+                        }
+                    }
+                }
+            }
+        "#;
+        let variables = serde_json::json!({
+            "input": {
+                "email": "test@example.com",
+                "password": "password123",
+                "deviceName": "Work laptop"
+            }
+        });
+
+        let request = Request::new(query)
+            .variables(Variables::from_json(variables))
+            .data(ClientInfoDTO {
+                device: None,
+                ip_address: Some("203.0.113.7".to_string()),
+                user_agent: Some("Mozilla/5.0".to_string()),
+            });
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+    }
+
+    fn context_with_session() -> UserContext {
+        UserContext {
+            claims: Some(Claims {
+                sub: "test-sub-123".to_string(),
+                origin_jti: Some("origin-of-test-access-token".to_string()),
+                provider: "cognito".to_string(),
+                ..Default::default()
+            }),
+            ..authenticated_context()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_mutation_revokes_current_session() {
+        let token_denylist = create_test_token_denylist();
+        let schema = create_test_schema_with_denylist(token_denylist.clone());
+
+        let query = r#"
+            mutation {
+                authenticationMutation {
+                    signOut(refreshToken: "test-refresh-token") {
+                        success
+                        message
+                    }
+                }
+            }
+        "#;
+
+        // IDリンクを持たないユーザーでもサインアウトできる
+        let context = context_with_session();
+        assert!(context.user.as_ref().unwrap().identity_links.is_empty());
+        let request = Request::new(query).data(context);
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["authenticationMutation"]["signOut"]["success"], true);
+
+        // 同じサインインから発行されたアクセストークンは期限内でも拒否される
+        let refreshed = Claims {
+            origin_jti: Some("origin-of-test-access-token".to_string()),
+            ..Default::default()
+        };
+        assert!(token_denylist.is_revoked(&refreshed).await.unwrap());
+    }
+
+    const GLOBAL_SIGN_OUT_MUTATION: &str = r#"
+        mutation {
+            authenticationMutation {
+                globalSignOut {
+                    success
+                }
+            }
+        }
+    "#;
+
+    #[tokio::test]
+    async fn test_global_sign_out_mutation() {
+        let token_denylist = create_test_token_denylist();
+        let schema = create_test_schema_with_denylist(token_denylist.clone());
+
+        let request = Request::new(GLOBAL_SIGN_OUT_MUTATION).data(context_with_session());
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["authenticationMutation"]["globalSignOut"]["success"],
+            true
+        );
+
+        // 他の端末のセッションから発行されたアクセストークンも拒否される
+        let other_device = Claims {
+            origin_jti: Some("origin-of-other-device".to_string()),
+            ..Default::default()
+        };
+        assert!(token_denylist.is_revoked(&other_device).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_global_sign_out_mutation_rejects_api_token() {
+        let schema = create_test_schema();
+
+        // パーソナルアクセストークンのクレームにはプロバイダーが無い
+        let context = UserContext {
+            claims: Some(Claims {
+                sub: "test-sub-123".to_string(),
+                ..Default::default()
+            }),
+            api_token_id: Some(Uuid::new_v4()),
+            ..authenticated_context()
+        };
+        let request = Request::new(GLOBAL_SIGN_OUT_MUTATION).data(context);
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1, "Errors: {:?}", response.errors);
+        assert_eq!(
+            error_code(&response.errors[0]),
+            Some(Value::from("FORBIDDEN"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_sign_out_mutation_requires_authentication() {
+        let schema = create_test_schema();
+
+        let request = Request::new(GLOBAL_SIGN_OUT_MUTATION).data(UserContext::default());
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1, "Errors: {:?}", response.errors);
+        let extensions = response.errors[0].extensions.as_ref().unwrap();
+        assert_eq!(
+            extensions.get("code"),
+            Some(&Value::from("UNAUTHENTICATED"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_mutation_rejects_revoked_session() {
+        let schema = create_test_schema();
+
+        let query = r#"
+            mutation RefreshTokens($refreshToken: String!) {
+                authenticationMutation {
+                    refreshTokens(refreshToken: $refreshToken) {
+                        accessToken
+                    }
+                }
+            }
+        "#;
+        // 記録の無いセッションは失効させたかどうか確認できないため、同じく拒否する
+        for refresh_token in [REVOKED_REFRESH_TOKEN, UNRECORDED_REFRESH_TOKEN] {
+            let variables = serde_json::json!({
+                "refreshToken": refresh_token
+            });
+
+            let request = Request::new(query).variables(Variables::from_json(variables));
+            let response = schema.execute(request).await;
+
+            assert_eq!(response.errors.len(), 1, "Errors: {:?}", response.errors);
+            let extensions = response.errors[0].extensions.as_ref().unwrap();
+            assert_eq!(extensions.get("code"), Some(&Value::from("NOT_AUTHORIZED")));
+        }
     }
 }
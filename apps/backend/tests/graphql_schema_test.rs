@@ -1,6 +1,73 @@
 #[cfg(test)]
 mod tests {
-    use async_graphql::Request;
+    use async_graphql::{EmptySubscription, Request, Response, Schema, Value, Variables};
+    use async_trait::async_trait;
+    use backend::{
+        application::{
+            dtos::user_dto::UserDTO,
+            policies::UserPolicy,
+            services::{
+                ApiTokenService, IdentityLinkService, Repositories, RoleService, SessionService,
+                TokenDenylist, UserService,
+            },
+            usecases::init_use_cases,
+        },
+        domain::{
+            entities::{
+                api_token::{ApiToken, NewApiToken},
+                identity_link::{IdentityLink, NewIdentityLink},
+                permission::Permission,
+                role::{NewRole, Role},
+                session::{NewSession, Session},
+                sign_in_code_request::{NewSignInCodeRequest, SignInCodeRequest},
+                sign_up_compensation::{NewSignUpCompensation, SignUpCompensation},
+                user::{NewUser, User, UserChanges},
+            },
+            enums::user_role::UserRole,
+            errors::{AuthenticationResult, RepositoryError, RepositoryResult},
+            repositories::{
+                api_token_repository::ApiTokenRepository,
+                identity_link_repository::IdentityLinkRepository,
+                permission_repository::PermissionRepository, session_repository::SessionRepository,
+                sign_in_code_request_repository::SignInCodeRequestRepository,
+                sign_up_compensation_repository::SignUpCompensationRepository,
+                user_repository::UserRepository,
+            },
+            services::{
+                authentication_service::AuthenticationService,
+                authentication_service_registry::AuthenticationServiceRegistry,
+            },
+            value_objects::{
+                authentication::{
+                    AuthenticationTokens, Claims, CodeDeliveryDetails, SignInCodeChallenge,
+                    SignInCodeVerification, SignInOutput, SignUpOutput, SoftwareTokenAssociation,
+                },
+                group_mapping::GroupMapping,
+            },
+        },
+        infrastructure::{
+            authentication::unverified_issuer, memory::InMemoryRevokedTokenRepository,
+        },
+        presentation::graphql::{
+            context::{AuthenticationFailure, UserContext},
+            mutations::{
+                api_token_mutation::ApiTokenMutation,
+                authentication_mutation::AuthenticationMutation,
+                identity_link_mutation::IdentityLinkMutation, role_mutation::RoleMutation,
+                session_mutation::SessionMutation, user_mutation::UserMutation,
+            },
+            resolvers::{
+                api_token_resolver::ApiTokenResolver, identity_link_resolver::IdentityLinkResolver,
+                role_resolver::RoleResolver, session_resolver::SessionResolver,
+                user_resolver::UserResolver,
+            },
+            schema::{MutationRoot, QueryRoot},
+        },
+    };
+    use mockall::mock;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_graphql_schema_creation() {
@@ -21,7 +88,7 @@ mod tests {
 
         // Verify the query parses correctly
         let request = Request::new(query);
-        assert!(request.query.len() > 0);
+        assert!(!request.query.is_empty());
     }
 
     #[tokio::test]
@@ -105,10 +172,13 @@ mod tests {
                         email: "test@example.com"
                         password: "password123"
                     }) {
-                        id
-                        provider
-                        sub
-                        userId
+                        identityLink {
+                            id
+                            provider
+                            sub
+                            userId
+                        }
+                        userConfirmed
                     }
                 }
             }
@@ -160,10 +230,12 @@ mod tests {
                         email: "user@example.com"
                         password: "password123"
                     }) {
-                        idToken
-                        accessToken
-                        refreshToken
-                        expiresIn
+                        ... on TokenSet {
+                            idToken
+                            accessToken
+                            refreshToken
+                            expiresIn
+                        }
                     }
                 }
             }
@@ -175,4 +247,1204 @@ mod tests {
         assert!(request.query.contains("accessToken"));
         assert!(request.query.contains("refreshToken"));
     }
+
+    // 認可ルールの検証用のモック（認証サービスは呼ばれない）
+    mock! {
+        AuthService {}
+
+        #[async_trait]
+        impl AuthenticationService for AuthService {
+            fn provider_name(&self) -> String;
+            async fn sign_up(&self, email: &str, password: &str) -> AuthenticationResult<SignUpOutput>;
+            async fn confirm_sign_up(&self, email: &str, confirmation_code: &str) -> AuthenticationResult<()>;
+            async fn resend_confirmation_code(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+            async fn sign_in(&self, email: &str, password: &str) -> AuthenticationResult<SignInOutput>;
+            async fn respond_to_auth_challenge(&self, email: &str, challenge_name: &str, session: Option<String>, responses: &HashMap<String, String>) -> AuthenticationResult<SignInOutput>;
+            async fn request_sign_in_code(&self, email: &str) -> AuthenticationResult<SignInCodeChallenge>;
+            async fn verify_sign_in_code(&self, email: &str, session: Option<String>, code: &str) -> AuthenticationResult<SignInCodeVerification>;
+            async fn refresh_tokens(&self, refresh_token: &str) -> AuthenticationResult<AuthenticationTokens>;
+            async fn forgot_password(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+            async fn confirm_forgot_password(&self, email: &str, confirmation_code: &str, new_password: &str) -> AuthenticationResult<()>;
+            async fn change_password(&self, access_token: &str, old_password: &str, new_password: &str) -> AuthenticationResult<()>;
+            async fn associate_software_token(&self, access_token: &str) -> AuthenticationResult<SoftwareTokenAssociation>;
+            async fn verify_software_token(&self, access_token: &str, user_code: &str, friendly_device_name: Option<String>) -> AuthenticationResult<()>;
+            async fn set_mfa_preference(&self, access_token: &str, enabled: bool) -> AuthenticationResult<()>;
+            async fn sign_out(&self, access_token: &str) -> AuthenticationResult<()>;
+            async fn revoke_token(&self, refresh_token: &str) -> AuthenticationResult<()>;
+            async fn delete_user(&self, username: &str) -> AuthenticationResult<()>;
+            async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims>;
+        }
+    }
+
+    mock! {
+        UserRepo {}
+
+        #[async_trait]
+        impl UserRepository for UserRepo {
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
+        }
+    }
+
+    mock! {
+        IdentityLinkRepo {}
+
+        #[async_trait]
+        impl IdentityLinkRepository for IdentityLinkRepo {
+            async fn create(&self, identity_link: NewIdentityLink) -> RepositoryResult<IdentityLink>;
+            async fn find_by_sub(&self, provider: &str, sub: &str) -> RepositoryResult<IdentityLink>;
+            async fn find_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<IdentityLink>>;
+            async fn delete_unless_last(&self, user_id: Uuid, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        CompensationRepo {}
+
+        #[async_trait]
+        impl SignUpCompensationRepository for CompensationRepo {
+            async fn create(&self, compensation: NewSignUpCompensation) -> RepositoryResult<SignUpCompensation>;
+            async fn find_unresolved(&self) -> RepositoryResult<Vec<SignUpCompensation>>;
+            async fn mark_resolved(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn record_failure(&self, id: Uuid, error: &str) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        SignInCodeRequestRepo {}
+
+        #[async_trait]
+        impl SignInCodeRequestRepository for SignInCodeRequestRepo {
+            async fn create(&self, request: NewSignInCodeRequest) -> RepositoryResult<SignInCodeRequest>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn count_by_email_since(&self, email: &str, since: chrono::DateTime<chrono::Utc>) -> RepositoryResult<u64>;
+            async fn start_attempt(&self, id: Uuid, max_attempts: i32, now: chrono::DateTime<chrono::Utc>) -> RepositoryResult<Option<SignInCodeRequest>>;
+            async fn update_provider_session(&self, id: Uuid, provider_session: Option<String>) -> RepositoryResult<()>;
+            async fn consume(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn delete_created_before(&self, before: chrono::DateTime<chrono::Utc>) -> RepositoryResult<u64>;
+        }
+    }
+
+    mock! {
+        PermissionRepo {}
+
+        #[async_trait]
+        impl PermissionRepository for PermissionRepo {
+            async fn find_permissions_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<String>>;
+            async fn find_all_permissions(&self) -> RepositoryResult<Vec<Permission>>;
+            async fn find_all_roles(&self) -> RepositoryResult<Vec<Role>>;
+            async fn find_roles_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Role>>;
+            async fn create_role(&self, role: NewRole) -> RepositoryResult<Role>;
+            async fn set_role_permissions(&self, role_id: Uuid, permissions: Vec<String>) -> RepositoryResult<Role>;
+            async fn delete_role(&self, role_id: Uuid) -> RepositoryResult<()>;
+            async fn assign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+            async fn unassign_role(&self, user_id: Uuid, role_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        SessionRepo {}
+
+        #[async_trait]
+        impl SessionRepository for SessionRepo {
+            async fn create(&self, session: NewSession) -> RepositoryResult<Session>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<Session>>;
+            async fn find_by_origin_jti(&self, origin_jti: &str) -> RepositoryResult<Option<Session>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke_all_by_user(&self, user_id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    mock! {
+        ApiTokenRepo {}
+
+        #[async_trait]
+        impl ApiTokenRepository for ApiTokenRepo {
+            async fn create(&self, token: NewApiToken) -> RepositoryResult<ApiToken>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<ApiToken>>;
+            async fn find_by_hash(&self, token_hash: &str) -> RepositoryResult<Option<ApiToken>>;
+            async fn find_active_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<ApiToken>>;
+            async fn touch(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn revoke(&self, id: Uuid) -> RepositoryResult<()>;
+        }
+    }
+
+    fn test_api_token(id: Uuid, user_id: Uuid, name: &str, scopes: Vec<String>) -> ApiToken {
+        ApiToken {
+            id,
+            user_id,
+            name: name.to_string(),
+            token_prefix: "morrow_pat_1a2b3c4d".to_string(),
+            token_hash: "hash".to_string(),
+            scopes,
+            expires_at: None,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    // find_by_idが返すセッションを所有するユーザー
+    const SESSION_OWNER_ID: Uuid = Uuid::from_u128(2);
+
+    fn test_session(id: Uuid, user_id: Uuid, origin_jti: &str) -> Session {
+        Session {
+            id,
+            user_id,
+            origin_jti: origin_jti.to_string(),
+            device: Some("Work laptop".to_string()),
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: Some("Mozilla/5.0".to_string()),
+            last_used_at: chrono::Utc::now(),
+            revoked_at: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    // SESSION_OWNER_IDのユーザーには2つ、他のユーザーには1つだけアカウントが紐付いている
+    const PRIMARY_IDENTITY_ID: Uuid = Uuid::from_u128(3);
+    const SECONDARY_IDENTITY_ID: Uuid = Uuid::from_u128(4);
+
+    fn test_identity_link(id: Uuid, user_id: Uuid, provider: &str) -> IdentityLink {
+        IdentityLink {
+            id,
+            provider: provider.to_string(),
+            sub: format!("{}-sub", provider),
+            user_id,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    // find_allが返すユーザーのうち、IDが固定されたユーザー
+    const LISTED_USER_ID: Uuid = Uuid::from_u128(1);
+
+    fn test_user(id: Uuid, role: UserRole) -> User {
+        User {
+            id,
+            name: "Test User".to_string(),
+            role,
+            mfa_enabled: false,
+            identity_links: vec![],
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    // 更新内容を反映したユーザー
+    fn updated_user(id: Uuid, changes: UserChanges) -> User {
+        let mut user = test_user(id, changes.role.unwrap_or(UserRole::User));
+        if let Some(name) = changes.name {
+            user.name = name;
+        }
+        if let Some(mfa_enabled) = changes.mfa_enabled {
+            user.mfa_enabled = mfa_enabled;
+        }
+        user
+    }
+
+    fn create_test_schema() -> Schema<QueryRoot, MutationRoot, EmptySubscription> {
+        let mut mock_user_repo = MockUserRepo::new();
+        mock_user_repo
+            .expect_create()
+            .returning(|user| Ok(test_user(Uuid::new_v4(), user.role)));
+        mock_user_repo
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(test_user(id, UserRole::User))));
+        mock_user_repo.expect_find_all().returning(|| {
+            Ok(vec![
+                test_user(LISTED_USER_ID, UserRole::User),
+                test_user(Uuid::new_v4(), UserRole::User),
+            ])
+        });
+        mock_user_repo
+            .expect_update()
+            .returning(|id, changes| Ok(updated_user(id, changes)));
+        mock_user_repo.expect_delete().returning(|_| Ok(()));
+        mock_user_repo
+            .expect_merge()
+            .returning(|_, target_id| Ok(test_user(target_id, UserRole::User)));
+        let user_repo = Arc::new(mock_user_repo);
+        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        mock_identity_link_repo
+            .expect_find_by_user()
+            .returning(|user_id| {
+                let mut identity_links =
+                    vec![test_identity_link(PRIMARY_IDENTITY_ID, user_id, "cognito")];
+                if user_id == SESSION_OWNER_ID {
+                    identity_links.push(test_identity_link(
+                        SECONDARY_IDENTITY_ID,
+                        user_id,
+                        "google",
+                    ));
+                }
+                Ok(identity_links)
+            });
+        mock_identity_link_repo
+            .expect_delete_unless_last()
+            .returning(|user_id, id| {
+                if user_id == SESSION_OWNER_ID {
+                    Ok(())
+                } else if id == PRIMARY_IDENTITY_ID {
+                    Err(RepositoryError::Conflict("last identity link".to_string()))
+                } else {
+                    Err(RepositoryError::NotFound("identity link".to_string()))
+                }
+            });
+        let identity_link_repo = Arc::new(mock_identity_link_repo);
+        let mut mock_permission_repo = MockPermissionRepo::new();
+        mock_permission_repo
+            .expect_find_all_permissions()
+            .returning(|| {
+                Ok(["users:read", "users:delete"]
+                    .into_iter()
+                    .map(|name| Permission {
+                        id: Uuid::new_v4(),
+                        name: name.to_string(),
+                        description: None,
+                    })
+                    .collect())
+            });
+        let permission_repo = Arc::new(mock_permission_repo);
+        let mut mock_api_token_repo = MockApiTokenRepo::new();
+        mock_api_token_repo.expect_create().returning(|token| {
+            Ok(test_api_token(
+                Uuid::new_v4(),
+                token.user_id,
+                &token.name,
+                token.scopes,
+            ))
+        });
+        mock_api_token_repo
+            .expect_find_active_by_user()
+            .returning(|user_id| {
+                Ok(vec![test_api_token(
+                    Uuid::new_v4(),
+                    user_id,
+                    "nightly batch",
+                    vec!["users:read".to_string()],
+                )])
+            });
+        mock_api_token_repo
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(test_api_token(id, SESSION_OWNER_ID, "ci", vec![]))));
+        mock_api_token_repo.expect_revoke().returning(|_| Ok(()));
+        let api_token_repo = Arc::new(mock_api_token_repo);
+        let mut mock_session_repo = MockSessionRepo::new();
+        mock_session_repo
+            .expect_find_active_by_user()
+            .returning(|user_id| {
+                Ok(vec![
+                    test_session(Uuid::new_v4(), user_id, "current-origin-jti"),
+                    test_session(Uuid::new_v4(), user_id, "other-origin-jti"),
+                ])
+            });
+        mock_session_repo
+            .expect_find_by_id()
+            .returning(|id| Ok(Some(test_session(id, SESSION_OWNER_ID, "other-origin-jti"))));
+        mock_session_repo.expect_revoke().returning(|_| Ok(()));
+        let session_repo = Arc::new(mock_session_repo);
+        let revoked_token_repo = Arc::new(InMemoryRevokedTokenRepository::new());
+        let token_denylist = Arc::new(TokenDenylist::new(
+            revoked_token_repo.clone(),
+            chrono::Duration::hours(1),
+        ));
+
+        // 直近にコードを送りすぎたメールアドレス
+        let mut mock_sign_in_code_request_repo = MockSignInCodeRequestRepo::new();
+        mock_sign_in_code_request_repo
+            .expect_count_by_email_since()
+            .returning(|email, _| Ok(if email == RATE_LIMITED_EMAIL { 5 } else { 0 }));
+        mock_sign_in_code_request_repo
+            .expect_create()
+            .returning(|request| {
+                Ok(SignInCodeRequest {
+                    id: Uuid::new_v4(),
+                    email: request.email,
+                    provider_session: request.provider_session,
+                    attempts: 0,
+                    expires_at: request.expires_at,
+                    consumed_at: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                })
+            });
+        // 期限切れで削除された要求
+        mock_sign_in_code_request_repo
+            .expect_start_attempt()
+            .returning(|_, _, _| Ok(None));
+
+        let mut auth_service = MockAuthService::new();
+        auth_service
+            .expect_provider_name()
+            .returning(|| "cognito".to_string());
+        auth_service.expect_request_sign_in_code().returning(|_| {
+            Ok(SignInCodeChallenge {
+                session: Some("custom-auth-session".to_string()),
+                code_delivery_details: CodeDeliveryDetails {
+                    destination: Some("u***@example.com".to_string()),
+                    delivery_medium: Some("EMAIL".to_string()),
+                    attribute_name: Some("email".to_string()),
+                },
+            })
+        });
+
+        let use_cases = init_use_cases(
+            Arc::new(Repositories {
+                user_repository: user_repo.clone(),
+                identity_link_repository: identity_link_repo.clone(),
+                sign_up_compensation_repository: Arc::new(MockCompensationRepo::new()),
+                permission_repository: permission_repo.clone(),
+                session_repository: session_repo.clone(),
+                revoked_token_repository: revoked_token_repo,
+                api_token_repository: api_token_repo.clone(),
+                sign_in_code_request_repository: Arc::new(mock_sign_in_code_request_repo),
+            }),
+            Arc::new(AuthenticationServiceRegistry::new(
+                "https://cognito.example.com",
+                Arc::new(auth_service),
+                unverified_issuer,
+            )),
+            None,
+            GroupMapping::default(),
+            token_denylist.clone(),
+        );
+        let user_service = Arc::new(UserService::new(user_repo.clone()));
+        let user_policy = Arc::new(UserPolicy::new(user_repo.clone()));
+        let api_token_service = Arc::new(ApiTokenService::new(
+            api_token_repo,
+            permission_repo.clone(),
+        ));
+        let role_service = Arc::new(RoleService::new(permission_repo, user_repo));
+        let session_service = Arc::new(SessionService::new(session_repo, token_denylist));
+        let identity_link_service = Arc::new(IdentityLinkService::new(identity_link_repo));
+
+        Schema::build(
+            QueryRoot {
+                user_resolver: UserResolver::new(user_service.clone(), user_policy.clone()),
+                role_resolver: RoleResolver::new(role_service.clone()),
+                session_resolver: SessionResolver::new(session_service.clone()),
+                api_token_resolver: ApiTokenResolver::new(api_token_service.clone()),
+                identity_link_resolver: IdentityLinkResolver::new(identity_link_service.clone()),
+            },
+            MutationRoot {
+                user_mutation: UserMutation::new(user_service, user_policy),
+                authentication_mutation: AuthenticationMutation::new(&use_cases),
+                role_mutation: RoleMutation::new(role_service),
+                session_mutation: SessionMutation::new(session_service),
+                api_token_mutation: ApiTokenMutation::new(api_token_service),
+                identity_link_mutation: IdentityLinkMutation::new(
+                    use_cases.link_identity.clone(),
+                    identity_link_service,
+                ),
+            },
+            EmptySubscription,
+        )
+        .finish()
+    }
+
+    fn context_for(id: Uuid, role: UserRole) -> UserContext {
+        UserContext {
+            user: Some(UserDTO::from(test_user(id, role))),
+            access_token: Some("test-access-token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    // 管理者ではないが、ロールの割り当てによって権限を持つユーザー
+    fn context_with_permission(id: Uuid, permission: &str) -> UserContext {
+        let mut context = context_for(id, UserRole::User);
+        context.permissions.insert(permission.to_string());
+        context
+    }
+
+    async fn execute(query: &str, variables: serde_json::Value, context: UserContext) -> Response {
+        create_test_schema()
+            .execute(
+                Request::new(query)
+                    .variables(Variables::from_json(variables))
+                    .data(context),
+            )
+            .await
+    }
+
+    fn assert_error_code(response: &Response, code: &str) {
+        assert_eq!(response.errors.len(), 1, "Errors: {:?}", response.errors);
+        assert_eq!(
+            response.errors[0]
+                .extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("code"))
+                .cloned(),
+            Some(Value::from(code))
+        );
+    }
+
+    fn assert_ok(response: &Response) {
+        assert!(response.errors.is_empty(), "Errors: {:?}", response.errors);
+    }
+
+    const USERS_QUERY: &str = "query { users { users { id } } }";
+    const USER_QUERY: &str = "query User($id: UUID!) { users { user(id: $id) { id } } }";
+    const USER_STATISTICS_QUERY: &str = "query { users { userStatistics { totalUsers } } }";
+    const CREATE_USER_MUTATION: &str = r#"
+        mutation {
+            users {
+                createUser(input: { name: "New User", email: "new@example.com", password: "password123" }) {
+                    id
+                }
+            }
+        }
+    "#;
+    const UPDATE_USER_MUTATION: &str = r#"
+        mutation UpdateUser($id: UUID!) {
+            users {
+                updateUser(id: $id, input: { name: "Renamed" }) {
+                    name
+                }
+            }
+        }
+    "#;
+    const DELETE_USER_MUTATION: &str =
+        "mutation DeleteUser($id: UUID!) { users { deleteUser(id: $id) } }";
+
+    fn listed_user_ids(response: Response) -> Vec<String> {
+        let data = response.data.into_json().unwrap();
+        data["users"]["users"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| user["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_users_query_filters_unauthorized_rows() {
+        let variables = serde_json::json!({});
+
+        let response = execute(USERS_QUERY, variables.clone(), UserContext::default()).await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+
+        // 一般ユーザーには自分自身だけが返り、クエリ全体は失敗しない
+        let response = execute(
+            USERS_QUERY,
+            variables.clone(),
+            context_for(LISTED_USER_ID, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+        assert_eq!(listed_user_ids(response), vec![LISTED_USER_ID.to_string()]);
+
+        let response = execute(
+            USERS_QUERY,
+            variables.clone(),
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+        assert!(listed_user_ids(response).is_empty());
+
+        let response = execute(
+            USERS_QUERY,
+            variables,
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+        assert_eq!(listed_user_ids(response).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_user_query_is_limited_to_self_or_admin() {
+        let own_id = Uuid::new_v4();
+        let other_id = Uuid::new_v4();
+
+        let response = execute(
+            USER_QUERY,
+            serde_json::json!({ "id": own_id }),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+
+        let response = execute(
+            USER_QUERY,
+            serde_json::json!({ "id": own_id }),
+            context_for(own_id, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["users"]["user"]["id"], own_id.to_string());
+
+        let response = execute(
+            USER_QUERY,
+            serde_json::json!({ "id": other_id }),
+            context_for(own_id, UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(
+            USER_QUERY,
+            serde_json::json!({ "id": other_id }),
+            context_for(own_id, UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+    }
+
+    #[tokio::test]
+    async fn test_user_statistics_query_requires_admin() {
+        let variables = serde_json::json!({});
+
+        let response = execute(
+            USER_STATISTICS_QUERY,
+            variables.clone(),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+
+        let response = execute(
+            USER_STATISTICS_QUERY,
+            variables.clone(),
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(
+            USER_STATISTICS_QUERY,
+            variables,
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+    }
+
+    #[tokio::test]
+    async fn test_create_user_mutation_requires_admin() {
+        let variables = serde_json::json!({});
+
+        let response = execute(
+            CREATE_USER_MUTATION,
+            variables.clone(),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+
+        let response = execute(
+            CREATE_USER_MUTATION,
+            variables.clone(),
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(
+            CREATE_USER_MUTATION,
+            variables,
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+    }
+
+    #[tokio::test]
+    async fn test_update_user_mutation_is_limited_to_self_or_admin() {
+        let own_id = Uuid::new_v4();
+        let other_id = Uuid::new_v4();
+
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": own_id }),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": own_id }),
+            context_for(own_id, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["users"]["updateUser"]["name"], "Renamed");
+
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": other_id }),
+            context_for(own_id, UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": other_id }),
+            context_for(own_id, UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+    }
+
+    #[tokio::test]
+    async fn test_delete_user_mutation_requires_admin() {
+        let own_id = Uuid::new_v4();
+
+        let response = execute(
+            DELETE_USER_MUTATION,
+            serde_json::json!({ "id": own_id }),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+
+        // 本人であっても管理者以外は削除できない
+        let response = execute(
+            DELETE_USER_MUTATION,
+            serde_json::json!({ "id": own_id }),
+            context_for(own_id, UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(
+            DELETE_USER_MUTATION,
+            serde_json::json!({ "id": Uuid::new_v4() }),
+            context_for(own_id, UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["users"]["deleteUser"], true);
+    }
+
+    #[tokio::test]
+    async fn test_users_read_permission_grants_access_to_every_user() {
+        let response = execute(
+            USERS_QUERY,
+            serde_json::json!({}),
+            context_with_permission(Uuid::new_v4(), "users:read"),
+        )
+        .await;
+        assert_ok(&response);
+        assert_eq!(listed_user_ids(response).len(), 2);
+
+        let response = execute(
+            USER_QUERY,
+            serde_json::json!({ "id": Uuid::new_v4() }),
+            context_with_permission(Uuid::new_v4(), "users:read"),
+        )
+        .await;
+        assert_ok(&response);
+
+        let response = execute(
+            USER_STATISTICS_QUERY,
+            serde_json::json!({}),
+            context_with_permission(Uuid::new_v4(), "users:read"),
+        )
+        .await;
+        assert_ok(&response);
+
+        // 参照権限では他のユーザーを更新できない
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": Uuid::new_v4() }),
+            context_with_permission(Uuid::new_v4(), "users:read"),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+    }
+
+    #[tokio::test]
+    async fn test_user_management_permissions_grant_mutations() {
+        let response = execute(
+            CREATE_USER_MUTATION,
+            serde_json::json!({}),
+            context_with_permission(Uuid::new_v4(), "users:create"),
+        )
+        .await;
+        assert_ok(&response);
+
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": Uuid::new_v4() }),
+            context_with_permission(Uuid::new_v4(), "users:update"),
+        )
+        .await;
+        assert_ok(&response);
+
+        let response = execute(
+            DELETE_USER_MUTATION,
+            serde_json::json!({ "id": Uuid::new_v4() }),
+            context_with_permission(Uuid::new_v4(), "users:delete"),
+        )
+        .await;
+        assert_ok(&response);
+    }
+
+    const ME_QUERY: &str = "query { users { me { id groups scopes } } }";
+    const USER_GROUPS_QUERY: &str =
+        "query User($id: UUID!) { users { user(id: $id) { id groups scopes } } }";
+
+    fn context_with_claims(id: Uuid, role: UserRole) -> UserContext {
+        let mut context = context_for(id, role);
+        context.claims = Some(Claims {
+            sub: "test-sub-123".to_string(),
+            groups: vec!["admins".to_string()],
+            scopes: vec!["aws.cognito.signin.user.admin".to_string()],
+            ..Default::default()
+        });
+        context
+    }
+
+    #[tokio::test]
+    async fn test_me_query_exposes_token_groups_and_scopes() {
+        let response = execute(
+            ME_QUERY,
+            serde_json::json!({}),
+            context_with_claims(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["users"]["me"]["groups"], serde_json::json!(["admins"]));
+        assert_eq!(
+            data["users"]["me"]["scopes"],
+            serde_json::json!(["aws.cognito.signin.user.admin"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_groups_are_hidden_on_other_users() {
+        let response = execute(
+            USER_GROUPS_QUERY,
+            serde_json::json!({ "id": Uuid::new_v4() }),
+            context_with_claims(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+
+        let data = response.data.into_json().unwrap();
+        assert!(data["users"]["user"]["groups"].is_null());
+        assert!(data["users"]["user"]["scopes"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_reason_is_reported_on_protected_fields() {
+        let context = UserContext {
+            authentication_failure: Some(AuthenticationFailure::UnknownUser),
+            ..Default::default()
+        };
+
+        let response = execute(ME_QUERY, serde_json::json!({}), context).await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+        assert_eq!(
+            response.errors[0]
+                .extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("reason"))
+                .cloned(),
+            Some(Value::from("unknown_user"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csrf_failure_blocks_mutations_but_not_queries() {
+        let own_id = Uuid::new_v4();
+        let mut context = context_for(own_id, UserRole::User);
+        context.csrf_failed = true;
+
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": own_id }),
+            context.clone(),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(USER_QUERY, serde_json::json!({ "id": own_id }), context).await;
+        assert_ok(&response);
+    }
+
+    const MY_SESSIONS_QUERY: &str =
+        "query { sessions { mySessions { id device ipAddress current } } }";
+    const REVOKE_SESSION_MUTATION: &str =
+        "mutation RevokeSession($id: UUID!) { sessions { revokeSession(id: $id) } }";
+
+    #[tokio::test]
+    async fn test_my_sessions_query_marks_current_session() {
+        let mut context = context_for(Uuid::new_v4(), UserRole::User);
+        context.claims = Some(Claims {
+            sub: "test-sub-123".to_string(),
+            origin_jti: Some("current-origin-jti".to_string()),
+            ..Default::default()
+        });
+
+        let response = execute(MY_SESSIONS_QUERY, serde_json::json!({}), context).await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        let sessions = data["sessions"]["mySessions"].as_array().unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0]["current"], true);
+        assert_eq!(sessions[0]["device"], "Work laptop");
+        assert_eq!(sessions[1]["current"], false);
+
+        let response = execute(
+            MY_SESSIONS_QUERY,
+            serde_json::json!({}),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "UNAUTHENTICATED");
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_mutation_is_limited_to_own_sessions() {
+        let variables = serde_json::json!({ "id": Uuid::new_v4() });
+
+        let response = execute(
+            REVOKE_SESSION_MUTATION,
+            variables.clone(),
+            context_for(SESSION_OWNER_ID, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+
+        // 管理者でも他のユーザーのセッションは見えない
+        let response = execute(
+            REVOKE_SESSION_MUTATION,
+            variables,
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_error_code(&response, "NOT_FOUND");
+    }
+
+    const MY_API_TOKENS_QUERY: &str =
+        "query { apiTokens { myApiTokens { id name tokenPrefix scopes } } }";
+    const CREATE_API_TOKEN_MUTATION: &str = "mutation CreateApiToken($input: CreateApiTokenInput!) { apiTokens { createApiToken(input: $input) { token apiToken { name scopes } } } }";
+    const REVOKE_API_TOKEN_MUTATION: &str =
+        "mutation RevokeApiToken($id: UUID!) { apiTokens { revokeApiToken(id: $id) } }";
+
+    #[tokio::test]
+    async fn test_create_api_token_returns_plaintext_once() {
+        let variables = serde_json::json!({
+            "input": { "name": "nightly batch", "scopes": ["users:read"] }
+        });
+
+        let response = execute(
+            CREATE_API_TOKEN_MUTATION,
+            variables,
+            context_with_permission(Uuid::new_v4(), "users:read"),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        let created = &data["apiTokens"]["createApiToken"];
+        assert!(
+            created["token"]
+                .as_str()
+                .unwrap()
+                .starts_with("morrow_pat_")
+        );
+        assert_eq!(created["apiToken"]["name"], "nightly batch");
+        assert_eq!(
+            created["apiToken"]["scopes"],
+            serde_json::json!(["users:read"])
+        );
+
+        let response = execute(
+            MY_API_TOKENS_QUERY,
+            serde_json::json!({}),
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        let tokens = data["apiTokens"]["myApiTokens"].as_array().unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0]["tokenPrefix"], "morrow_pat_1a2b3c4d");
+        assert!(tokens[0].get("token").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_api_token_is_limited_to_granted_permissions() {
+        // 自分が持っていない権限はスコープにできない
+        let response = execute(
+            CREATE_API_TOKEN_MUTATION,
+            serde_json::json!({
+                "input": { "name": "escalation", "scopes": ["users:delete"] }
+            }),
+            context_with_permission(Uuid::new_v4(), "users:read"),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        // トークンで認証されたリクエストからは、新しいトークンを作れない
+        let mut context = context_with_permission(Uuid::new_v4(), "users:read");
+        context.api_token_id = Some(Uuid::new_v4());
+        let response = execute(
+            CREATE_API_TOKEN_MUTATION,
+            serde_json::json!({
+                "input": { "name": "chained", "scopes": ["users:read"] }
+            }),
+            context,
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        // 存在しない権限は、管理者でも指定できない
+        let response = execute(
+            CREATE_API_TOKEN_MUTATION,
+            serde_json::json!({
+                "input": { "name": "typo", "scopes": ["users:raed"] }
+            }),
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_error_code(&response, "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_revoke_api_token_mutation_is_limited_to_own_tokens() {
+        let variables = serde_json::json!({ "id": Uuid::new_v4() });
+
+        let response = execute(
+            REVOKE_API_TOKEN_MUTATION,
+            variables.clone(),
+            context_for(SESSION_OWNER_ID, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+
+        let response = execute(
+            REVOKE_API_TOKEN_MUTATION,
+            variables,
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_error_code(&response, "NOT_FOUND");
+    }
+
+    const MY_IDENTITIES_QUERY: &str = "query { identities { myIdentities { id provider } } }";
+    const LINK_IDENTITY_MUTATION: &str = "mutation LinkIdentity($provider: String!, $token: String!) { identities { linkIdentity(provider: $provider, token: $token) { id } } }";
+    const UNLINK_IDENTITY_MUTATION: &str =
+        "mutation UnlinkIdentity($id: UUID!) { identities { unlinkIdentity(id: $id) } }";
+    const MERGE_USERS_MUTATION: &str = "mutation MergeUsers($sourceId: UUID!, $targetId: UUID!) { users { mergeUsers(sourceId: $sourceId, targetId: $targetId) { id } } }";
+
+    #[tokio::test]
+    async fn test_unlink_identity_keeps_last_identity() {
+        let response = execute(
+            MY_IDENTITIES_QUERY,
+            serde_json::json!({}),
+            context_for(SESSION_OWNER_ID, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["identities"]["myIdentities"].as_array().unwrap().len(),
+            2
+        );
+
+        let response = execute(
+            UNLINK_IDENTITY_MUTATION,
+            serde_json::json!({ "id": SECONDARY_IDENTITY_ID }),
+            context_for(SESSION_OWNER_ID, UserRole::User),
+        )
+        .await;
+        assert_ok(&response);
+
+        // 紐付いているアカウントが1つだけなら、解除するとサインインできなくなる
+        let response = execute(
+            UNLINK_IDENTITY_MUTATION,
+            serde_json::json!({ "id": PRIMARY_IDENTITY_ID }),
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "CONFLICT");
+
+        // 他のユーザーの紐付けは見えない
+        let response = execute(
+            UNLINK_IDENTITY_MUTATION,
+            serde_json::json!({ "id": SECONDARY_IDENTITY_ID }),
+            context_for(Uuid::new_v4(), UserRole::Admin),
+        )
+        .await;
+        assert_error_code(&response, "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_link_identity_rejects_api_tokens_and_unknown_providers() {
+        let variables = serde_json::json!({ "provider": "github", "token": "github-token" });
+
+        let response = execute(
+            LINK_IDENTITY_MUTATION,
+            variables.clone(),
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "NOT_FOUND");
+
+        let mut context = context_for(Uuid::new_v4(), UserRole::User);
+        context.api_token_id = Some(Uuid::new_v4());
+        let response = execute(LINK_IDENTITY_MUTATION, variables, context).await;
+        assert_error_code(&response, "FORBIDDEN");
+    }
+
+    // パーソナルアクセストークンで認証されたコンテキスト（所有者本人として振る舞う）
+    fn api_token_context(id: Uuid, scopes: &[&str]) -> UserContext {
+        let mut context = context_for(id, UserRole::User);
+        context.api_token_id = Some(Uuid::new_v4());
+        context.permissions = scopes.iter().map(|scope| scope.to_string()).collect();
+        context
+    }
+
+    #[tokio::test]
+    async fn test_api_token_cannot_manage_account_without_scope() {
+        // 読み取りだけのトークンでは、本人のリソースでもアカウントを操作できない
+        for (query, variables) in [
+            (
+                UPDATE_USER_MUTATION,
+                serde_json::json!({ "id": SESSION_OWNER_ID }),
+            ),
+            (
+                REVOKE_SESSION_MUTATION,
+                serde_json::json!({ "id": Uuid::new_v4() }),
+            ),
+            (
+                REVOKE_API_TOKEN_MUTATION,
+                serde_json::json!({ "id": Uuid::new_v4() }),
+            ),
+            (
+                UNLINK_IDENTITY_MUTATION,
+                serde_json::json!({ "id": SECONDARY_IDENTITY_ID }),
+            ),
+            (
+                "mutation { authenticationMutation { signOut { success } } }",
+                serde_json::json!({}),
+            ),
+            (
+                "mutation { authenticationMutation { setMfaPreference(enabled: false) { id } } }",
+                serde_json::json!({}),
+            ),
+        ] {
+            let response = execute(
+                query,
+                variables,
+                api_token_context(SESSION_OWNER_ID, &["users:read"]),
+            )
+            .await;
+            assert_error_code(&response, "FORBIDDEN");
+        }
+
+        // users:updateのスコープを持つトークンは、ユーザーを更新できる
+        let response = execute(
+            UPDATE_USER_MUTATION,
+            serde_json::json!({ "id": SESSION_OWNER_ID }),
+            api_token_context(SESSION_OWNER_ID, &["users:update"]),
+        )
+        .await;
+        assert_ok(&response);
+    }
+
+    #[tokio::test]
+    async fn test_merge_users_requires_admin() {
+        let admin_id = Uuid::new_v4();
+        let target_id = Uuid::new_v4();
+        let variables = serde_json::json!({ "sourceId": Uuid::new_v4(), "targetId": target_id });
+
+        let response = execute(
+            MERGE_USERS_MUTATION,
+            variables.clone(),
+            context_for(admin_id, UserRole::Admin),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["users"]["mergeUsers"]["id"],
+            serde_json::json!(target_id)
+        );
+
+        let response = execute(
+            MERGE_USERS_MUTATION,
+            variables,
+            context_for(Uuid::new_v4(), UserRole::User),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        // 操作中の管理者自身のアカウントは統合元にできない
+        let response = execute(
+            MERGE_USERS_MUTATION,
+            serde_json::json!({ "sourceId": admin_id, "targetId": target_id }),
+            context_for(admin_id, UserRole::Admin),
+        )
+        .await;
+        assert_error_code(&response, "FORBIDDEN");
+
+        let response = execute(
+            MERGE_USERS_MUTATION,
+            serde_json::json!({ "sourceId": target_id, "targetId": target_id }),
+            context_for(admin_id, UserRole::Admin),
+        )
+        .await;
+        assert_error_code(&response, "VALIDATION_FAILED");
+    }
+
+    const RATE_LIMITED_EMAIL: &str = "limited@example.com";
+    const REQUEST_SIGN_IN_CODE_MUTATION: &str = "mutation RequestSignInCode($email: String!) { authenticationMutation { requestSignInCode(input: { email: $email }) { session expiresAt codeDeliveryDetails { destination } } } }";
+    const VERIFY_SIGN_IN_CODE_MUTATION: &str = "mutation VerifySignInCode($session: UUID!) { authenticationMutation { verifySignInCode(input: { session: $session, code: \"123456\" }) { idToken } } }";
+
+    #[tokio::test]
+    async fn test_sign_in_code_mutations_limit_requests_and_expire_sessions() {
+        let response = execute(
+            REQUEST_SIGN_IN_CODE_MUTATION,
+            serde_json::json!({ "email": "user@example.com" }),
+            UserContext::default(),
+        )
+        .await;
+        assert_ok(&response);
+        let data = response.data.into_json().unwrap();
+        let challenge = &data["authenticationMutation"]["requestSignInCode"];
+        // プロバイダーのセッションではなく、このアプリケーションのセッションを返す
+        assert!(Uuid::parse_str(challenge["session"].as_str().unwrap()).is_ok());
+        assert_eq!(
+            challenge["codeDeliveryDetails"]["destination"],
+            "u***@example.com"
+        );
+
+        let response = execute(
+            REQUEST_SIGN_IN_CODE_MUTATION,
+            serde_json::json!({ "email": RATE_LIMITED_EMAIL }),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "TOO_MANY_REQUESTS");
+
+        let response = execute(
+            VERIFY_SIGN_IN_CODE_MUTATION,
+            serde_json::json!({ "session": Uuid::new_v4() }),
+            UserContext::default(),
+        )
+        .await;
+        assert_error_code(&response, "EXPIRED_CODE");
+    }
 }
@@ -1,20 +1,27 @@
 #[cfg(test)]
 mod tests {
-    use anyhow::Result;
     use async_trait::async_trait;
     use backend::application::usecases::sign_up::SignUp;
     use backend::domain::{
         entities::{
-            identity_link::{IdentityLink, NewIdentityLink},
-            user::{NewUser, User},
+            identity_link::IdentityLink,
+            sign_up_compensation::{NewSignUpCompensation, SignUpCompensation},
+            user::{NewUser, User, UserChanges},
         },
+        enums::user_role::UserRole,
+        errors::{AuthenticationError, AuthenticationResult, RepositoryResult},
         repositories::{
-            identity_link_repository::IdentityLinkRepository, user_repository::UserRepository,
+            sign_up_compensation_repository::SignUpCompensationRepository,
+            user_repository::UserRepository,
         },
         services::authentication_service::AuthenticationService,
-        value_objects::authentication::{Claims, SignInOutput, SignUpOutput},
+        value_objects::authentication::{
+            AuthenticationTokens, Claims, CodeDeliveryDetails, SignInCodeChallenge,
+            SignInCodeVerification, SignInOutput, SignUpOutput, SoftwareTokenAssociation,
+        },
     };
     use mockall::mock;
+    use std::collections::HashMap;
     use std::sync::Arc;
     use uuid::Uuid;
 
@@ -25,10 +32,24 @@ mod tests {
         #[async_trait]
         impl AuthenticationService for AuthService {
             fn provider_name(&self) -> String;
-            async fn sign_up(&self, email: &str, password: &str) -> Result<SignUpOutput>;
-            async fn sign_in(&self, email: &str, password: &str) -> Result<SignInOutput>;
-            async fn sign_out(&self, access_token: &str) -> Result<()>;
-            async fn verify_token(&self, access_token: &str) -> Result<Claims>;
+            async fn sign_up(&self, email: &str, password: &str) -> AuthenticationResult<SignUpOutput>;
+            async fn confirm_sign_up(&self, email: &str, confirmation_code: &str) -> AuthenticationResult<()>;
+            async fn resend_confirmation_code(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+            async fn sign_in(&self, email: &str, password: &str) -> AuthenticationResult<SignInOutput>;
+            async fn respond_to_auth_challenge(&self, email: &str, challenge_name: &str, session: Option<String>, responses: &HashMap<String, String>) -> AuthenticationResult<SignInOutput>;
+            async fn request_sign_in_code(&self, email: &str) -> AuthenticationResult<SignInCodeChallenge>;
+            async fn verify_sign_in_code(&self, email: &str, session: Option<String>, code: &str) -> AuthenticationResult<SignInCodeVerification>;
+            async fn refresh_tokens(&self, refresh_token: &str) -> AuthenticationResult<AuthenticationTokens>;
+            async fn forgot_password(&self, email: &str) -> AuthenticationResult<CodeDeliveryDetails>;
+            async fn confirm_forgot_password(&self, email: &str, confirmation_code: &str, new_password: &str) -> AuthenticationResult<()>;
+            async fn change_password(&self, access_token: &str, old_password: &str, new_password: &str) -> AuthenticationResult<()>;
+            async fn associate_software_token(&self, access_token: &str) -> AuthenticationResult<SoftwareTokenAssociation>;
+            async fn verify_software_token(&self, access_token: &str, user_code: &str, friendly_device_name: Option<String>) -> AuthenticationResult<()>;
+            async fn set_mfa_preference(&self, access_token: &str, enabled: bool) -> AuthenticationResult<()>;
+            async fn sign_out(&self, access_token: &str) -> AuthenticationResult<()>;
+            async fn revoke_token(&self, refresh_token: &str) -> AuthenticationResult<()>;
+            async fn delete_user(&self, username: &str) -> AuthenticationResult<()>;
+            async fn verify_token(&self, access_token: &str) -> AuthenticationResult<Claims>;
         }
     }
 
@@ -37,21 +58,25 @@ mod tests {
 
         #[async_trait]
         impl UserRepository for UserRepo {
-            async fn create(&self, user: NewUser) -> Result<User>;
-            async fn find_by_id(&self, id: Uuid) -> Result<Option<User>>;
-            async fn find_all(&self) -> Result<Vec<User>>;
-            async fn update(&self, user: User) -> Result<User>;
-            async fn delete(&self, id: Uuid) -> Result<()>;
+            async fn create(&self, user: NewUser) -> RepositoryResult<User>;
+            async fn create_with_identity_link(&self, user: NewUser, provider: String, sub: String) -> RepositoryResult<(User, IdentityLink)>;
+            async fn find_by_id(&self, id: Uuid) -> RepositoryResult<Option<User>>;
+            async fn find_all(&self) -> RepositoryResult<Vec<User>>;
+            async fn update(&self, id: Uuid, changes: UserChanges) -> RepositoryResult<User>;
+            async fn delete(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn merge(&self, source_id: Uuid, target_id: Uuid) -> RepositoryResult<User>;
         }
     }
 
     mock! {
-        IdentityLinkRepo {}
+        CompensationRepo {}
 
         #[async_trait]
-        impl IdentityLinkRepository for IdentityLinkRepo {
-            async fn create(&self, new_identity_link: NewIdentityLink) -> Result<IdentityLink>;
-            async fn find_by_sub(&self, sub: &str) -> Result<IdentityLink>;
+        impl SignUpCompensationRepository for CompensationRepo {
+            async fn create(&self, compensation: NewSignUpCompensation) -> RepositoryResult<SignUpCompensation>;
+            async fn find_unresolved(&self) -> RepositoryResult<Vec<SignUpCompensation>>;
+            async fn mark_resolved(&self, id: Uuid) -> RepositoryResult<()>;
+            async fn record_failure(&self, id: Uuid, error: &str) -> RepositoryResult<()>;
         }
     }
 
@@ -59,6 +84,8 @@ mod tests {
         User {
             id: Uuid::new_v4(),
             name: "Test User".to_string(),
+            role: UserRole::User,
+            mfa_enabled: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             identity_links: Vec::new(),
@@ -81,7 +108,7 @@ mod tests {
         // Arrange
         let mut mock_auth_service = MockAuthService::new();
         let mut mock_user_repo = MockUserRepo::new();
-        let mut mock_identity_link_repo = MockIdentityLinkRepo::new();
+        let mock_compensation_repo = MockCompensationRepo::new();
 
         let test_user = create_test_user();
         let test_identity_link = create_test_identity_link(test_user.id);
@@ -108,22 +135,17 @@ mod tests {
             .returning(|| "cognito".to_string());
 
         let user_clone = test_user.clone();
-        mock_user_repo
-            .expect_create()
-            .times(1)
-            .returning(move |_| Ok(user_clone.clone()));
-
         let identity_link_clone = test_identity_link.clone();
-        mock_identity_link_repo
-            .expect_create()
+        mock_user_repo
+            .expect_create_with_identity_link()
             .times(1)
-            .returning(move |_| Ok(identity_link_clone.clone()));
+            .returning(move |_, _, _| Ok((user_clone.clone(), identity_link_clone.clone())));
 
         // Create use cases
         let sign_up = SignUp::new(
             Arc::new(mock_auth_service),
             Arc::new(mock_user_repo),
-            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_compensation_repo),
         );
 
         let input = backend::application::dtos::authentication_dto::SignUpInputDTO {
@@ -137,10 +159,10 @@ mod tests {
 
         // Assert
         assert!(result.is_ok());
-        let identity_link_dto = result.unwrap();
-        assert_eq!(identity_link_dto.provider, "cognito");
-        assert_eq!(identity_link_dto.sub, "test-sub-123");
-        assert_eq!(identity_link_dto.user_id, test_user.id);
+        let output_dto = result.unwrap();
+        assert_eq!(output_dto.identity_link.provider, "cognito");
+        assert_eq!(output_dto.identity_link.sub, "test-sub-123");
+        assert_eq!(output_dto.identity_link.user_id, test_user.id);
     }
 
     #[tokio::test]
@@ -148,7 +170,7 @@ mod tests {
         // Arrange
         let mut mock_auth_service = MockAuthService::new();
         let mock_user_repo = MockUserRepo::new();
-        let mock_identity_link_repo = MockIdentityLinkRepo::new();
+        let mock_compensation_repo = MockCompensationRepo::new();
 
         // Setup mock expectations for failure
         mock_auth_service
@@ -158,13 +180,17 @@ mod tests {
                 mockall::predicate::eq("invalid_password"),
             )
             .times(1)
-            .returning(|_, _| Err(anyhow::anyhow!("Invalid password")));
+            .returning(|_, _| {
+                Err(AuthenticationError::InvalidParameter(
+                    "Invalid password".to_string(),
+                ))
+            });
 
         // Create use cases
         let sign_up = SignUp::new(
             Arc::new(mock_auth_service),
             Arc::new(mock_user_repo),
-            Arc::new(mock_identity_link_repo),
+            Arc::new(mock_compensation_repo),
         );
 
         let input = backend::application::dtos::authentication_dto::SignUpInputDTO {
@@ -24,6 +24,12 @@ terraform {
   # source = "${include.envcommon.locals.base_source_url}?ref=v0.8.0"
   # source = "../../../modules/${basename(get_terragrunt_dir())}"
   source = find_in_parent_folders("modules/${basename(get_terragrunt_dir())}")
+
+  # Package the Cognito Lambda triggers before Terraform reads them
+  before_hook "build_lambda" {
+    commands = ["plan", "apply"]
+    execute  = ["sh", "lambda/build.sh"]
+  }
 }
 
 # ---------------------------------------------------------------------------------------------------------------------
@@ -38,4 +44,10 @@ inputs = {
       name = "morrow-dev"
     }
   }
+  sign_in_code_email_from = "no-reply@example.com"
+  cognito_hosted_ui = {
+    domain        = "morrow-dev"
+    callback_urls = ["http://localhost:3000/auth/callback"]
+    logout_urls   = ["http://localhost:3000/"]
+  }
 }
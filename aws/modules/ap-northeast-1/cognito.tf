@@ -25,6 +25,19 @@ resource "aws_cognito_user_pool" "morrow" {
   #   mutable             = true
   #   required            = true
   # }
+
+  # Sign in with an emailed code (CUSTOM_AUTH)
+  lambda_config {
+    define_auth_challenge          = aws_lambda_function.auth_challenge["define_auth_challenge"].arn
+    create_auth_challenge          = aws_lambda_function.auth_challenge["create_auth_challenge"].arn
+    verify_auth_challenge_response = aws_lambda_function.auth_challenge["verify_auth_challenge_response"].arn
+  }
+}
+
+# Hosted UI (<domain>.auth.<region>.amazoncognito.com), used by /auth/authorize and /auth/callback
+resource "aws_cognito_user_pool_domain" "morrow" {
+  domain       = var.cognito_hosted_ui.domain
+  user_pool_id = aws_cognito_user_pool.morrow.id
 }
 
 resource "aws_cognito_user_pool_client" "morrow_api" {
@@ -33,8 +46,21 @@ resource "aws_cognito_user_pool_client" "morrow_api" {
   # generate_secret              = true
   # refresh_token_validity       = 30
   # prevent_user_existence_errors = "ENABLED"
+  # The legacy flow names cannot be mixed with the ALLOW_ ones, so all flows use the ALLOW_ form
   explicit_auth_flows = [
-    "ADMIN_NO_SRP_AUTH",
-    "USER_PASSWORD_AUTH",
+    "ALLOW_ADMIN_USER_PASSWORD_AUTH",
+    "ALLOW_USER_PASSWORD_AUTH",
+    "ALLOW_CUSTOM_AUTH",
+    "ALLOW_REFRESH_TOKEN_AUTH",
   ]
+
+  # Authorization code flow with PKCE through the Hosted UI
+  # The scopes match the backend's default AUTH_OAUTH_SCOPES
+  # Add social identity providers (e.g. "Google") here once they are configured on the user pool
+  allowed_oauth_flows_user_pool_client = true
+  allowed_oauth_flows                  = ["code"]
+  allowed_oauth_scopes                 = ["openid", "email", "profile"]
+  callback_urls                        = var.cognito_hosted_ui.callback_urls
+  logout_urls                          = var.cognito_hosted_ui.logout_urls
+  supported_identity_providers         = ["COGNITO"]
 }
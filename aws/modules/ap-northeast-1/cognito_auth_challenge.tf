@@ -0,0 +1,75 @@
+# Lambda triggers for signing in with an emailed code (CUSTOM_AUTH)
+# The packages are built into .build/ by lambda/build.sh (run by a Terragrunt before_hook)
+locals {
+  auth_challenge_triggers = toset([
+    "define_auth_challenge",
+    "create_auth_challenge",
+    "verify_auth_challenge_response",
+  ])
+}
+
+data "aws_iam_policy_document" "auth_challenge_assume_role" {
+  statement {
+    actions = ["sts:AssumeRole"]
+    principals {
+      type        = "Service"
+      identifiers = ["lambda.amazonaws.com"]
+    }
+  }
+}
+
+resource "aws_iam_role" "auth_challenge" {
+  name               = "${var.aws_cognito_user_pool.morrow.name}-auth-challenge"
+  assume_role_policy = data.aws_iam_policy_document.auth_challenge_assume_role.json
+}
+
+resource "aws_iam_role_policy_attachment" "auth_challenge_logs" {
+  role       = aws_iam_role.auth_challenge.name
+  policy_arn = "arn:aws:iam::aws:policy/service-role/AWSLambdaBasicExecutionRole"
+}
+
+# Only the Create Auth Challenge trigger sends email, but the triggers share one role
+data "aws_iam_policy_document" "auth_challenge_send_email" {
+  statement {
+    actions   = ["ses:SendEmail"]
+    resources = ["*"]
+    condition {
+      test     = "StringEquals"
+      variable = "ses:FromAddress"
+      values   = [var.sign_in_code_email_from]
+    }
+  }
+}
+
+resource "aws_iam_role_policy" "auth_challenge_send_email" {
+  name   = "send-sign-in-code"
+  role   = aws_iam_role.auth_challenge.id
+  policy = data.aws_iam_policy_document.auth_challenge_send_email.json
+}
+
+resource "aws_lambda_function" "auth_challenge" {
+  for_each = local.auth_challenge_triggers
+
+  function_name    = "${var.aws_cognito_user_pool.morrow.name}-${replace(each.key, "_", "-")}"
+  role             = aws_iam_role.auth_challenge.arn
+  runtime          = "nodejs20.x"
+  handler          = "index.handler"
+  filename         = "${path.module}/.build/${each.key}.zip"
+  source_code_hash = filebase64sha256("${path.module}/.build/${each.key}.zip")
+
+  environment {
+    variables = {
+      SIGN_IN_CODE_EMAIL_FROM = var.sign_in_code_email_from
+    }
+  }
+}
+
+resource "aws_lambda_permission" "auth_challenge" {
+  for_each = local.auth_challenge_triggers
+
+  statement_id  = "AllowCognitoInvoke"
+  action        = "lambda:InvokeFunction"
+  function_name = aws_lambda_function.auth_challenge[each.key].function_name
+  principal     = "cognito-idp.amazonaws.com"
+  source_arn    = aws_cognito_user_pool.morrow.arn
+}
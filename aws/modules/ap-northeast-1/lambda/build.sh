@@ -0,0 +1,10 @@
+#!/bin/sh
+# Packages each Cognito trigger into .build/<trigger>.zip for cognito_auth_challenge.tf
+set -eu
+
+cd "$(dirname "$0")"
+mkdir -p ../.build
+for trigger in define_auth_challenge create_auth_challenge verify_auth_challenge_response; do
+  rm -f "../.build/${trigger}.zip"
+  (cd "${trigger}" && zip -q -X -r "../../.build/${trigger}.zip" .)
+done
@@ -0,0 +1,45 @@
+import { randomInt } from "node:crypto";
+import { SESClient, SendEmailCommand } from "@aws-sdk/client-ses";
+
+// Generates the sign in code and emails it to the user.
+// Retries after a wrong answer keep the code that was already sent.
+const ses = new SESClient({});
+
+const maskEmail = (email) => {
+  const [local, domain] = email.split("@");
+  return `${local.charAt(0)}***@${domain}`;
+};
+
+export const handler = async (event) => {
+  const email = event.request.userAttributes.email;
+  const previous = (event.request.session ?? [])
+    .map((challenge) => challenge.challengeMetadata ?? "")
+    .filter((metadata) => metadata.startsWith("CODE-"))
+    .at(-1);
+
+  let code;
+  if (previous) {
+    code = previous.slice("CODE-".length);
+  } else {
+    code = randomInt(0, 1_000_000).toString().padStart(6, "0");
+    await ses.send(
+      new SendEmailCommand({
+        Source: process.env.SIGN_IN_CODE_EMAIL_FROM,
+        Destination: { ToAddresses: [email] },
+        Message: {
+          Subject: { Data: "Your sign in code" },
+          Body: {
+            Text: {
+              Data: `Your sign in code is ${code}. It expires in 3 minutes.`,
+            },
+          },
+        },
+      }),
+    );
+  }
+
+  event.response.publicChallengeParameters = { destination: maskEmail(email) };
+  event.response.privateChallengeParameters = { code };
+  event.response.challengeMetadata = `CODE-${code}`;
+  return event;
+};
@@ -0,0 +1,28 @@
+// Decides the next step of the CUSTOM_AUTH flow used for email sign in codes.
+// A wrong answer issues the challenge again so the backend can accept a retry with the
+// reissued session. The backend allows 3 wrong answers per code, and so does this trigger.
+const MAX_WRONG_ANSWERS = 3;
+
+export const handler = async (event) => {
+  const session = event.request.session ?? [];
+  const customOnly = session.every(
+    (challenge) => challenge.challengeName === "CUSTOM_CHALLENGE",
+  );
+  const last = session.at(-1);
+
+  if (event.request.userNotFound || !customOnly) {
+    event.response.issueTokens = false;
+    event.response.failAuthentication = true;
+  } else if (last?.challengeResult === true) {
+    event.response.issueTokens = true;
+    event.response.failAuthentication = false;
+  } else if (session.length >= MAX_WRONG_ANSWERS) {
+    event.response.issueTokens = false;
+    event.response.failAuthentication = true;
+  } else {
+    event.response.issueTokens = false;
+    event.response.failAuthentication = false;
+    event.response.challengeName = "CUSTOM_CHALLENGE";
+  }
+  return event;
+};
@@ -0,0 +1,13 @@
+import { timingSafeEqual } from "node:crypto";
+
+// Checks the answer against the code generated by the Create Auth Challenge trigger.
+export const handler = async (event) => {
+  const expected = Buffer.from(event.request.privateChallengeParameters.code ?? "");
+  const answer = Buffer.from(event.request.challengeAnswer ?? "");
+
+  event.response.answerCorrect =
+    expected.length > 0 &&
+    expected.length === answer.length &&
+    timingSafeEqual(expected, answer);
+  return event;
+};
@@ -1,3 +1,18 @@
 variable "aws_cognito_user_pool" {
   type        = map(any)
 }
+
+# Sender of the sign in code emails. Must be a verified SES identity
+variable "sign_in_code_email_from" {
+  type = string
+}
+
+# Hosted UI domain prefix and the redirect URLs of the app client
+# callback_urls must include the backend's AUTH_OAUTH_REDIRECT_URI (<api>/auth/callback)
+variable "cognito_hosted_ui" {
+  type = object({
+    domain        = string
+    callback_urls = list(string)
+    logout_urls   = list(string)
+  })
+}